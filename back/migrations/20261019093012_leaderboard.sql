@@ -0,0 +1,14 @@
+CREATE TABLE snapshots (
+  id         INTEGER NOT NULL PRIMARY KEY,
+  day        INTEGER NOT NULL,
+  min_games  INTEGER NOT NULL,
+  player     INTEGER NOT NULL,
+  rank       INTEGER,
+  rating     REAL    NOT NULL,
+  games      INTEGER NOT NULL,
+  created_ms INTEGER NOT NULL
+    DEFAULT (strftime('%s', 'now') || substr(strftime('%f', 'now'), 4)),
+
+  UNIQUE(day, player),
+  FOREIGN KEY(player) REFERENCES players(id) ON DELETE CASCADE
+);
@@ -0,0 +1 @@
+ALTER TABLE players ADD COLUMN admin BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,30 @@
+CREATE TABLE seasons (
+  id         INTEGER NOT NULL PRIMARY KEY,
+  name       TEXT    NOT NULL UNIQUE
+    CHECK(LENGTH(TRIM(name)) > 0 AND LENGTH(name) <= 32),
+  start_ms   INTEGER NOT NULL,
+  end_ms     INTEGER NOT NULL
+    CHECK(end_ms > start_ms),
+  reset      REAL    NOT NULL
+    CHECK(reset >= 0 AND reset <= 1),
+  created_ms INTEGER NOT NULL
+    DEFAULT (strftime('%s', 'now') || substr(strftime('%f', 'now'), 4))
+);
+
+CREATE INDEX seasons_start_ms_idx ON seasons(start_ms);
+
+CREATE TABLE standings (
+  id         INTEGER NOT NULL PRIMARY KEY,
+  season     INTEGER NOT NULL,
+  min_games  INTEGER NOT NULL,
+  player     INTEGER NOT NULL,
+  rank       INTEGER,
+  rating     REAL    NOT NULL,
+  games      INTEGER NOT NULL,
+  created_ms INTEGER NOT NULL
+    DEFAULT (strftime('%s', 'now') || substr(strftime('%f', 'now'), 4)),
+
+  UNIQUE(season, player),
+  FOREIGN KEY(season) REFERENCES seasons(id) ON DELETE CASCADE,
+  FOREIGN KEY(player) REFERENCES players(id) ON DELETE CASCADE
+);
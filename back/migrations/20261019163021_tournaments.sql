@@ -0,0 +1,46 @@
+CREATE TABLE tournaments (
+  id         INTEGER NOT NULL PRIMARY KEY,
+  name       TEXT    NOT NULL UNIQUE
+    CHECK(LENGTH(TRIM(name)) > 0 AND LENGTH(name) <= 32),
+  format     TEXT    NOT NULL
+    CHECK(format IN ('roundRobin', 'singleElimination', 'groupKnockout')),
+  groups     INTEGER NOT NULL
+    CHECK(groups > 0),
+  advancing  INTEGER NOT NULL
+    CHECK(advancing > 0),
+  created_ms INTEGER NOT NULL
+    DEFAULT (strftime('%s', 'now') || substr(strftime('%f', 'now'), 4))
+);
+
+CREATE TABLE participants (
+  id         INTEGER NOT NULL PRIMARY KEY,
+  tournament INTEGER NOT NULL,
+  player     INTEGER NOT NULL,
+  seed       INTEGER NOT NULL,
+
+  UNIQUE(tournament, player),
+  UNIQUE(tournament, seed),
+  FOREIGN KEY(tournament) REFERENCES tournaments(id) ON DELETE CASCADE,
+  FOREIGN KEY(player) REFERENCES players(id)
+);
+
+CREATE TABLE fixtures (
+  id          INTEGER NOT NULL PRIMARY KEY,
+  tournament  INTEGER NOT NULL,
+  group_index INTEGER,
+  round       INTEGER NOT NULL,
+  position    INTEGER NOT NULL,
+  player_one  INTEGER,
+  player_two  INTEGER,
+  game        INTEGER UNIQUE,
+  winner      INTEGER,
+
+  UNIQUE(tournament, group_index, round, position),
+  FOREIGN KEY(tournament) REFERENCES tournaments(id) ON DELETE CASCADE,
+  FOREIGN KEY(player_one) REFERENCES players(id),
+  FOREIGN KEY(player_two) REFERENCES players(id),
+  FOREIGN KEY(game) REFERENCES games(id),
+  FOREIGN KEY(winner) REFERENCES players(id)
+);
+
+CREATE INDEX fixtures_tournament_idx ON fixtures(tournament);
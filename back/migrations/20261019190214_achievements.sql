@@ -0,0 +1,13 @@
+CREATE TABLE achievements (
+  id         INTEGER NOT NULL PRIMARY KEY,
+  player     INTEGER NOT NULL,
+  kind       TEXT    NOT NULL
+    CHECK(kind IN ('firstWin', 'winStreak', 'beatTheBest', 'shutout', 'centurion', 'tieBreak')),
+  game       INTEGER NOT NULL,
+  created_ms INTEGER NOT NULL
+    DEFAULT (strftime('%s', 'now') || substr(strftime('%f', 'now'), 4)),
+
+  UNIQUE(player, kind),
+  FOREIGN KEY(player) REFERENCES players(id) ON DELETE CASCADE,
+  FOREIGN KEY(game) REFERENCES games(id) ON DELETE CASCADE
+);
@@ -0,0 +1,10 @@
+CREATE TABLE streaks (
+  player       INTEGER NOT NULL PRIMARY KEY,
+  current      INTEGER NOT NULL,
+  longest_win  INTEGER NOT NULL
+    CHECK(longest_win >= 0),
+  longest_loss INTEGER NOT NULL
+    CHECK(longest_loss >= 0),
+
+  FOREIGN KEY(player) REFERENCES players(id) ON DELETE CASCADE
+);
@@ -0,0 +1,19 @@
+CREATE TABLE audit (
+  id         INTEGER NOT NULL PRIMARY KEY,
+  -- Null when the actor is not a player, such as an invitee rejecting an invite
+  actor      INTEGER,
+  -- Actions and entities are validated by `types::AuditAction` and `types::Entity`, so that new
+  -- ones do not require rebuilding the table
+  action     TEXT    NOT NULL,
+  entity     TEXT    NOT NULL,
+  entity_id  INTEGER NOT NULL,
+  before     TEXT,
+  after      TEXT,
+  created_ms INTEGER NOT NULL
+    DEFAULT (strftime('%s', 'now') || substr(strftime('%f', 'now'), 4)),
+
+  FOREIGN KEY(actor) REFERENCES players(id) ON DELETE SET NULL
+);
+
+CREATE INDEX audit_actor_idx ON audit(actor);
+CREATE INDEX audit_entity_idx ON audit(entity, entity_id);
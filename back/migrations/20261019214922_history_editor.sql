@@ -0,0 +1,48 @@
+ALTER TABLE games ADD COLUMN editor INTEGER REFERENCES players(id) ON DELETE SET NULL;
+ALTER TABLE games ADD COLUMN reason TEXT;
+
+ALTER TABLE history ADD COLUMN editor INTEGER REFERENCES players(id) ON DELETE SET NULL;
+ALTER TABLE history ADD COLUMN reason TEXT;
+
+-- The editor and reason of the change are carried by the new version of the game
+DROP TRIGGER games_before_update;
+
+CREATE TRIGGER games_before_update
+  AFTER UPDATE ON games
+  FOR EACH ROW
+  WHEN
+    NEW.id = OLD.id
+    AND (
+      NEW.player_one <> OLD.player_one
+      OR NEW.player_two <> OLD.player_two
+      OR NEW.score_one <> OLD.score_one
+      OR NEW.score_two <> OLD.score_two
+      OR NEW.challenge <> OLD.challenge
+      OR NEW.deleted <> OLD.deleted
+      OR NEW.millis <> OLD.millis
+    )
+BEGIN
+  INSERT INTO history (
+    game,
+    player_one,
+    player_two,
+    score_one,
+    score_two,
+    challenge,
+    deleted,
+    millis,
+    editor,
+    reason
+  ) VALUES (
+    OLD.id,
+    OLD.player_one,
+    OLD.player_two,
+    OLD.score_one,
+    OLD.score_two,
+    OLD.challenge,
+    OLD.deleted,
+    OLD.millis,
+    NEW.editor,
+    NEW.reason
+  );
+END;
@@ -0,0 +1 @@
+ALTER TABLE games ADD COLUMN registrar INTEGER REFERENCES players(id) ON DELETE SET NULL;
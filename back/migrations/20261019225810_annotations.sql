@@ -0,0 +1,6 @@
+-- Must match `MAX_COMMENT` in the games store
+ALTER TABLE games ADD COLUMN comment TEXT CHECK(LENGTH(comment) <= 256);
+ALTER TABLE games ADD COLUMN venue TEXT;
+ALTER TABLE games ADD COLUMN first_server INTEGER REFERENCES players(id);
+
+CREATE INDEX games_venue_idx ON games(venue);
@@ -0,0 +1,4 @@
+CREATE TABLE points (
+  game INTEGER NOT NULL PRIMARY KEY REFERENCES games(id) ON DELETE CASCADE,
+  log TEXT NOT NULL CHECK(LENGTH(log) > 0 AND log NOT GLOB '*[^12ab]*')
+);
@@ -0,0 +1,16 @@
+CREATE TABLE live_games (
+  scorer     INTEGER NOT NULL PRIMARY KEY,
+  player_one INTEGER NOT NULL
+    CHECK(player_one <> player_two),
+  player_two INTEGER NOT NULL
+    CHECK(player_one <> player_two),
+  -- Only who won each point is tracked live, so there are no serves in the log
+  log        TEXT    NOT NULL DEFAULT ''
+    CHECK(log NOT GLOB '*[^12]*'),
+  started_ms INTEGER NOT NULL
+    DEFAULT (strftime('%s', 'now') || substr(strftime('%f', 'now'), 4)),
+
+  FOREIGN KEY(scorer) REFERENCES players(id) ON DELETE CASCADE,
+  FOREIGN KEY(player_one) REFERENCES players(id) ON DELETE CASCADE,
+  FOREIGN KEY(player_two) REFERENCES players(id) ON DELETE CASCADE
+);
@@ -14,6 +14,32 @@ pub struct Args {
     #[cfg(feature = "local")]
     pub init: Option<u16>,
     pub smtp: Option<Smtp>,
+    pub venues: Vec<String>,
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, clap::Subcommand)]
+pub enum Command {
+    /// Replay all games with alternative rating parameters and compare the leaderboards
+    Simulate {
+        /// K-factor of the Elo rating updates
+        #[arg(short, long)]
+        k_factor: Option<f64>,
+
+        /// Multiplier applied to the rating changes of challenges
+        #[arg(short, long)]
+        challenge_multiplier: Option<f64>,
+    },
+
+    /// Grant a player access to seasons, tournaments, the audit log and simulations
+    Admin {
+        /// Email of the player
+        email: String,
+
+        /// Revoke the access instead
+        #[arg(short, long)]
+        revoke: bool,
+    },
 }
 
 #[derive(Debug, Copy, Clone)]
@@ -48,6 +74,15 @@ struct Inner {
 
     #[command(flatten)]
     smtp: SmtpInner,
+
+    /// Venue or table where games can be played
+    ///
+    /// Can be repeated or given as a comma separated list
+    #[arg(long = "venue", value_delimiter = ',')]
+    venues: Vec<String>,
+
+    #[command(subcommand)]
+    command: Option<Command>,
 }
 
 #[derive(Debug)]
@@ -98,6 +133,8 @@ impl From<Inner> for Args {
             ),
             init: value.init,
             smtp,
+            venues: value.venues,
+            command: value.command,
         }
     }
 }
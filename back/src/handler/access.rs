@@ -1,4 +1,4 @@
-use crate::{mailbox, server, store, types};
+use crate::{server, store, types};
 
 #[derive(Debug, Clone)]
 pub struct User<A>
@@ -6,7 +6,6 @@ where
     A: Access,
 {
     id: types::Id,
-    name: String,
     email: String,
     _access: std::marker::PhantomData<A>,
 }
@@ -19,24 +18,9 @@ where
         self.id
     }
 
-    pub fn name(&self) -> &String {
-        &self.name
-    }
-
     pub fn email(&self) -> &String {
         &self.email
     }
-
-    pub fn update_name(&mut self, name: String) {
-        self.name = name;
-    }
-
-    pub fn make_proto(&self) -> mailbox::Proto {
-        mailbox::Proto {
-            name: self.name.clone(),
-            email: self.email.clone(),
-        }
-    }
 }
 
 #[derive(Debug, Clone)]
@@ -80,7 +64,6 @@ impl server::auth::Provider for Auth {
         if let Some(user) = self.store.players().auth(user).await? {
             return Ok(Some(Dynamic::Regular(User {
                 id: user.id,
-                name: user.name,
                 email: user.email,
                 _access: std::marker::PhantomData,
             })));
@@ -89,7 +72,6 @@ impl server::auth::Provider for Auth {
         if let Ok(Some(user)) = self.store.invites().auth(user).await {
             return Ok(Some(Dynamic::Pending(User {
                 id: user.id,
-                name: user.name,
                 email: user.email,
                 _access: std::marker::PhantomData,
             })));
@@ -1,25 +1,144 @@
 use super::model;
+use crate::ws;
+
+// Slow sockets lag behind the channel, so the most recent pushes are also kept to be replayed
+const CHANNEL_CAPACITY: usize = 16;
+const REPLAY_CAPACITY: usize = 256;
 
 // TODO: Move out of `handler`
 #[derive(Debug, Clone)]
 pub struct Broadcaster {
-    sender: tokio::sync::broadcast::Sender<model::Push>,
+    sender: tokio::sync::broadcast::Sender<(ws::Seq, model::Push)>,
+    replay: std::sync::Arc<std::sync::Mutex<Replay>>,
+}
+
+#[derive(Debug, Default)]
+struct Replay {
+    seq: ws::Seq,
+    buffer: std::collections::VecDeque<(ws::Seq, model::Push)>,
 }
 
 impl Broadcaster {
     pub fn new() -> Self {
-        let (sender, _) = tokio::sync::broadcast::channel(16);
-        Self { sender }
+        let (sender, _) = tokio::sync::broadcast::channel(CHANNEL_CAPACITY);
+        Self {
+            sender,
+            replay: std::sync::Arc::default(),
+        }
     }
 
-    pub fn subscribe(&self) -> tokio::sync::broadcast::Receiver<model::Push> {
-        self.sender.subscribe()
+    // Returns the sequence of the last push sent before subscribing
+    pub fn subscribe(
+        &self,
+    ) -> (
+        ws::Seq,
+        tokio::sync::broadcast::Receiver<(ws::Seq, model::Push)>,
+    ) {
+        let replay = self.lock();
+        (replay.seq, self.sender.subscribe())
     }
 
     pub fn send(&self, payload: model::Push) {
         let message = payload.to_string();
-        if let Ok(count) = self.sender.send(payload) {
-            tracing::info!(listeners = %count, "Push {message}");
+
+        // Kept locked while sending so that the channel and the buffer agree on the order
+        let mut replay = self.lock();
+        replay.seq += 1;
+        let seq = replay.seq;
+
+        if replay.buffer.len() == REPLAY_CAPACITY {
+            replay.buffer.pop_front();
+        }
+        replay.buffer.push_back((seq, payload.clone()));
+
+        if let Ok(count) = self.sender.send((seq, payload)) {
+            tracing::info!(listeners = %count, %seq, "Push {message}");
         }
     }
+
+    pub fn replay(&self, after: ws::Seq) -> ws::Replay<model::Push> {
+        let replay = self.lock();
+
+        let oldest = replay
+            .buffer
+            .front()
+            .map_or(replay.seq + 1, |(seq, _)| *seq);
+
+        if after >= replay.seq {
+            ws::Replay::Missed(Vec::new())
+        } else if after + 1 < oldest {
+            ws::Replay::Resync(replay.seq)
+        } else {
+            ws::Replay::Missed(
+                replay
+                    .buffer
+                    .iter()
+                    .filter(|(seq, _)| *seq > after)
+                    .cloned()
+                    .collect(),
+            )
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Replay> {
+        // Replaying is best effort, so a poisoned buffer is still used
+        self.replay
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{super::model, ws, Broadcaster, REPLAY_CAPACITY};
+
+    fn push(broadcaster: &Broadcaster, count: usize) {
+        for id in 0..count {
+            broadcaster.send(model::Push::Game(model::push::Game::PointsRecorded {
+                game: i64::try_from(id).unwrap(),
+                player_one: 1,
+                player_two: 2,
+            }));
+        }
+    }
+
+    fn missed(replay: ws::Replay<model::Push>) -> Vec<ws::Seq> {
+        match replay {
+            ws::Replay::Missed(pushes) => pushes.into_iter().map(|(seq, _)| seq).collect(),
+            ws::Replay::Resync(seq) => panic!("Unexpected resync at {seq}"),
+        }
+    }
+
+    #[test]
+    fn sequence() {
+        let broadcaster = Broadcaster::new();
+        push(&broadcaster, 2);
+
+        let (seq, mut receiver) = broadcaster.subscribe();
+        assert_eq!(seq, 2);
+
+        push(&broadcaster, 1);
+        assert_eq!(receiver.try_recv().unwrap().0, 3);
+    }
+
+    #[test]
+    fn replay() {
+        let broadcaster = Broadcaster::new();
+        assert!(missed(broadcaster.replay(0)).is_empty());
+
+        push(&broadcaster, 3);
+        assert_eq!(missed(broadcaster.replay(0)), vec![1, 2, 3]);
+        assert_eq!(missed(broadcaster.replay(1)), vec![2, 3]);
+        assert!(missed(broadcaster.replay(3)).is_empty());
+    }
+
+    #[test]
+    fn resync() {
+        let broadcaster = Broadcaster::new();
+        push(&broadcaster, REPLAY_CAPACITY + 2);
+
+        let latest = ws::Seq::try_from(REPLAY_CAPACITY + 2).unwrap();
+        assert!(matches!(broadcaster.replay(0), ws::Replay::Resync(seq) if seq == latest));
+        assert_eq!(missed(broadcaster.replay(2)).len(), REPLAY_CAPACITY);
+    }
 }
@@ -0,0 +1,56 @@
+use super::super::{access, model};
+use crate::smtp;
+
+#[derive(Debug)]
+pub struct Achievement<'a, A, S>
+where
+    A: super::Access,
+    S: smtp::Smtp,
+{
+    handler: &'a mut super::Handler<A, S>,
+}
+
+impl<'a, A, S> Achievement<'a, A, S>
+where
+    A: super::Access,
+    S: smtp::Smtp,
+{
+    pub fn new(handler: &'a mut super::Handler<A, S>) -> Self {
+        Self { handler }
+    }
+}
+
+impl<'a, S> Achievement<'a, access::Regular, S>
+where
+    S: smtp::Smtp,
+{
+    pub async fn handle(
+        self,
+        request: model::request::Achievement,
+    ) -> Result<model::Response, model::Error> {
+        match request {
+            model::request::Achievement::List => self
+                .handler
+                .store
+                .achievements()
+                .list()
+                .await
+                .map_err(model::Error::Store)
+                .map(|r| model::Response::Achievements(r.into_iter().map(Into::into).collect())),
+        }
+    }
+}
+
+impl<'a, S> Achievement<'a, access::Pending, S>
+where
+    S: smtp::Smtp,
+{
+    // allow(clippy::unused_async): To match the expected signature
+    #[allow(clippy::unused_async)]
+    pub async fn handle(
+        self,
+        _: model::request::Achievement,
+    ) -> Result<model::Response, model::Error> {
+        Err(model::Error::Forbidden)
+    }
+}
@@ -0,0 +1,60 @@
+use super::super::{access, model};
+use crate::smtp;
+
+#[derive(Debug)]
+pub struct Audit<'a, A, S>
+where
+    A: super::Access,
+    S: smtp::Smtp,
+{
+    handler: &'a mut super::Handler<A, S>,
+}
+
+impl<'a, A, S> Audit<'a, A, S>
+where
+    A: super::Access,
+    S: smtp::Smtp,
+{
+    pub fn new(handler: &'a mut super::Handler<A, S>) -> Self {
+        Self { handler }
+    }
+}
+
+impl<'a, S> Audit<'a, access::Regular, S>
+where
+    S: smtp::Smtp,
+{
+    pub async fn handle(
+        self,
+        request: model::request::Audit,
+    ) -> Result<model::Response, model::Error> {
+        match request {
+            model::request::Audit::List {
+                actor,
+                entity,
+                entity_id,
+            } => {
+                self.handler.require_admin().await?;
+
+                self.handler
+                    .store
+                    .audit()
+                    .list(actor, entity, entity_id)
+                    .await
+                    .map_err(model::Error::Store)
+                    .map(|r| model::Response::Audit(r.into_iter().map(Into::into).collect()))
+            }
+        }
+    }
+}
+
+impl<'a, S> Audit<'a, access::Pending, S>
+where
+    S: smtp::Smtp,
+{
+    // allow(clippy::unused_async): To match the expected signature
+    #[allow(clippy::unused_async)]
+    pub async fn handle(self, _: model::request::Audit) -> Result<model::Response, model::Error> {
+        Err(model::Error::Forbidden)
+    }
+}
@@ -36,6 +36,12 @@ where
                 .await
                 .map_err(model::Error::Store)
                 .map(|r| model::Response::Games(r.into_iter().map(Into::into).collect())),
+            model::request::Game::ListVenue(venue) => games
+                .list_venue(&venue)
+                .await
+                .map_err(model::Error::Store)
+                .map(|r| model::Response::Games(r.into_iter().map(Into::into).collect())),
+            model::request::Game::Venues => Ok(model::Response::Venues(games.venues().to_vec())),
             model::request::Game::Register {
                 player,
                 opponent,
@@ -43,13 +49,18 @@ where
                 opponent_score,
                 challenge,
                 millis,
+                comment,
+                venue,
+                first_server,
             } => {
-                let (game, updates) = games
+                let (game, updates, derived) = games
                     .register(
+                        self.handler.user.id(),
                         (player, opponent),
                         (score, opponent_score),
                         challenge,
                         millis,
+                        (comment.as_deref(), venue.as_deref(), first_server),
                         skillratings::elo::EloRating::new().rating,
                         super::rating_updater,
                     )
@@ -63,12 +74,37 @@ where
                         updates: updates.into_iter().map(Into::into).collect(),
                     }));
 
+                self.handler.send_derived(derived);
+
                 Ok(model::Response::Done)
             }
-            model::request::Game::Update(game) => {
-                let (game, updates) = games
+            model::request::Game::Update { game, reason } => {
+                let (game, updates, derived) = games
                     .update(
                         game,
+                        (self.handler.user.id(), reason.as_deref()),
+                        skillratings::elo::EloRating::new().rating,
+                        super::rating_updater,
+                    )
+                    .await
+                    .map_err(model::Error::Store)?;
+
+                self.handler
+                    .broadcaster
+                    .send(model::Push::Game(model::push::Game::Updated {
+                        game,
+                        updates: updates.into_iter().map(Into::into).collect(),
+                    }));
+
+                self.handler.send_derived(derived);
+
+                Ok(model::Response::Done)
+            }
+            model::request::Game::Revert { game, history } => {
+                let (game, updates, derived) = games
+                    .revert(
+                        (game, history),
+                        self.handler.user.id(),
                         skillratings::elo::EloRating::new().rating,
                         super::rating_updater,
                     )
@@ -82,6 +118,31 @@ where
                         updates: updates.into_iter().map(Into::into).collect(),
                     }));
 
+                self.handler.send_derived(derived);
+
+                Ok(model::Response::Done)
+            }
+            model::request::Game::Undo(id) => {
+                let (game, updates, derived) = games
+                    .undo(
+                        id,
+                        self.handler.user.id(),
+                        super::super::UNDO_WINDOW,
+                        skillratings::elo::EloRating::new().rating,
+                        super::rating_updater,
+                    )
+                    .await
+                    .map_err(model::Error::Store)?;
+
+                self.handler
+                    .broadcaster
+                    .send(model::Push::Game(model::push::Game::Undone {
+                        game,
+                        updates: updates.into_iter().map(Into::into).collect(),
+                    }));
+
+                self.handler.send_derived(derived);
+
                 Ok(model::Response::Done)
             }
             model::request::Game::History(game) => games
@@ -89,6 +150,51 @@ where
                 .await
                 .map_err(model::Error::Store)
                 .map(|r| model::Response::History(r.into_iter().map(Into::into).collect())),
+            model::request::Game::Record { game, points } => {
+                let (player_one, player_two) = games
+                    .record_points(game, &points)
+                    .await
+                    .map_err(model::Error::Store)?;
+
+                self.handler.broadcaster.send(model::Push::Game(
+                    model::push::Game::PointsRecorded {
+                        game,
+                        player_one,
+                        player_two,
+                    },
+                ));
+
+                Ok(model::Response::Done)
+            }
+            model::request::Game::Points(game) => games
+                .points(game)
+                .await
+                .map_err(model::Error::Store)
+                .map(model::Response::Points),
+            model::request::Game::Preview {
+                player,
+                opponent,
+                millis,
+                challenge,
+            } => {
+                let (rating, opponent_rating) = games
+                    .ratings_at(
+                        (player, opponent),
+                        millis,
+                        challenge,
+                        skillratings::elo::EloRating::new().rating,
+                    )
+                    .await
+                    .map_err(model::Error::Store)?;
+
+                Ok(model::Response::Preview {
+                    rating,
+                    opponent_rating,
+                    probability: super::win_probability(rating, opponent_rating),
+                    win: super::rating_updater(rating, opponent_rating, true, challenge),
+                    loss: super::rating_updater(rating, opponent_rating, false, challenge),
+                })
+            }
         }
     }
 }
@@ -89,6 +89,11 @@ where
                     .await
                     .map_err(model::Error::Store)?;
 
+                let invitee = mailbox::Proto {
+                    name: player.name.clone(),
+                    email: player.email.clone(),
+                };
+
                 self.handler
                     .broadcaster
                     .send(model::Push::Player(model::push::Player::Joined(player)));
@@ -100,7 +105,7 @@ where
                             name: initiator.name,
                             email: initiator.email,
                         },
-                        invitee: self.handler.user.make_proto(),
+                        invitee,
                         accepted: true,
                     })
                     .await;
@@ -113,6 +118,11 @@ where
                     .await
                     .map_err(model::Error::Store)?;
 
+                let invitee = mailbox::Proto {
+                    name: invite.name.clone(),
+                    email: invite.email.clone(),
+                };
+
                 self.handler
                     .broadcaster
                     .send(model::Push::Player(model::push::Player::Uninvited(invite)));
@@ -124,7 +134,7 @@ where
                             name: initiator.name,
                             email: initiator.email,
                         },
-                        invitee: self.handler.user.make_proto(),
+                        invitee,
                         accepted: false,
                     })
                     .await;
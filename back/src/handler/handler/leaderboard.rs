@@ -0,0 +1,92 @@
+use super::super::{access, model};
+use crate::smtp;
+
+#[derive(Debug)]
+pub struct Leaderboard<'a, A, S>
+where
+    A: super::Access,
+    S: smtp::Smtp,
+{
+    handler: &'a mut super::Handler<A, S>,
+}
+
+impl<'a, A, S> Leaderboard<'a, A, S>
+where
+    A: super::Access,
+    S: smtp::Smtp,
+{
+    pub fn new(handler: &'a mut super::Handler<A, S>) -> Self {
+        Self { handler }
+    }
+}
+
+impl<'a, S> Leaderboard<'a, access::Regular, S>
+where
+    S: smtp::Smtp,
+{
+    pub async fn handle(
+        self,
+        request: model::request::Leaderboard,
+    ) -> Result<model::Response, model::Error> {
+        let leaderboard = self.handler.store.leaderboard();
+
+        match request {
+            model::request::Leaderboard::Get { at } => leaderboard
+                .get(
+                    at,
+                    super::super::MIN_GAMES,
+                    skillratings::elo::EloRating::new().rating,
+                )
+                .await
+                .map_err(model::Error::Store)
+                .map(|r| model::Response::Leaderboard {
+                    min_games: super::super::MIN_GAMES,
+                    standings: r.into_iter().map(Into::into).collect(),
+                }),
+            model::request::Leaderboard::AllTime => leaderboard
+                .all_time(
+                    super::super::MIN_GAMES,
+                    skillratings::elo::EloRating::new().rating,
+                    super::rating_updater,
+                )
+                .await
+                .map_err(model::Error::Store)
+                .map(|r| model::Response::Leaderboard {
+                    min_games: super::super::MIN_GAMES,
+                    standings: r.into_iter().map(Into::into).collect(),
+                }),
+            model::request::Leaderboard::Simulate {
+                k_factor,
+                challenge_multiplier,
+            } => {
+                self.handler.require_admin().await?;
+
+                let (parameters, comparisons) =
+                    super::simulate(&self.handler.store, k_factor, challenge_multiplier)
+                        .await
+                        .map_err(model::Error::Store)?;
+
+                Ok(model::Response::Simulation {
+                    min_games: super::super::MIN_GAMES,
+                    parameters,
+                    movers: super::movers(&comparisons, super::super::MOVERS),
+                    comparisons: comparisons.into_iter().map(Into::into).collect(),
+                })
+            }
+        }
+    }
+}
+
+impl<'a, S> Leaderboard<'a, access::Pending, S>
+where
+    S: smtp::Smtp,
+{
+    // allow(clippy::unused_async): To match the expected signature
+    #[allow(clippy::unused_async)]
+    pub async fn handle(
+        self,
+        _: model::request::Leaderboard,
+    ) -> Result<model::Response, model::Error> {
+        Err(model::Error::Forbidden)
+    }
+}
@@ -0,0 +1,129 @@
+use super::super::{access, model};
+use crate::smtp;
+
+#[derive(Debug)]
+pub struct LiveGame<'a, A, S>
+where
+    A: super::Access,
+    S: smtp::Smtp,
+{
+    handler: &'a mut super::Handler<A, S>,
+}
+
+impl<'a, A, S> LiveGame<'a, A, S>
+where
+    A: super::Access,
+    S: smtp::Smtp,
+{
+    pub fn new(handler: &'a mut super::Handler<A, S>) -> Self {
+        Self { handler }
+    }
+}
+
+impl<'a, S> LiveGame<'a, access::Regular, S>
+where
+    S: smtp::Smtp,
+{
+    pub async fn handle(
+        self,
+        request: model::request::LiveGame,
+    ) -> Result<model::Response, model::Error> {
+        let live_games = self.handler.store.live_games();
+        let scorer = self.handler.user.id();
+
+        match request {
+            model::request::LiveGame::List => {
+                Ok(model::Response::LiveGames(live_games.list().await))
+            }
+            model::request::LiveGame::Start { player, opponent } => {
+                let live = live_games
+                    .start(scorer, (player, opponent))
+                    .await
+                    .map_err(model::Error::Store)?;
+
+                self.handler
+                    .broadcaster
+                    .send(model::Push::Live(model::push::LiveGame::Started(live)));
+
+                Ok(model::Response::Done)
+            }
+            model::request::LiveGame::Point { side } => {
+                let live = live_games
+                    .point(scorer, side)
+                    .await
+                    .map_err(model::Error::Store)?;
+
+                self.handler
+                    .broadcaster
+                    .send(model::Push::Live(model::push::LiveGame::Updated(live)));
+
+                Ok(model::Response::Done)
+            }
+            model::request::LiveGame::Undo => {
+                let live = live_games.undo(scorer).await.map_err(model::Error::Store)?;
+
+                self.handler
+                    .broadcaster
+                    .send(model::Push::Live(model::push::LiveGame::Updated(live)));
+
+                Ok(model::Response::Done)
+            }
+            model::request::LiveGame::Cancel => {
+                let live = live_games
+                    .cancel(scorer)
+                    .await
+                    .map_err(model::Error::Store)?;
+
+                self.handler
+                    .broadcaster
+                    .send(model::Push::Live(model::push::LiveGame::Cancelled(live)));
+
+                Ok(model::Response::Done)
+            }
+            model::request::LiveGame::Finish => {
+                let (live, game, updates, derived) = live_games
+                    .finish(
+                        scorer,
+                        skillratings::elo::EloRating::new().rating,
+                        super::rating_updater,
+                    )
+                    .await
+                    .map_err(model::Error::Store)?;
+
+                let id = game.id;
+
+                self.handler
+                    .broadcaster
+                    .send(model::Push::Game(model::push::Game::Registered {
+                        game,
+                        updates: updates.into_iter().map(Into::into).collect(),
+                    }));
+
+                self.handler
+                    .broadcaster
+                    .send(model::Push::Live(model::push::LiveGame::Finished {
+                        live,
+                        game: id,
+                    }));
+
+                self.handler.send_derived(derived);
+
+                Ok(model::Response::Done)
+            }
+        }
+    }
+}
+
+impl<'a, S> LiveGame<'a, access::Pending, S>
+where
+    S: smtp::Smtp,
+{
+    // allow(clippy::unused_async): To match the expected signature
+    #[allow(clippy::unused_async)]
+    pub async fn handle(
+        self,
+        _: model::request::LiveGame,
+    ) -> Result<model::Response, model::Error> {
+        Err(model::Error::Forbidden)
+    }
+}
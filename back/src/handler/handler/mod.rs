@@ -1,15 +1,22 @@
+mod achievement;
+mod audit;
 mod game;
 mod invite;
+mod leaderboard;
+mod live_game;
 mod player;
+mod season;
+mod subscription;
+mod tournament;
 
 use super::{access, broadcaster, model};
-use crate::{smtp, store, ws};
+use crate::{smtp, store, types, ws};
 
-pub trait Access: access::Access + Sized {
+pub trait Access: access::Access + Clone + Send + Sync + 'static {
     fn handle<S>(
         handler: &mut Handler<Self, S>,
         request: model::Request,
-    ) -> impl std::future::Future<Output = Result<model::Response, model::Error>>
+    ) -> impl std::future::Future<Output = Result<model::Response, model::Error>> + Send
     where
         S: smtp::Smtp;
 }
@@ -27,6 +34,7 @@ macro_rules! impl_access {
                 match request {
                     model::Request::Version => Ok(model::Response::Version {
                         server: super::VERSION,
+                        protocol: handler.version,
                         data: handler.store.version(),
                     }),
                     model::Request::Player(request) => {
@@ -36,6 +44,27 @@ macro_rules! impl_access {
                         invite::Invite::new(handler).handle(request).await
                     }
                     model::Request::Game(request) => game::Game::new(handler).handle(request).await,
+                    model::Request::Leaderboard(request) => {
+                        leaderboard::Leaderboard::new(handler).handle(request).await
+                    }
+                    model::Request::Season(request) => {
+                        season::Season::new(handler).handle(request).await
+                    }
+                    model::Request::Tournament(request) => {
+                        tournament::Tournament::new(handler).handle(request).await
+                    }
+                    model::Request::Achievement(request) => {
+                        achievement::Achievement::new(handler).handle(request).await
+                    }
+                    model::Request::Audit(request) => {
+                        audit::Audit::new(handler).handle(request).await
+                    }
+                    model::Request::LiveGame(request) => {
+                        live_game::LiveGame::new(handler).handle(request).await
+                    }
+                    model::Request::Subscription(request) => {
+                        subscription::Subscription::new(handler).handle(request)
+                    }
                 }
             }
         }
@@ -45,7 +74,8 @@ macro_rules! impl_access {
 impl_access!(access::Regular);
 impl_access!(access::Pending);
 
-#[derive(Debug)]
+// Cloned for every request, so the state of the connection is shared between the clones
+#[derive(Debug, Clone)]
 pub struct Handler<A, S>
 where
     A: Access,
@@ -55,6 +85,8 @@ where
     store: store::Store,
     smtp: S,
     broadcaster: broadcaster::Broadcaster,
+    topics: std::sync::Arc<std::sync::Mutex<std::collections::BTreeSet<model::push::Topic>>>,
+    version: u32,
 }
 
 impl<A, S> Handler<A, S>
@@ -74,6 +106,53 @@ where
             store,
             smtp,
             broadcaster,
+            topics: std::sync::Arc::new(std::sync::Mutex::new(
+                model::push::Topic::DEFAULT.into_iter().collect(),
+            )),
+            version: super::VERSION,
+        }
+    }
+
+    #[must_use]
+    pub fn with_version(mut self, version: u32) -> Self {
+        self.version = version;
+        self
+    }
+}
+
+impl<S> Handler<access::Regular, S>
+where
+    S: smtp::Smtp,
+{
+    async fn require_admin(&self) -> Result<(), model::Error> {
+        if self
+            .store
+            .players()
+            .is_admin(self.user.id())
+            .await
+            .map_err(model::Error::Store)?
+        {
+            Ok(())
+        } else {
+            Err(model::Error::Forbidden)
+        }
+    }
+
+    // Streaks and achievements are pushed after the push of the write that changed them
+    fn send_derived(&self, derived: types::Derived) {
+        for (old, new) in derived.streaks {
+            for push in streak_pushes(old, new) {
+                self.broadcaster.send(model::Push::Streak(push));
+            }
+        }
+
+        if !derived.unlocked.is_empty() || !derived.revoked.is_empty() {
+            self.broadcaster.send(model::Push::Achievement(
+                model::push::Achievement::Updated {
+                    unlocked: derived.unlocked.into_iter().map(Into::into).collect(),
+                    revoked: derived.revoked.into_iter().map(Into::into).collect(),
+                },
+            ));
         }
     }
 }
@@ -87,12 +166,136 @@ where
     type Response = model::Response;
     type Error = model::Error;
     type Push = model::Push;
+    type PresentedResponse = model::legacy::Versioned<model::Response, model::legacy::Response>;
+    type PresentedPush = model::legacy::Versioned<model::Push, model::legacy::Push>;
 
-    fn subscribe(&self) -> tokio::sync::broadcast::Receiver<Self::Push> {
+    fn subscribe(
+        &self,
+    ) -> (
+        ws::Seq,
+        tokio::sync::broadcast::Receiver<(ws::Seq, Self::Push)>,
+    ) {
         self.broadcaster.subscribe()
     }
 
+    fn replay(&self, after: ws::Seq) -> ws::Replay<Self::Push> {
+        self.broadcaster.replay(after)
+    }
+
+    fn accepts(&self, push: &Self::Push) -> bool {
+        self.topics().iter().any(|&topic| push.matches(topic))
+    }
+
+    fn closes(&self, push: &Self::Push) -> Option<ws::Close> {
+        match push {
+            model::Push::Player(model::push::Player::Joined(player))
+                if &player.email == self.user.email() =>
+            {
+                Some(ws::Close::reconnect("Invitation accepted"))
+            }
+            model::Push::Player(model::push::Player::Uninvited(invite))
+                if &invite.email == self.user.email() =>
+            {
+                Some(ws::Close::revoked("Invitation withdrawn"))
+            }
+            _ => None,
+        }
+    }
+
+    fn rejects(&self) -> Option<ws::Close> {
+        if (super::MIN_VERSION..=super::VERSION).contains(&self.version) {
+            None
+        } else {
+            tracing::warn!(version = %self.version, "Unsupported protocol version");
+            Some(ws::Close::outdated("Outdated client, please reload"))
+        }
+    }
+
+    fn owner(&self) -> String {
+        self.user.email().clone()
+    }
+
+    fn cancellable(&self, request: &Self::Request) -> bool {
+        !request.writes()
+    }
+
+    fn present_response(&self, response: Self::Response) -> Self::PresentedResponse {
+        response.versioned(self.version)
+    }
+
+    fn present_push(&self, push: Self::Push) -> Option<Self::PresentedPush> {
+        push.versioned(self.version)
+    }
+
     async fn call(&mut self, request: Self::Request) -> Result<Self::Response, Self::Error> {
+        let store = self.store.clone();
+        let _guard = store.shared().await;
+        self.execute(request).await
+    }
+
+    async fn batch(
+        &mut self,
+        requests: Vec<Self::Request>,
+        stop_on_error: bool,
+    ) -> Vec<Result<Self::Response, Self::Error>> {
+        tracing::debug!(count = %requests.len(), %stop_on_error, "Batch");
+
+        if !stop_on_error {
+            let mut results = Vec::with_capacity(requests.len());
+            for request in requests {
+                results.push(self.call(request).await);
+            }
+            return results;
+        }
+
+        // Nothing undoes a write, so the batch is all or nothing only if no request can fail after
+        // one that wrote
+        let last = requests.len().saturating_sub(1);
+        if requests[..last].iter().any(model::Request::writes) {
+            return requests
+                .iter()
+                .enumerate()
+                .map(|(i, request)| {
+                    if i < last && request.writes() {
+                        Err(model::Error::WriteNotLast)
+                    } else {
+                        Err(model::Error::Skipped)
+                    }
+                })
+                .collect();
+        }
+
+        let store = self.store.clone();
+        let _guard = store.exclusive().await;
+
+        let mut results = Vec::with_capacity(requests.len());
+        let mut failed = false;
+        for request in requests {
+            if failed {
+                results.push(Err(model::Error::Skipped));
+            } else {
+                let result = self.execute(request).await;
+                failed = result.is_err();
+                results.push(result);
+            }
+        }
+        results
+    }
+}
+
+impl<A, S> Handler<A, S>
+where
+    A: Access,
+    S: smtp::Smtp,
+{
+    fn topics(&self) -> std::sync::MutexGuard<'_, std::collections::BTreeSet<model::push::Topic>> {
+        // The topics are always left consistent, so a poisoned set is still used
+        self.topics
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    async fn execute(&mut self, request: model::Request) -> Result<model::Response, model::Error> {
         let start = std::time::Instant::now();
         let message = request.to_string();
 
@@ -118,25 +321,134 @@ pub async fn refresh(store: &store::Store) -> Result<(), store::Error> {
     store
         .games()
         .refresh(skillratings::elo::EloRating::new().rating, rating_updater)
+        .await?;
+    store.live_games().load().await
+}
+
+pub async fn snapshot(store: &store::Store) -> Result<(), store::Error> {
+    store
+        .leaderboard()
+        .snapshot(super::MIN_GAMES, skillratings::elo::EloRating::new().rating)
+        .await?;
+    store.seasons().archive(super::MIN_GAMES).await
+}
+
+pub async fn simulate(
+    store: &store::Store,
+    k_factor: Option<f64>,
+    challenge_multiplier: Option<f64>,
+) -> Result<(types::RatingParameters, Vec<types::Comparison>), store::Error> {
+    let parameters = types::RatingParameters {
+        k_factor: k_factor.unwrap_or(RATING_PARAMETERS.k_factor),
+        challenge_multiplier: challenge_multiplier
+            .unwrap_or(RATING_PARAMETERS.challenge_multiplier),
+    };
+
+    if !parameters.k_factor.is_finite() || parameters.k_factor <= 0.0 {
+        return Err(store::Error::InvalidValue("The K-factor must be positive"));
+    }
+
+    if !parameters.challenge_multiplier.is_finite() || parameters.challenge_multiplier <= 0.0 {
+        return Err(store::Error::InvalidValue(
+            "The challenge multiplier must be positive",
+        ));
+    }
+
+    store
+        .leaderboard()
+        .simulate(
+            super::MIN_GAMES,
+            skillratings::elo::EloRating::new().rating,
+            rating_updater,
+            parameterized_updater(parameters),
+        )
         .await
-        .map(|_| ())
+        .map(|comparisons| (parameters, comparisons))
 }
 
+#[must_use]
+pub fn movers(comparisons: &[types::Comparison], count: usize) -> Vec<types::Id> {
+    let mut movers = comparisons
+        .iter()
+        .map(|c| (c.player, (c.simulated_rating - c.rating).abs()))
+        .filter(|(_, movement)| *movement > f64::EPSILON)
+        .collect::<Vec<_>>();
+
+    movers.sort_by(|lhs, rhs| rhs.1.total_cmp(&lhs.1));
+    movers
+        .into_iter()
+        .take(count)
+        .map(|(player, _)| player)
+        .collect()
+}
+
+const RATING_PARAMETERS: types::RatingParameters = types::RatingParameters {
+    k_factor: 32.0,
+    challenge_multiplier: 3.0,
+};
+
 fn rating_updater(one: f64, two: f64, won: bool, challenge: bool) -> f64 {
-    let ratings = skillratings::elo::elo(
-        &skillratings::elo::EloRating { rating: one },
-        &skillratings::elo::EloRating { rating: two },
-        if won {
-            &skillratings::Outcomes::WIN
+    parameterized_updater(RATING_PARAMETERS)(one, two, won, challenge)
+}
+
+fn parameterized_updater(
+    parameters: types::RatingParameters,
+) -> impl Copy + Fn(f64, f64, bool, bool) -> f64 {
+    move |one, two, won, challenge| {
+        let ratings = skillratings::elo::elo(
+            &skillratings::elo::EloRating { rating: one },
+            &skillratings::elo::EloRating { rating: two },
+            if won {
+                &skillratings::Outcomes::WIN
+            } else {
+                &skillratings::Outcomes::LOSS
+            },
+            &skillratings::elo::EloConfig {
+                k: parameters.k_factor,
+            },
+        );
+        let delta = ratings.0.rating - one;
+        if challenge {
+            delta * parameters.challenge_multiplier
+        } else {
+            delta
+        }
+    }
+}
+
+// A streak only becomes noteworthy once it reaches the first threshold
+const STREAK_THRESHOLDS: [i64; 3] = [3, 5, 10];
+
+fn streak_pushes(old: types::Streak, new: types::Streak) -> Vec<model::push::Streak> {
+    let noteworthy = |current: i64| current.abs() >= STREAK_THRESHOLDS[0];
+    let same_run =
+        old.current.signum() == new.current.signum() && new.current.abs() >= old.current.abs();
+    let crossed = STREAK_THRESHOLDS
+        .iter()
+        .any(|&t| old.current.abs() < t && new.current.abs() >= t);
+
+    let mut pushes = Vec::new();
+
+    if noteworthy(old.current) && !same_run {
+        pushes.push(model::push::Streak::Broken(old));
+        if noteworthy(new.current) {
+            pushes.push(model::push::Streak::Started(new));
+        }
+    } else if noteworthy(new.current) && crossed {
+        if noteworthy(old.current) {
+            pushes.push(model::push::Streak::Extended(new));
         } else {
-            &skillratings::Outcomes::LOSS
-        },
-        &skillratings::elo::EloConfig::new(),
-    );
-    let delta = ratings.0.rating - one;
-    if challenge {
-        delta * 3.0
-    } else {
-        delta
+            pushes.push(model::push::Streak::Started(new));
+        }
     }
+
+    pushes
+}
+
+fn win_probability(one: f64, two: f64) -> f64 {
+    skillratings::elo::expected_score(
+        &skillratings::elo::EloRating { rating: one },
+        &skillratings::elo::EloRating { rating: two },
+    )
+    .0
 }
@@ -41,7 +41,7 @@ where
                 .map_err(model::Error::Store)
                 .map(|r| model::Response::Players(r.into_iter().map(Into::into).collect())),
             model::request::Player::Rename(name) => {
-                let player = players
+                let (before, player) = players
                     .rename(self.handler.user.id(), &name)
                     .await
                     .map_err(model::Error::Store)?;
@@ -50,14 +50,42 @@ where
                     .broadcaster
                     .send(model::Push::Player(model::push::Player::Renamed {
                         player: player.id,
-                        old: self.handler.user.name().clone(),
-                        new: player.name.clone(),
+                        old: before.name,
+                        new: player.name,
                     }));
 
-                self.handler.user.update_name(player.name);
-
                 Ok(model::Response::Done)
             }
+            model::request::Player::RatingHistory {
+                id,
+                from,
+                to,
+                resolution,
+                season,
+            } => self
+                .handler
+                .store
+                .games()
+                .rating_history(id, (from, to), resolution, season)
+                .await
+                .map_err(model::Error::Store)
+                .map(|r| model::Response::RatingHistory(r.into_iter().map(Into::into).collect())),
+            model::request::Player::SuggestOpponents => players
+                .suggest_opponents(
+                    self.handler.user.id(),
+                    skillratings::elo::EloRating::new().rating,
+                )
+                .await
+                .map_err(model::Error::Store)
+                .map(|r| model::Response::Suggestions(r.into_iter().map(Into::into).collect())),
+            model::request::Player::Streaks { season } => self
+                .handler
+                .store
+                .streaks()
+                .list(season)
+                .await
+                .map_err(model::Error::Store)
+                .map(|r| model::Response::Streaks(r.into_iter().map(Into::into).collect())),
         }
     }
 }
@@ -0,0 +1,122 @@
+use super::super::{access, model};
+use crate::smtp;
+
+#[derive(Debug)]
+pub struct Season<'a, A, S>
+where
+    A: super::Access,
+    S: smtp::Smtp,
+{
+    handler: &'a mut super::Handler<A, S>,
+}
+
+impl<'a, A, S> Season<'a, A, S>
+where
+    A: super::Access,
+    S: smtp::Smtp,
+{
+    pub fn new(handler: &'a mut super::Handler<A, S>) -> Self {
+        Self { handler }
+    }
+}
+
+impl<'a, S> Season<'a, access::Regular, S>
+where
+    S: smtp::Smtp,
+{
+    pub async fn handle(
+        self,
+        request: model::request::Season,
+    ) -> Result<model::Response, model::Error> {
+        let seasons = self.handler.store.seasons();
+
+        match request {
+            model::request::Season::List => seasons
+                .list()
+                .await
+                .map_err(model::Error::Store)
+                .map(|r| model::Response::Seasons(r.into_iter().map(Into::into).collect())),
+            model::request::Season::Create {
+                name,
+                start,
+                end,
+                reset,
+            } => {
+                self.handler.require_admin().await?;
+
+                let (season, updates, derived) = seasons
+                    .create(
+                        self.handler.user.id(),
+                        &name,
+                        (start, end),
+                        reset,
+                        skillratings::elo::EloRating::new().rating,
+                        super::rating_updater,
+                    )
+                    .await
+                    .map_err(model::Error::Store)?;
+
+                self.handler
+                    .broadcaster
+                    .send(model::Push::Season(model::push::Season::Created {
+                        season,
+                        updates: updates.into_iter().map(Into::into).collect(),
+                    }));
+
+                // Resets change the ratings that achievements are unlocked by
+                self.handler.send_derived(derived);
+
+                Ok(model::Response::Done)
+            }
+            model::request::Season::Delete(id) => {
+                self.handler.require_admin().await?;
+
+                let (season, updates, derived) = seasons
+                    .delete(
+                        self.handler.user.id(),
+                        id,
+                        skillratings::elo::EloRating::new().rating,
+                        super::rating_updater,
+                    )
+                    .await
+                    .map_err(model::Error::Store)?;
+
+                self.handler
+                    .broadcaster
+                    .send(model::Push::Season(model::push::Season::Deleted {
+                        season,
+                        updates: updates.into_iter().map(Into::into).collect(),
+                    }));
+
+                // Resets change the ratings that achievements are unlocked by
+                self.handler.send_derived(derived);
+
+                Ok(model::Response::Done)
+            }
+            model::request::Season::Games(id) => seasons
+                .games(id)
+                .await
+                .map_err(model::Error::Store)
+                .map(|r| model::Response::Games(r.into_iter().map(Into::into).collect())),
+            model::request::Season::Standings(id) => seasons
+                .standings(id, super::super::MIN_GAMES)
+                .await
+                .map_err(model::Error::Store)
+                .map(|r| model::Response::Leaderboard {
+                    min_games: super::super::MIN_GAMES,
+                    standings: r.into_iter().map(Into::into).collect(),
+                }),
+        }
+    }
+}
+
+impl<'a, S> Season<'a, access::Pending, S>
+where
+    S: smtp::Smtp,
+{
+    // allow(clippy::unused_async): To match the expected signature
+    #[allow(clippy::unused_async)]
+    pub async fn handle(self, _: model::request::Season) -> Result<model::Response, model::Error> {
+        Err(model::Error::Forbidden)
+    }
+}
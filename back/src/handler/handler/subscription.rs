@@ -0,0 +1,41 @@
+use super::super::model;
+use crate::smtp;
+
+#[derive(Debug)]
+pub struct Subscription<'a, A, S>
+where
+    A: super::Access,
+    S: smtp::Smtp,
+{
+    handler: &'a mut super::Handler<A, S>,
+}
+
+impl<'a, A, S> Subscription<'a, A, S>
+where
+    A: super::Access,
+    S: smtp::Smtp,
+{
+    pub fn new(handler: &'a mut super::Handler<A, S>) -> Self {
+        Self { handler }
+    }
+
+    // Subscriptions only affect what this connection is pushed, so any access level can manage them
+    pub fn handle(
+        self,
+        request: model::request::Subscription,
+    ) -> Result<model::Response, model::Error> {
+        let mut topics = self.handler.topics();
+
+        match request {
+            model::request::Subscription::List => {}
+            model::request::Subscription::Subscribe(subscribe) => topics.extend(subscribe),
+            model::request::Subscription::Unsubscribe(unsubscribe) => {
+                for topic in unsubscribe {
+                    topics.remove(&topic);
+                }
+            }
+        }
+
+        Ok(model::Response::Topics(topics.iter().copied().collect()))
+    }
+}
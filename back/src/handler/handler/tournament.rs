@@ -0,0 +1,120 @@
+use super::super::{access, model};
+use crate::smtp;
+
+#[derive(Debug)]
+pub struct Tournament<'a, A, S>
+where
+    A: super::Access,
+    S: smtp::Smtp,
+{
+    handler: &'a mut super::Handler<A, S>,
+}
+
+impl<'a, A, S> Tournament<'a, A, S>
+where
+    A: super::Access,
+    S: smtp::Smtp,
+{
+    pub fn new(handler: &'a mut super::Handler<A, S>) -> Self {
+        Self { handler }
+    }
+}
+
+impl<'a, S> Tournament<'a, access::Regular, S>
+where
+    S: smtp::Smtp,
+{
+    pub async fn handle(
+        self,
+        request: model::request::Tournament,
+    ) -> Result<model::Response, model::Error> {
+        let tournaments = self.handler.store.tournaments();
+
+        match request {
+            model::request::Tournament::List => tournaments
+                .list()
+                .await
+                .map_err(model::Error::Store)
+                .map(|r| model::Response::Tournaments(r.into_iter().map(Into::into).collect())),
+            model::request::Tournament::Create {
+                name,
+                format,
+                players,
+                groups,
+                advancing,
+            } => {
+                self.handler.require_admin().await?;
+
+                let (tournament, fixtures) = tournaments
+                    .create(
+                        self.handler.user.id(),
+                        &name,
+                        format,
+                        &players,
+                        (groups, advancing),
+                    )
+                    .await
+                    .map_err(model::Error::Store)?;
+
+                self.handler.broadcaster.send(model::Push::Tournament(
+                    model::push::Tournament::Created {
+                        tournament,
+                        fixtures: fixtures.into_iter().map(Into::into).collect(),
+                    },
+                ));
+
+                Ok(model::Response::Done)
+            }
+            model::request::Tournament::Delete(id) => {
+                self.handler.require_admin().await?;
+
+                let tournament = tournaments
+                    .delete(self.handler.user.id(), id)
+                    .await
+                    .map_err(model::Error::Store)?;
+
+                self.handler.broadcaster.send(model::Push::Tournament(
+                    model::push::Tournament::Deleted(tournament),
+                ));
+
+                Ok(model::Response::Done)
+            }
+            model::request::Tournament::Fixtures(id) => tournaments
+                .fixtures(id)
+                .await
+                .map_err(model::Error::Store)
+                .map(|r| model::Response::Fixtures(r.into_iter().map(Into::into).collect())),
+            model::request::Tournament::Link { fixture, game } => {
+                self.handler.require_admin().await?;
+
+                let (tournament, fixtures) = tournaments
+                    .link(self.handler.user.id(), fixture, game)
+                    .await
+                    .map_err(model::Error::Store)?;
+
+                self.handler.broadcaster.send(model::Push::Tournament(
+                    model::push::Tournament::Updated {
+                        tournament,
+                        fixtures: fixtures.into_iter().map(Into::into).collect(),
+                    },
+                ));
+
+                Ok(model::Response::Done)
+            }
+        }
+    }
+}
+
+impl<'a, S> Tournament<'a, access::Pending, S>
+where
+    S: smtp::Smtp,
+{
+    // allow(clippy::unused_async): To match the expected signature
+    #[allow(clippy::unused_async)]
+    pub async fn handle(
+        self,
+        _: model::request::Tournament,
+    ) -> Result<model::Response, model::Error> {
+        Err(model::Error::Forbidden)
+    }
+}
@@ -212,6 +212,9 @@ async fn populate_games(
                 opponent_score,
                 challenge,
                 millis: types::Millis::from(millis),
+                comment: None,
+                venue: None,
+                first_server: None,
             }))
             .await
         {
@@ -228,6 +231,9 @@ async fn populate_games(
                         opponent_score,
                         challenge: false,
                         millis: types::Millis::from(millis),
+                        comment: None,
+                        venue: None,
+                        first_server: None,
                     }))
                     .await?
                 else {
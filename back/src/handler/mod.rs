@@ -5,9 +5,16 @@ mod model;
 
 pub use access::{Auth, Dynamic as UserAccess};
 pub use broadcaster::Broadcaster;
-pub use handler::{refresh, Handler};
+pub use handler::{movers, refresh, simulate, snapshot, Handler};
 
-const VERSION: u32 = 3;
+const VERSION: u32 = 4;
+// Oldest protocol that can still be served, assumed for clients that do not declare theirs
+pub const MIN_VERSION: u32 = 3;
+const MIN_GAMES: i64 = 5;
+// How many of the players whose rating moved the most are highlighted in a simulation
+pub const MOVERS: usize = 5;
+// How long after registering a game it can still be undone
+const UNDO_WINDOW: i64 = 2 * 60 * 1000;
 
 #[cfg(feature = "local")]
 pub mod mock;
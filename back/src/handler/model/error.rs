@@ -5,6 +5,8 @@ pub enum Error {
     Store(store::Error),
     InvalidEmail(mailbox::Error),
     Forbidden,
+    Skipped,
+    WriteNotLast,
 }
 
 impl std::error::Error for Error {}
@@ -15,6 +17,10 @@ impl std::fmt::Display for Error {
             Self::Store(error) => error.fmt(f),
             Self::InvalidEmail(error) => error.fmt(f),
             Self::Forbidden => f.write_str("Forbidden"),
+            Self::Skipped => f.write_str("Skipped after a previous request failed"),
+            Self::WriteNotLast => {
+                f.write_str("Only the last request of a batch that stops on error can write")
+            }
         }
     }
 }
@@ -23,7 +29,7 @@ impl From<Error> for ws::Error {
     fn from(value: Error) -> Self {
         match value {
             Error::Store(error) => match error {
-                store::Error::Query(_) => Self::new(
+                store::Error::Query(_) | store::Error::Snapshot(_) => Self::new(
                     hyper::StatusCode::INTERNAL_SERVER_ERROR,
                     "Internal server error",
                 ),
@@ -34,15 +40,21 @@ impl From<Error> for ws::Error {
                     Self::new(hyper::StatusCode::CONFLICT, &error)
                 }
                 store::Error::NotFound => Self::new(hyper::StatusCode::NOT_FOUND, "Not found"),
+                store::Error::Forbidden => Self::new(hyper::StatusCode::FORBIDDEN, "Forbidden"),
             },
             Error::InvalidEmail(error) => Self::new(hyper::StatusCode::BAD_REQUEST, &error),
             Error::Forbidden => Self::new(hyper::StatusCode::FORBIDDEN, "Forbidden"),
+            error @ Error::Skipped => Self::new(hyper::StatusCode::FAILED_DEPENDENCY, &error),
+            error @ Error::WriteNotLast => Self::new(hyper::StatusCode::BAD_REQUEST, &error),
         }
     }
 }
 
 impl Error {
     pub fn is_warn(&self) -> bool {
-        !matches!(self, Self::Store(store::Error::Query(_)))
+        !matches!(
+            self,
+            Self::Store(store::Error::Query(_) | store::Error::Snapshot(_))
+        )
     }
 }
@@ -0,0 +1,187 @@
+use super::push;
+use crate::types;
+
+// Protocol version where games gained their annotations and history its editors, older clients
+// get the shapes in this module instead and only the pushes they know
+const ANNOTATED_GAMES: u32 = 4;
+
+// Sent as whichever shape the protocol of the client knows
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(untagged)]
+pub enum Versioned<T, L> {
+    Latest(T),
+    Legacy(L),
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Response {
+    Games(Vec<GameTuple>),
+    History(Vec<HistoryTuple>),
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Push {
+    Game(Game),
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Game {
+    Registered {
+        game: GameRecord,
+        updates: Vec<GameTuple>,
+    },
+    Updated {
+        game: GameRecord,
+        updates: Vec<GameTuple>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GameRecord {
+    pub id: types::Id,
+    pub player_one: types::Id,
+    pub player_two: types::Id,
+    pub score_one: i64,
+    pub score_two: i64,
+    pub rating_one: f64,
+    pub rating_two: f64,
+    pub rating_delta: f64,
+    pub challenge: bool,
+    pub deleted: bool,
+    pub millis: types::Millis,
+    pub created_ms: types::Millis,
+}
+
+impl From<types::Game> for GameRecord {
+    fn from(value: types::Game) -> Self {
+        Self {
+            id: value.id,
+            player_one: value.player_one,
+            player_two: value.player_two,
+            score_one: value.score_one,
+            score_two: value.score_two,
+            rating_one: value.rating_one,
+            rating_two: value.rating_two,
+            rating_delta: value.rating_delta,
+            challenge: value.challenge,
+            deleted: value.deleted,
+            millis: value.millis,
+            created_ms: value.created_ms,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct GameTuple(
+    pub types::Id,
+    pub types::Id,
+    pub types::Id,
+    pub i64,
+    pub i64,
+    pub f64,
+    pub f64,
+    pub f64,
+    pub bool,
+    pub bool,
+    pub types::Millis,
+    pub types::Millis,
+);
+
+impl From<types::GameTuple> for GameTuple {
+    fn from(value: types::GameTuple) -> Self {
+        Self(
+            value.0, value.1, value.2, value.3, value.4, value.5, value.6, value.7, value.8,
+            value.9, value.10, value.11,
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct HistoryTuple(
+    pub types::Id,
+    pub types::Id,
+    pub types::Id,
+    pub types::Id,
+    pub i64,
+    pub i64,
+    pub bool,
+    pub bool,
+    pub types::Millis,
+    pub types::Millis,
+);
+
+impl From<types::HistoryTuple> for HistoryTuple {
+    fn from(value: types::HistoryTuple) -> Self {
+        Self(
+            value.0, value.1, value.2, value.3, value.4, value.5, value.6, value.7, value.8,
+            value.9,
+        )
+    }
+}
+
+impl super::Response {
+    #[must_use]
+    pub fn versioned(self, version: u32) -> Versioned<Self, Response> {
+        if version >= ANNOTATED_GAMES {
+            return Versioned::Latest(self);
+        }
+
+        match self {
+            Self::Games(games) => Versioned::Legacy(Response::Games(legacy(games))),
+            Self::History(history) => Versioned::Legacy(Response::History(
+                history.into_iter().map(Into::into).collect(),
+            )),
+            response => Versioned::Latest(response),
+        }
+    }
+}
+
+impl super::Push {
+    // Pushes older clients do not know are left out
+    #[must_use]
+    pub fn versioned(self, version: u32) -> Option<Versioned<Self, Push>> {
+        if version >= ANNOTATED_GAMES {
+            return Some(Versioned::Latest(self));
+        }
+
+        match self {
+            push @ Self::Player(_) => Some(Versioned::Latest(push)),
+            Self::Game(push::Game::Registered { game, updates }) => {
+                Some(Versioned::Legacy(Push::Game(Game::Registered {
+                    game: game.into(),
+                    updates: legacy(updates),
+                })))
+            }
+            Self::Game(push::Game::Updated { game, updates }) => {
+                Some(Versioned::Legacy(Push::Game(Game::Updated {
+                    game: game.into(),
+                    updates: legacy(updates),
+                })))
+            }
+            // Older clients know removed games as deleted ones
+            Self::Game(push::Game::Undone { game, updates }) => {
+                Some(Versioned::Legacy(Push::Game(Game::Updated {
+                    game: GameRecord {
+                        deleted: true,
+                        ..GameRecord::from(game)
+                    },
+                    updates: legacy(updates),
+                })))
+            }
+            Self::Game(push::Game::PointsRecorded { .. })
+            | Self::Season(_)
+            | Self::Tournament(_)
+            | Self::Achievement(_)
+            | Self::Streak(_)
+            | Self::Live(_) => None,
+        }
+    }
+}
+
+fn legacy(updates: Vec<types::GameTuple>) -> Vec<GameTuple> {
+    updates.into_iter().map(Into::into).collect()
+}
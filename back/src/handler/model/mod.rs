@@ -1,4 +1,5 @@
 pub mod error;
+pub mod legacy;
 pub mod push;
 pub mod request;
 pub mod response;
@@ -5,6 +5,60 @@ use crate::types;
 pub enum Push {
     Player(Player),
     Game(Game),
+    Season(Season),
+    Tournament(Tournament),
+    Achievement(Achievement),
+    Streak(Streak),
+    Live(LiveGame),
+}
+
+impl Push {
+    pub fn matches(&self, topic: Topic) -> bool {
+        match self {
+            Self::Player(Player::Renamed { .. }) => topic == Topic::Players,
+            Self::Player(Player::Invited(_) | Player::Uninvited(_)) => topic == Topic::Invites,
+            Self::Player(Player::Joined(_)) => matches!(topic, Topic::Players | Topic::Invites),
+            Self::Game(
+                Game::Registered { game, updates }
+                | Game::Updated { game, updates }
+                | Game::Undone { game, updates },
+            ) => match topic {
+                Topic::Games => true,
+                Topic::GamesOf(player) => {
+                    game.player_one == player
+                        || game.player_two == player
+                        || involves(updates, player)
+                }
+                _ => false,
+            },
+            Self::Game(Game::PointsRecorded {
+                player_one,
+                player_two,
+                ..
+            }) => match topic {
+                Topic::Games => true,
+                Topic::GamesOf(player) => *player_one == player || *player_two == player,
+                _ => false,
+            },
+            // Resets change the ratings of the games played since the season started
+            Self::Season(Season::Created { updates, .. } | Season::Deleted { updates, .. }) => {
+                match topic {
+                    Topic::Seasons => true,
+                    Topic::Games => !updates.is_empty(),
+                    Topic::GamesOf(player) => involves(updates, player),
+                    _ => false,
+                }
+            }
+            Self::Tournament(_) => topic == Topic::Tournaments,
+            Self::Achievement(_) => topic == Topic::Achievements,
+            Self::Streak(_) => topic == Topic::Streaks,
+            Self::Live(_) => topic == Topic::Live,
+        }
+    }
+}
+
+fn involves(updates: &[types::GameTuple], player: types::Id) -> bool {
+    updates.iter().any(|u| u.1 == player || u.2 == player)
 }
 
 impl std::fmt::Display for Push {
@@ -19,6 +73,31 @@ impl std::fmt::Display for Push {
             Self::Game(resource) => match resource {
                 Game::Registered { .. } => f.write_str("Game::Registered"),
                 Game::Updated { .. } => f.write_str("Game::Updated"),
+                Game::Undone { .. } => f.write_str("Game::Undone"),
+                Game::PointsRecorded { .. } => f.write_str("Game::PointsRecorded"),
+            },
+            Self::Season(resource) => match resource {
+                Season::Created { .. } => f.write_str("Season::Created"),
+                Season::Deleted { .. } => f.write_str("Season::Deleted"),
+            },
+            Self::Tournament(resource) => match resource {
+                Tournament::Created { .. } => f.write_str("Tournament::Created"),
+                Tournament::Updated { .. } => f.write_str("Tournament::Updated"),
+                Tournament::Deleted(_) => f.write_str("Tournament::Deleted"),
+            },
+            Self::Achievement(resource) => match resource {
+                Achievement::Updated { .. } => f.write_str("Achievement::Updated"),
+            },
+            Self::Streak(resource) => match resource {
+                Streak::Started(_) => f.write_str("Streak::Started"),
+                Streak::Extended(_) => f.write_str("Streak::Extended"),
+                Streak::Broken(_) => f.write_str("Streak::Broken"),
+            },
+            Self::Live(resource) => match resource {
+                LiveGame::Started(_) => f.write_str("Live::Started"),
+                LiveGame::Updated(_) => f.write_str("Live::Updated"),
+                LiveGame::Cancelled(_) => f.write_str("Live::Cancelled"),
+                LiveGame::Finished { .. } => f.write_str("Live::Finished"),
             },
         }
     }
@@ -48,4 +127,101 @@ pub enum Game {
         game: types::Game,
         updates: Vec<types::GameTuple>,
     },
+    Undone {
+        game: types::Game,
+        updates: Vec<types::GameTuple>,
+    },
+    PointsRecorded {
+        game: types::Id,
+        player_one: types::Id,
+        player_two: types::Id,
+    },
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Season {
+    Created {
+        season: types::Season,
+        updates: Vec<types::GameTuple>,
+    },
+    Deleted {
+        season: types::Season,
+        updates: Vec<types::GameTuple>,
+    },
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Tournament {
+    Created {
+        tournament: types::Tournament,
+        fixtures: Vec<types::FixtureTuple>,
+    },
+    Updated {
+        tournament: types::Tournament,
+        fixtures: Vec<types::FixtureTuple>,
+    },
+    Deleted(types::Tournament),
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Achievement {
+    Updated {
+        unlocked: Vec<types::AchievementTuple>,
+        revoked: Vec<types::AchievementTuple>,
+    },
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Streak {
+    Started(types::Streak),
+    Extended(types::Streak),
+    // Carries the streak as it was before being broken
+    Broken(types::Streak),
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LiveGame {
+    Started(types::LiveGame),
+    Updated(types::LiveGame),
+    Cancelled(types::LiveGame),
+    Finished {
+        live: types::LiveGame,
+        game: types::Id,
+    },
+}
+
+#[derive(
+    Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, serde::Serialize, serde::Deserialize,
+)]
+#[serde(rename_all = "camelCase")]
+pub enum Topic {
+    Players,
+    Invites,
+    Games,
+    // Only the games involving the given player
+    GamesOf(types::Id),
+    Live,
+    Seasons,
+    Tournaments,
+    Achievements,
+    Streaks,
+}
+
+impl Topic {
+    // Clients get every push until they unsubscribe from it
+    pub const DEFAULT: [Self; 8] = [
+        Self::Players,
+        Self::Invites,
+        Self::Games,
+        Self::Live,
+        Self::Seasons,
+        Self::Tournaments,
+        Self::Achievements,
+        Self::Streaks,
+    ];
 }
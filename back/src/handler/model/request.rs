@@ -7,6 +7,13 @@ pub enum Request {
     Player(Player),
     Invite(Invite),
     Game(Game),
+    Leaderboard(Leaderboard),
+    Season(Season),
+    Tournament(Tournament),
+    Achievement(Achievement),
+    Audit(Audit),
+    LiveGame(LiveGame),
+    Subscription(Subscription),
 }
 
 impl std::fmt::Display for Request {
@@ -17,6 +24,9 @@ impl std::fmt::Display for Request {
                 Player::Id => f.write_str("Player::Id"),
                 Player::List => f.write_str("Player::List"),
                 Player::Rename(_) => f.write_str("Player::Renmae"),
+                Player::RatingHistory { .. } => f.write_str("Player::RatingHistory"),
+                Player::SuggestOpponents => f.write_str("Player::SuggestOpponents"),
+                Player::Streaks { .. } => f.write_str("Player::Streaks"),
             },
             Self::Invite(resource) => match resource {
                 Invite::List => f.write_str("Invite::List"),
@@ -27,20 +37,132 @@ impl std::fmt::Display for Request {
             },
             Self::Game(resource) => match resource {
                 Game::List => f.write_str("Game::List"),
+                Game::ListVenue(_) => f.write_str("Game::ListVenue"),
+                Game::Venues => f.write_str("Game::Venues"),
                 Game::Register { .. } => f.write_str("Game::Register"),
-                Game::Update(_) => f.write_str("Game::Update"),
+                Game::Update { .. } => f.write_str("Game::Update"),
+                Game::Revert { .. } => f.write_str("Game::Revert"),
+                Game::Undo(_) => f.write_str("Game::Undo"),
                 Game::History(_) => f.write_str("Game::History"),
+                Game::Record { .. } => f.write_str("Game::Record"),
+                Game::Points(_) => f.write_str("Game::Points"),
+                Game::Preview { .. } => f.write_str("Game::Preview"),
+            },
+            Self::Leaderboard(resource) => match resource {
+                Leaderboard::Get { .. } => f.write_str("Leaderboard::Get"),
+                Leaderboard::AllTime => f.write_str("Leaderboard::AllTime"),
+                Leaderboard::Simulate { .. } => f.write_str("Leaderboard::Simulate"),
+            },
+            Self::Season(resource) => match resource {
+                Season::List => f.write_str("Season::List"),
+                Season::Create { .. } => f.write_str("Season::Create"),
+                Season::Delete(_) => f.write_str("Season::Delete"),
+                Season::Games(_) => f.write_str("Season::Games"),
+                Season::Standings(_) => f.write_str("Season::Standings"),
+            },
+            Self::Tournament(resource) => match resource {
+                Tournament::List => f.write_str("Tournament::List"),
+                Tournament::Create { .. } => f.write_str("Tournament::Create"),
+                Tournament::Delete(_) => f.write_str("Tournament::Delete"),
+                Tournament::Fixtures(_) => f.write_str("Tournament::Fixtures"),
+                Tournament::Link { .. } => f.write_str("Tournament::Link"),
+            },
+            Self::Achievement(resource) => match resource {
+                Achievement::List => f.write_str("Achievement::List"),
+            },
+            Self::Audit(resource) => match resource {
+                Audit::List { .. } => f.write_str("Audit::List"),
+            },
+            Self::LiveGame(resource) => match resource {
+                LiveGame::List => f.write_str("LiveGame::List"),
+                LiveGame::Start { .. } => f.write_str("LiveGame::Start"),
+                LiveGame::Point { .. } => f.write_str("LiveGame::Point"),
+                LiveGame::Undo => f.write_str("LiveGame::Undo"),
+                LiveGame::Cancel => f.write_str("LiveGame::Cancel"),
+                LiveGame::Finish => f.write_str("LiveGame::Finish"),
+            },
+            Self::Subscription(resource) => match resource {
+                Subscription::List => f.write_str("Subscription::List"),
+                Subscription::Subscribe(_) => f.write_str("Subscription::Subscribe"),
+                Subscription::Unsubscribe(_) => f.write_str("Subscription::Unsubscribe"),
             },
         }
     }
 }
 
+impl Request {
+    // Whether the request changes the store or the live games
+    pub fn writes(&self) -> bool {
+        match self {
+            Self::Version
+            | Self::Player(
+                Player::Id
+                | Player::List
+                | Player::RatingHistory { .. }
+                | Player::SuggestOpponents
+                | Player::Streaks { .. },
+            )
+            | Self::Invite(Invite::List)
+            | Self::Game(
+                Game::List
+                | Game::ListVenue(_)
+                | Game::Venues
+                | Game::History(_)
+                | Game::Points(_)
+                | Game::Preview { .. },
+            )
+            | Self::Leaderboard(_)
+            | Self::Season(Season::List | Season::Games(_) | Season::Standings(_))
+            | Self::Tournament(Tournament::List | Tournament::Fixtures(_))
+            | Self::Achievement(_)
+            | Self::Audit(_)
+            | Self::LiveGame(LiveGame::List)
+            | Self::Subscription(_) => false,
+            Self::Player(Player::Rename(_))
+            | Self::Invite(
+                Invite::Player { .. } | Invite::Cancel(_) | Invite::Accept | Invite::Reject,
+            )
+            | Self::Game(
+                Game::Register { .. }
+                | Game::Update { .. }
+                | Game::Revert { .. }
+                | Game::Undo(_)
+                | Game::Record { .. },
+            )
+            | Self::Season(Season::Create { .. } | Season::Delete(_))
+            | Self::Tournament(
+                Tournament::Create { .. } | Tournament::Delete(_) | Tournament::Link { .. },
+            )
+            | Self::LiveGame(
+                LiveGame::Start { .. }
+                | LiveGame::Point { .. }
+                | LiveGame::Undo
+                | LiveGame::Cancel
+                | LiveGame::Finish,
+            ) => true,
+        }
+    }
+}
+
 #[derive(Debug, Clone, serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub enum Player {
     Id,
     List,
     Rename(String),
+    #[serde(rename_all = "camelCase")]
+    // Both can be narrowed down to the games of a season
+    RatingHistory {
+        id: types::Id,
+        from: Option<types::Millis>,
+        to: Option<types::Millis>,
+        resolution: types::Resolution,
+        season: Option<types::Id>,
+    },
+    SuggestOpponents,
+    Streaks {
+        season: Option<types::Id>,
+    },
 }
 
 #[derive(Debug, Clone, serde::Deserialize)]
@@ -57,6 +179,8 @@ pub enum Invite {
 #[serde(rename_all = "camelCase")]
 pub enum Game {
     List,
+    ListVenue(String),
+    Venues,
     #[serde(rename_all = "camelCase")]
     Register {
         player: types::Id,
@@ -65,8 +189,144 @@ pub enum Game {
         opponent_score: u8,
         challenge: bool,
         millis: types::Millis,
+        comment: Option<String>,
+        venue: Option<String>,
+        first_server: Option<types::Id>,
     },
-    #[serde(rename_all = "camelCase")]
-    Update(types::Game),
+    #[serde(deserialize_with = "update")]
+    Update {
+        game: types::Game,
+        reason: Option<String>,
+    },
+    Revert {
+        game: types::Id,
+        history: types::Id,
+    },
+    Undo(types::Id),
     History(types::Id),
+    Record {
+        game: types::Id,
+        points: Vec<types::Point>,
+    },
+    Points(types::Id),
+    #[serde(rename_all = "camelCase")]
+    Preview {
+        player: types::Id,
+        opponent: types::Id,
+        millis: types::Millis,
+        challenge: bool,
+    },
+}
+
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Leaderboard {
+    Get {
+        at: Option<types::Millis>,
+    },
+    AllTime,
+    #[serde(rename_all = "camelCase")]
+    Simulate {
+        k_factor: Option<f64>,
+        challenge_multiplier: Option<f64>,
+    },
+}
+
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Season {
+    List,
+    Create {
+        name: String,
+        start: types::Millis,
+        end: types::Millis,
+        reset: f64,
+    },
+    Delete(types::Id),
+    Games(types::Id),
+    Standings(types::Id),
+}
+
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Tournament {
+    List,
+    Create {
+        name: String,
+        format: types::Format,
+        players: Vec<types::Id>,
+        #[serde(default)]
+        groups: i64,
+        #[serde(default)]
+        advancing: i64,
+    },
+    Delete(types::Id),
+    Fixtures(types::Id),
+    Link {
+        fixture: types::Id,
+        game: types::Id,
+    },
+}
+
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Achievement {
+    List,
+}
+
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Audit {
+    #[serde(rename_all = "camelCase")]
+    List {
+        actor: Option<types::Id>,
+        entity: Option<types::Entity>,
+        entity_id: Option<types::Id>,
+    },
+}
+
+// Live games are kept by the player scoring them, so each player can only score one at a time
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LiveGame {
+    List,
+    Start {
+        player: types::Id,
+        opponent: types::Id,
+    },
+    Point {
+        side: types::Side,
+    },
+    Undo,
+    Cancel,
+    Finish,
+}
+
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Subscription {
+    List,
+    Subscribe(Vec<super::push::Topic>),
+    Unsubscribe(Vec<super::push::Topic>),
+}
+
+// Clients before the protocol that attributed edits send the bare game, without a reason
+fn update<'de, D>(deserializer: D) -> Result<(types::Game, Option<String>), D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(serde::Deserialize)]
+    #[serde(untagged)]
+    enum Update {
+        Reasoned {
+            game: types::Game,
+            reason: Option<String>,
+        },
+        Bare(types::Game),
+    }
+
+    match <Update as serde::Deserialize>::deserialize(deserializer)? {
+        Update::Reasoned { game, reason } => Ok((game, reason)),
+        Update::Bare(game) => Ok((game, None)),
+    }
 }
@@ -5,6 +5,7 @@ use crate::types;
 pub enum Response {
     Version {
         server: u32,
+        protocol: u32,
         data: u32,
     },
     User {
@@ -14,7 +15,39 @@ pub enum Response {
     },
     Players(Vec<types::PlayerTuple>),
     Games(Vec<types::GameTuple>),
+    Venues(Vec<String>),
     History(Vec<types::HistoryTuple>),
+    Points(types::Points),
+    RatingHistory(Vec<types::RatingPointTuple>),
+    Suggestions(Vec<types::SuggestionTuple>),
+    Streaks(Vec<types::StreakTuple>),
     Invites(Vec<types::InviteTuple>),
+    Seasons(Vec<types::SeasonTuple>),
+    Tournaments(Vec<types::TournamentTuple>),
+    Fixtures(Vec<types::FixtureTuple>),
+    Achievements(Vec<types::AchievementTuple>),
+    Audit(Vec<types::AuditTuple>),
+    LiveGames(Vec<types::LiveGame>),
+    Topics(Vec<super::push::Topic>),
+    #[serde(rename_all = "camelCase")]
+    Preview {
+        rating: f64,
+        opponent_rating: f64,
+        probability: f64,
+        win: f64,
+        loss: f64,
+    },
+    #[serde(rename_all = "camelCase")]
+    Leaderboard {
+        min_games: i64,
+        standings: Vec<types::StandingTuple>,
+    },
+    #[serde(rename_all = "camelCase")]
+    Simulation {
+        min_games: i64,
+        parameters: types::RatingParameters,
+        comparisons: Vec<types::ComparisonTuple>,
+        movers: Vec<types::Id>,
+    },
     Done,
 }
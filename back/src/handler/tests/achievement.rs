@@ -0,0 +1,323 @@
+use super::{super::model, *};
+use crate::types;
+
+use sqlx::sqlite::SqliteConnectOptions;
+use sqlx::sqlite::SqlitePoolOptions;
+
+type Unlock = (types::Id, types::AchievementKind, types::Id);
+
+fn achievements(pushes: Vec<model::Push>) -> Vec<model::push::Achievement> {
+    pushes
+        .into_iter()
+        .filter_map(|p| match p {
+            model::Push::Achievement(achievement) => Some(achievement),
+            _ => None,
+        })
+        .collect()
+}
+
+fn changes(pushes: Vec<model::Push>) -> (Vec<Unlock>, Vec<Unlock>) {
+    let to_unlock = |achievements: Vec<types::AchievementTuple>| {
+        let mut unlocks = achievements
+            .into_iter()
+            .map(|a| (a.1, a.2, a.3))
+            .collect::<Vec<_>>();
+        unlocks.sort_unstable();
+        unlocks
+    };
+
+    let mut pushes = achievements(pushes).into_iter();
+    let Some(model::push::Achievement::Updated { unlocked, revoked }) = pushes.next() else {
+        panic!()
+    };
+    assert!(pushes.next().is_none());
+
+    (to_unlock(unlocked), to_unlock(revoked))
+}
+
+#[sqlx::test]
+async fn unlock_and_revoke(pool: SqlitePoolOptions, conn: SqliteConnectOptions) {
+    let (player, store, mut handler, _) = init!(pool, conn);
+
+    let accepted = handler
+        .invite_full(&player, &store, ACCEPTED_NAME, ACCEPTED_EMAIL)
+        .await
+        .unwrap();
+
+    let first = handler
+        .register((player.id, accepted.id), (11, 0), false, super::now())
+        .await
+        .unwrap();
+
+    assert_eq!(
+        changes(handler.side_effects().unwrap()),
+        (
+            vec![
+                (player.id, types::AchievementKind::FirstWin, first.id),
+                (player.id, types::AchievementKind::Shutout, first.id),
+            ],
+            vec![]
+        )
+    );
+
+    let second = handler
+        .register(
+            (accepted.id, player.id),
+            (12, 10),
+            false,
+            types::Millis::from(i64::from(super::now()) + 1),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(
+        changes(handler.side_effects().unwrap()),
+        (
+            vec![
+                (accepted.id, types::AchievementKind::FirstWin, second.id),
+                (accepted.id, types::AchievementKind::BeatTheBest, second.id),
+                (accepted.id, types::AchievementKind::TieBreak, second.id),
+            ],
+            vec![]
+        )
+    );
+
+    let listed = store
+        .achievements()
+        .list()
+        .await
+        .unwrap()
+        .into_iter()
+        .map(Into::into)
+        .collect::<Vec<types::AchievementTuple>>();
+    assert_eq!(listed.len(), 5);
+
+    handler
+        .call(
+            model::Request::Achievement(model::request::Achievement::List),
+            false,
+        )
+        .await
+        .ok(model::Response::Achievements(listed))
+        .unwrap()
+        .none()
+        .unwrap()
+        .none()
+        .unwrap();
+
+    let model::Push::Game(model::push::Game::Updated { .. }) = handler
+        .call(
+            model::Request::Game(model::request::Game::Update {
+                game: types::Game {
+                    deleted: true,
+                    ..first
+                },
+                reason: None,
+            }),
+            true,
+        )
+        .await
+        .done()
+        .unwrap()
+        .none()
+        .unwrap()
+        .some()
+        .unwrap()
+    else {
+        panic!()
+    };
+
+    assert_eq!(
+        changes(handler.side_effects().unwrap()),
+        (
+            vec![],
+            vec![
+                (player.id, types::AchievementKind::FirstWin, first.id),
+                (player.id, types::AchievementKind::Shutout, first.id),
+                (accepted.id, types::AchievementKind::BeatTheBest, second.id),
+            ]
+        )
+    );
+
+    handler
+        .register(
+            (accepted.id, player.id),
+            (11, 5),
+            false,
+            types::Millis::from(i64::from(super::now()) + 2),
+        )
+        .await
+        .unwrap();
+
+    assert!(achievements(handler.side_effects().unwrap()).is_empty());
+}
+
+#[sqlx::test]
+async fn win_streak(pool: SqlitePoolOptions, conn: SqliteConnectOptions) {
+    let (player, store, mut handler, _) = init!(pool, conn);
+
+    let accepted = handler
+        .invite_full(&player, &store, ACCEPTED_NAME, ACCEPTED_EMAIL)
+        .await
+        .unwrap();
+
+    let start = i64::from(super::now()) - 20;
+    let mut games = Vec::with_capacity(10);
+    let mut side_effects = Vec::new();
+    for i in 0..10 {
+        games.push(
+            handler
+                .register(
+                    (player.id, accepted.id),
+                    (11, 9),
+                    false,
+                    types::Millis::from(start + i),
+                )
+                .await
+                .unwrap(),
+        );
+        side_effects.extend(handler.side_effects().unwrap());
+    }
+
+    let streaks = achievements(side_effects)
+        .into_iter()
+        .flat_map(|model::push::Achievement::Updated { unlocked, .. }| unlocked)
+        .filter(|a| a.2 == types::AchievementKind::WinStreak)
+        .map(|a| (a.1, a.3))
+        .collect::<Vec<_>>();
+    assert_eq!(streaks, vec![(player.id, games[9].id)]);
+
+    // Breaking the streak in the middle revokes it
+    handler
+        .register(
+            (accepted.id, player.id),
+            (11, 9),
+            false,
+            types::Millis::from(start + 5),
+        )
+        .await
+        .unwrap();
+
+    let (_, revoked) = changes(handler.side_effects().unwrap());
+    assert!(revoked.contains(&(player.id, types::AchievementKind::WinStreak, games[9].id)));
+
+    // Evaluating from the start agrees with evaluating from the change
+    let (unlocked, revoked) = store.achievements().evaluate(None).await.unwrap();
+    assert!(unlocked.is_empty());
+    assert!(revoked.is_empty());
+}
+
+#[sqlx::test]
+async fn season_resets(pool: SqlitePoolOptions, conn: SqliteConnectOptions) {
+    const DAY: i64 = 24 * 60 * 60 * 1000;
+
+    let (player, store, mut handler, pool) = init!(pool, conn);
+    super::set_admin(&pool, player.id).await.unwrap();
+
+    let accepted = handler
+        .invite_full(&player, &store, ACCEPTED_NAME, ACCEPTED_EMAIL)
+        .await
+        .unwrap();
+    let other = handler
+        .invite_full(&player, &store, INVITED_NAME, INVITED_EMAIL)
+        .await
+        .unwrap();
+
+    handler
+        .register(
+            (other.id, accepted.id),
+            (11, 5),
+            false,
+            types::Millis::from(DAY),
+        )
+        .await
+        .unwrap();
+    handler.side_effects().unwrap();
+
+    let game = handler
+        .register(
+            (player.id, other.id),
+            (11, 5),
+            false,
+            types::Millis::from(3 * DAY),
+        )
+        .await
+        .unwrap();
+
+    let (unlocked, _) = changes(handler.side_effects().unwrap());
+    assert!(unlocked.contains(&(player.id, types::AchievementKind::BeatTheBest, game.id)));
+
+    // Resetting the ratings before the game makes its loser no better than anyone else
+    let model::Push::Season(model::push::Season::Created { season, .. }) = handler
+        .call(
+            model::Request::Season(model::request::Season::Create {
+                name: String::from("season"),
+                start: types::Millis::from(2 * DAY),
+                end: types::Millis::from(4 * DAY),
+                reset: 1.0,
+            }),
+            true,
+        )
+        .await
+        .done()
+        .unwrap()
+        .none()
+        .unwrap()
+        .some()
+        .unwrap()
+    else {
+        panic!()
+    };
+
+    assert_eq!(
+        changes(handler.side_effects().unwrap()),
+        (
+            vec![],
+            vec![(player.id, types::AchievementKind::BeatTheBest, game.id)]
+        )
+    );
+
+    let model::Push::Season(model::push::Season::Deleted { .. }) = handler
+        .call(
+            model::Request::Season(model::request::Season::Delete(season.id)),
+            true,
+        )
+        .await
+        .done()
+        .unwrap()
+        .none()
+        .unwrap()
+        .some()
+        .unwrap()
+    else {
+        panic!()
+    };
+
+    assert_eq!(
+        changes(handler.side_effects().unwrap()),
+        (
+            vec![(player.id, types::AchievementKind::BeatTheBest, game.id)],
+            vec![]
+        )
+    );
+}
+
+#[sqlx::test]
+async fn forbidden(pool: SqlitePoolOptions, conn: SqliteConnectOptions) {
+    let (_, store, mut handler, _) = init!(pool, conn);
+
+    let invited = handler.invite(INVITED_NAME, INVITED_EMAIL).await.unwrap();
+
+    let mut handler = framework::Handler::pending(&invited.email, &store)
+        .await
+        .unwrap();
+
+    handler
+        .call(
+            model::Request::Achievement(model::request::Achievement::List),
+            false,
+        )
+        .await
+        .err(model::Error::Forbidden)
+        .unwrap();
+}
@@ -0,0 +1,555 @@
+use super::{super::model, *};
+use crate::types;
+
+use sqlx::sqlite::SqliteConnectOptions;
+use sqlx::sqlite::SqlitePoolOptions;
+
+fn list(
+    actor: Option<types::Id>,
+    entity: Option<types::Entity>,
+    entity_id: Option<types::Id>,
+) -> model::Request {
+    model::Request::Audit(model::request::Audit::List {
+        actor,
+        entity,
+        entity_id,
+    })
+}
+
+fn entries(response: model::Response) -> Vec<types::Audit> {
+    match response {
+        model::Response::Audit(entries) => entries.into_iter().map(Into::into).collect(),
+        r => panic!("{r:?}"),
+    }
+}
+
+#[sqlx::test]
+async fn players_and_invites(pool: SqlitePoolOptions, conn: SqliteConnectOptions) {
+    let (player, store, mut handler, pool) = init!(pool, conn);
+    super::set_admin(&pool, player.id).await.unwrap();
+
+    let invited = handler.invite(INVITED_NAME, INVITED_EMAIL).await.unwrap();
+
+    let model::Push::Player(model::push::Player::Uninvited(_)) = handler
+        .call(
+            model::Request::Invite(model::request::Invite::Cancel(invited.id)),
+            true,
+        )
+        .await
+        .done()
+        .unwrap()
+        .none()
+        .unwrap()
+        .some()
+        .unwrap()
+    else {
+        panic!()
+    };
+
+    let accepted = handler
+        .invite_full(&player, &store, ACCEPTED_NAME, ACCEPTED_EMAIL)
+        .await
+        .unwrap();
+
+    let model::Push::Player(model::push::Player::Renamed { .. }) = handler
+        .call(
+            model::Request::Player(model::request::Player::Rename(String::from("new"))),
+            true,
+        )
+        .await
+        .done()
+        .unwrap()
+        .none()
+        .unwrap()
+        .some()
+        .unwrap()
+    else {
+        panic!()
+    };
+
+    let all = entries(
+        handler
+            .call(list(None, None, None), false)
+            .await
+            .raw()
+            .unwrap(),
+    );
+
+    assert_eq!(
+        all.iter()
+            .map(|e| (e.actor, e.action, e.entity))
+            .collect::<Vec<_>>(),
+        vec![
+            (
+                Some(player.id),
+                types::AuditAction::Rename,
+                types::Entity::Player
+            ),
+            (
+                Some(accepted.id),
+                types::AuditAction::AcceptInvite,
+                types::Entity::Invite
+            ),
+            (
+                Some(player.id),
+                types::AuditAction::Invite,
+                types::Entity::Invite
+            ),
+            (
+                Some(player.id),
+                types::AuditAction::CancelInvite,
+                types::Entity::Invite
+            ),
+            (
+                Some(player.id),
+                types::AuditAction::Invite,
+                types::Entity::Invite
+            ),
+        ]
+    );
+
+    let rename = &all[0];
+    let before = serde_json::from_str::<types::Player>(rename.before.as_ref().unwrap()).unwrap();
+    let after = serde_json::from_str::<types::Player>(rename.after.as_ref().unwrap()).unwrap();
+    assert_eq!(before.name, TESTER_NAME);
+    assert_eq!(after.name, "new");
+
+    handler
+        .call(list(Some(accepted.id), None, None), false)
+        .await
+        .map_ok(entries, vec![all[1].clone()])
+        .unwrap()
+        .none()
+        .unwrap()
+        .none()
+        .unwrap();
+
+    handler
+        .call(
+            list(None, Some(types::Entity::Invite), Some(invited.id)),
+            false,
+        )
+        .await
+        .map_ok(entries, vec![all[3].clone(), all[4].clone()])
+        .unwrap()
+        .none()
+        .unwrap()
+        .none()
+        .unwrap();
+
+    let cancelled = serde_json::from_str::<types::Invite>(all[3].before.as_ref().unwrap()).unwrap();
+    assert_eq!(cancelled, invited);
+    assert!(all[3].after.is_none());
+
+    handler
+        .call(list(None, Some(types::Entity::Season), None), false)
+        .await
+        .map_ok(entries, vec![])
+        .unwrap()
+        .none()
+        .unwrap()
+        .none()
+        .unwrap();
+}
+
+#[sqlx::test]
+async fn admin_actions(pool: SqlitePoolOptions, conn: SqliteConnectOptions) {
+    let (player, _, mut handler, pool) = init!(pool, conn);
+    super::set_admin(&pool, player.id).await.unwrap();
+
+    let model::Push::Season(model::push::Season::Created { season, .. }) = handler
+        .call(
+            model::Request::Season(model::request::Season::Create {
+                name: String::from("season"),
+                start: types::Millis::from(0),
+                end: types::Millis::from(1000),
+                reset: 1.0,
+            }),
+            true,
+        )
+        .await
+        .done()
+        .unwrap()
+        .none()
+        .unwrap()
+        .some()
+        .unwrap()
+    else {
+        panic!()
+    };
+    handler.side_effects().unwrap();
+
+    let model::Push::Season(model::push::Season::Deleted { .. }) = handler
+        .call(
+            model::Request::Season(model::request::Season::Delete(season.id)),
+            true,
+        )
+        .await
+        .done()
+        .unwrap()
+        .none()
+        .unwrap()
+        .some()
+        .unwrap()
+    else {
+        panic!()
+    };
+    handler.side_effects().unwrap();
+
+    let snapshot = Some(serde_json::to_string(&season).unwrap());
+
+    handler
+        .call(
+            list(
+                Some(player.id),
+                Some(types::Entity::Season),
+                Some(season.id),
+            ),
+            false,
+        )
+        .await
+        .map_ok(
+            |r| {
+                entries(r)
+                    .into_iter()
+                    .map(|e| (e.action, e.before, e.after))
+                    .collect::<Vec<_>>()
+            },
+            vec![
+                (types::AuditAction::DeleteSeason, snapshot.clone(), None),
+                (types::AuditAction::CreateSeason, None, snapshot),
+            ],
+        )
+        .unwrap()
+        .none()
+        .unwrap()
+        .none()
+        .unwrap();
+}
+
+#[sqlx::test]
+async fn tournament_actions(pool: SqlitePoolOptions, conn: SqliteConnectOptions) {
+    let (player, store, mut handler, pool) = init!(pool, conn);
+    super::set_admin(&pool, player.id).await.unwrap();
+
+    let accepted = handler
+        .invite_full(&player, &store, ACCEPTED_NAME, ACCEPTED_EMAIL)
+        .await
+        .unwrap();
+
+    let model::Push::Tournament(model::push::Tournament::Created {
+        tournament,
+        fixtures,
+    }) = handler
+        .call(
+            model::Request::Tournament(model::request::Tournament::Create {
+                name: String::from("tournament"),
+                format: types::Format::RoundRobin,
+                players: vec![player.id, accepted.id],
+                groups: 0,
+                advancing: 1,
+            }),
+            true,
+        )
+        .await
+        .done()
+        .unwrap()
+        .none()
+        .unwrap()
+        .some()
+        .unwrap()
+    else {
+        panic!()
+    };
+
+    let fixture = types::Fixture::from(fixtures[0].clone());
+
+    let game = handler
+        .register((player.id, accepted.id), (11, 5), false, now())
+        .await
+        .unwrap();
+    handler.side_effects().unwrap();
+
+    let model::Push::Tournament(model::push::Tournament::Updated { .. }) = handler
+        .call(
+            model::Request::Tournament(model::request::Tournament::Link {
+                fixture: fixture.id,
+                game: game.id,
+            }),
+            true,
+        )
+        .await
+        .done()
+        .unwrap()
+        .none()
+        .unwrap()
+        .some()
+        .unwrap()
+    else {
+        panic!()
+    };
+
+    handler
+        .call(
+            list(None, Some(types::Entity::Tournament), Some(tournament.id)),
+            false,
+        )
+        .await
+        .map_ok(
+            |r| {
+                entries(r)
+                    .into_iter()
+                    .map(|e| (e.actor, e.action, e.before, e.after))
+                    .collect::<Vec<_>>()
+            },
+            vec![(
+                Some(player.id),
+                types::AuditAction::CreateTournament,
+                None,
+                Some(serde_json::to_string(&tournament).unwrap()),
+            )],
+        )
+        .unwrap()
+        .none()
+        .unwrap()
+        .none()
+        .unwrap();
+
+    let linked = entries(
+        handler
+            .call(
+                list(None, Some(types::Entity::Fixture), Some(fixture.id)),
+                false,
+            )
+            .await
+            .raw()
+            .unwrap(),
+    );
+
+    assert_eq!(linked.len(), 1);
+    assert_eq!(linked[0].actor, Some(player.id));
+    assert_eq!(linked[0].action, types::AuditAction::LinkFixture);
+
+    let before =
+        serde_json::from_str::<types::Fixture>(linked[0].before.as_ref().unwrap()).unwrap();
+    let after = serde_json::from_str::<types::Fixture>(linked[0].after.as_ref().unwrap()).unwrap();
+    assert_eq!(before, fixture);
+    assert_eq!(
+        after,
+        types::Fixture {
+            game: Some(game.id),
+            winner: Some(player.id),
+            ..fixture
+        }
+    );
+}
+
+#[sqlx::test]
+async fn game_actions(pool: SqlitePoolOptions, conn: SqliteConnectOptions) {
+    let (player, store, mut handler, _) = init!(pool, conn);
+
+    let accepted = handler
+        .invite_full(&player, &store, ACCEPTED_NAME, ACCEPTED_EMAIL)
+        .await
+        .unwrap();
+
+    let reverted = handler
+        .register((player.id, accepted.id), (11, 5), false, now())
+        .await
+        .unwrap();
+    handler.side_effects().unwrap();
+
+    let model::Push::Game(model::push::Game::Updated { game: edited, .. }) = handler
+        .call(
+            model::Request::Game(model::request::Game::Update {
+                game: types::Game {
+                    score_one: 7,
+                    score_two: 11,
+                    ..reverted.clone()
+                },
+                reason: None,
+            }),
+            true,
+        )
+        .await
+        .done()
+        .unwrap()
+        .none()
+        .unwrap()
+        .some()
+        .unwrap()
+    else {
+        panic!()
+    };
+    handler.side_effects().unwrap();
+
+    let history = store.games().history(reverted.id).await.unwrap();
+
+    let model::Push::Game(model::push::Game::Updated { .. }) = handler
+        .call(
+            model::Request::Game(model::request::Game::Revert {
+                game: reverted.id,
+                history: history[0].id,
+            }),
+            true,
+        )
+        .await
+        .done()
+        .unwrap()
+        .none()
+        .unwrap()
+        .some()
+        .unwrap()
+    else {
+        panic!()
+    };
+    handler.side_effects().unwrap();
+
+    let undone = handler
+        .register(
+            (player.id, accepted.id),
+            (11, 3),
+            false,
+            types::Millis::from(i64::from(now()) + 1),
+        )
+        .await
+        .unwrap();
+    handler.side_effects().unwrap();
+
+    let model::Push::Game(model::push::Game::Undone { .. }) = handler
+        .call(
+            model::Request::Game(model::request::Game::Undo(undone.id)),
+            true,
+        )
+        .await
+        .done()
+        .unwrap()
+        .none()
+        .unwrap()
+        .some()
+        .unwrap()
+    else {
+        panic!()
+    };
+    handler.side_effects().unwrap();
+
+    handler
+        .call(list(None, Some(types::Entity::Game), None), false)
+        .await
+        .map_ok(
+            |r| {
+                entries(r)
+                    .into_iter()
+                    .map(|e| (e.actor, e.action, e.entity_id, e.before, e.after))
+                    .collect::<Vec<_>>()
+            },
+            vec![
+                (
+                    Some(player.id),
+                    types::AuditAction::UndoGame,
+                    undone.id,
+                    Some(serde_json::to_string(&undone).unwrap()),
+                    None,
+                ),
+                (
+                    Some(player.id),
+                    types::AuditAction::RevertGame,
+                    reverted.id,
+                    Some(serde_json::to_string(&edited).unwrap()),
+                    Some(serde_json::to_string(&reverted).unwrap()),
+                ),
+            ],
+        )
+        .unwrap()
+        .none()
+        .unwrap()
+        .none()
+        .unwrap();
+}
+
+#[sqlx::test]
+async fn forbidden(pool: SqlitePoolOptions, conn: SqliteConnectOptions) {
+    let (_, store, mut handler, _) = init!(pool, conn);
+
+    handler
+        .call(list(None, None, None), false)
+        .await
+        .err(model::Error::Forbidden)
+        .unwrap();
+
+    let invited = handler.invite(INVITED_NAME, INVITED_EMAIL).await.unwrap();
+
+    let mut handler = framework::Handler::pending(&invited.email, &store)
+        .await
+        .unwrap();
+
+    handler
+        .call(list(None, None, None), false)
+        .await
+        .err(model::Error::Forbidden)
+        .unwrap();
+}
+
+#[sqlx::test]
+async fn admin_access(pool: SqlitePoolOptions, conn: SqliteConnectOptions) {
+    let (player, store, mut handler, _) = init!(pool, conn);
+
+    handler
+        .call(list(None, None, None), false)
+        .await
+        .err(model::Error::Forbidden)
+        .unwrap();
+
+    assert_eq!(
+        store
+            .players()
+            .set_admin(&player.email, true)
+            .await
+            .unwrap(),
+        player.id
+    );
+
+    handler
+        .call(list(None, None, None), false)
+        .await
+        .map_ok(
+            |r| {
+                entries(r)
+                    .into_iter()
+                    .map(|e| (e.actor, e.action, e.entity_id, e.before, e.after))
+                    .collect::<Vec<_>>()
+            },
+            vec![(
+                None,
+                types::AuditAction::GrantAdmin,
+                player.id,
+                Some(String::from("false")),
+                Some(String::from("true")),
+            )],
+        )
+        .unwrap()
+        .none()
+        .unwrap()
+        .none()
+        .unwrap();
+
+    assert!(matches!(
+        store.players().set_admin("unknown@email.com", true).await,
+        Err(store::Error::NotFound)
+    ));
+
+    assert_eq!(
+        store
+            .players()
+            .set_admin(&player.email, false)
+            .await
+            .unwrap(),
+        player.id
+    );
+
+    handler
+        .call(list(None, None, None), false)
+        .await
+        .err(model::Error::Forbidden)
+        .unwrap();
+}
@@ -0,0 +1,106 @@
+use super::{super::model, *};
+use crate::store;
+
+use sqlx::sqlite::SqliteConnectOptions;
+use sqlx::sqlite::SqlitePoolOptions;
+
+fn rename(name: &str) -> model::Request {
+    model::Request::Player(model::request::Player::Rename(String::from(name)))
+}
+
+#[sqlx::test]
+async fn stop_on_error(pool: SqlitePoolOptions, conn: SqliteConnectOptions) {
+    let (player, store, mut handler, _) = init!(pool, conn);
+
+    let results = handler
+        .batch(
+            vec![rename(""), rename("new"), model::Request::Version],
+            true,
+        )
+        .await;
+
+    assert!(matches!(
+        results.as_slice(),
+        [
+            Err(model::Error::Store(store::Error::BlankValue("name"))),
+            Err(model::Error::Skipped),
+            Err(model::Error::Skipped),
+        ]
+    ));
+
+    let results = handler
+        .batch(vec![model::Request::Version, rename("new")], true)
+        .await;
+
+    assert!(matches!(
+        results.as_slice(),
+        [
+            Ok(model::Response::Version { .. }),
+            Ok(model::Response::Done)
+        ]
+    ));
+
+    assert!(matches!(
+        handler.push().unwrap(),
+        model::Push::Player(model::push::Player::Renamed { .. })
+    ));
+
+    // Writes cannot be undone, so none can be followed by a request that might fail
+    let results = handler
+        .batch(vec![rename("newer"), rename(""), rename("newest")], true)
+        .await;
+
+    assert!(matches!(
+        results.as_slice(),
+        [
+            Err(model::Error::WriteNotLast),
+            Err(model::Error::WriteNotLast),
+            Err(model::Error::Skipped),
+        ]
+    ));
+
+    let results = handler
+        .batch(vec![model::Request::Version, rename("")], true)
+        .await;
+
+    assert!(matches!(
+        results.as_slice(),
+        [
+            Ok(model::Response::Version { .. }),
+            Err(model::Error::Store(store::Error::BlankValue("name"))),
+        ]
+    ));
+
+    assert_eq!(
+        store
+            .players()
+            .list()
+            .await
+            .unwrap()
+            .into_iter()
+            .find(|p| p.id == player.id)
+            .unwrap()
+            .name,
+        "new"
+    );
+}
+
+#[sqlx::test]
+async fn independent(pool: SqlitePoolOptions, conn: SqliteConnectOptions) {
+    let (_, _, mut handler, _) = init!(pool, conn);
+
+    let results = handler.batch(vec![rename(""), rename("new")], false).await;
+
+    assert!(matches!(
+        results.as_slice(),
+        [
+            Err(model::Error::Store(store::Error::BlankValue("name"))),
+            Ok(model::Response::Done),
+        ]
+    ));
+
+    assert!(matches!(
+        handler.push().unwrap(),
+        model::Push::Player(model::push::Player::Renamed { .. })
+    ));
+}
@@ -9,14 +9,14 @@ where
     A: handler::Access,
 {
     inner: handler::Handler<A, Smtp>,
-    push: tokio::sync::broadcast::Receiver<model::Push>,
+    push: tokio::sync::broadcast::Receiver<(ws::Seq, model::Push)>,
     email: tokio::sync::mpsc::Receiver<smtp::Payload>,
 }
 
 impl Handler<access::Regular> {
     pub async fn new(user: &str, store: &store::Store) -> Result<Self> {
         let broadcaster = broadcaster::Broadcaster::new();
-        let push = broadcaster.subscribe();
+        let (_, push) = broadcaster.subscribe();
         let (smtp, email) = Smtp::new();
 
         let auth = access::Auth::new(store.clone());
@@ -42,7 +42,7 @@ impl Handler<access::Regular> {
 impl Handler<access::Pending> {
     pub async fn pending(user: &str, store: &store::Store) -> Result<Self> {
         let broadcaster = broadcaster::Broadcaster::new();
-        let push = broadcaster.subscribe();
+        let (_, push) = broadcaster.subscribe();
         let (smtp, email) = Smtp::new();
 
         let auth = access::Auth::new(store.clone());
@@ -119,12 +119,101 @@ impl Handler<access::Regular> {
             p => Err(Error::from(p)),
         }
     }
+
+    pub async fn register(
+        &mut self,
+        (player, opponent): (types::Id, types::Id),
+        (score, opponent_score): (u8, u8),
+        challenge: bool,
+        millis: types::Millis,
+    ) -> Result<types::Game> {
+        match self
+            .call(
+                model::Request::Game(model::request::Game::Register {
+                    player,
+                    opponent,
+                    score,
+                    opponent_score,
+                    challenge,
+                    millis,
+                    comment: None,
+                    venue: None,
+                    first_server: None,
+                }),
+                true,
+            )
+            .await
+            .done()?
+            .none()?
+            .some()?
+        {
+            model::Push::Game(model::push::Game::Registered { game, .. }) => Ok(game),
+            p => Err(Error::from(p)),
+        }
+    }
 }
 
 impl<A> Handler<A>
 where
     A: handler::Access,
 {
+    // Achievements and streaks are pushed after the game that caused them, so they are consumed
+    // here once the game push has been verified. Any other push is still unexpected
+    pub fn side_effects(&mut self) -> Result<Vec<model::Push>> {
+        let mut side_effects = Vec::new();
+        while let Some(push) = next_push(&mut self.push)? {
+            match push {
+                model::Push::Achievement(_) | model::Push::Streak(_) => side_effects.push(push),
+                push => return Err(Error::from(push)),
+            }
+        }
+        Ok(side_effects)
+    }
+
+    // Some requests broadcast more than one push, the ones after the first are fetched here
+    pub fn push(&mut self) -> Result<model::Push> {
+        next_push(&mut self.push)?.ok_or(Error::NoMessage)
+    }
+
+    pub fn accepts(&self, push: &model::Push) -> bool {
+        ws::Service::accepts(&self.inner, push)
+    }
+
+    #[must_use]
+    pub fn with_version(mut self, version: u32) -> Self {
+        self.inner = self.inner.with_version(version);
+        self
+    }
+
+    pub fn rejects(&self) -> Option<ws::Close> {
+        ws::Service::rejects(&self.inner)
+    }
+
+    pub fn present_response(
+        &self,
+        response: model::Response,
+    ) -> serde_json::Result<serde_json::Value> {
+        serde_json::to_value(ws::Service::present_response(&self.inner, response))
+    }
+
+    pub fn present_push(&self, push: model::Push) -> serde_json::Result<Option<serde_json::Value>> {
+        ws::Service::present_push(&self.inner, push)
+            .map(serde_json::to_value)
+            .transpose()
+    }
+
+    pub fn closes(&self, push: &model::Push) -> Option<ws::Close> {
+        ws::Service::closes(&self.inner, push)
+    }
+
+    pub async fn batch(
+        &mut self,
+        requests: Vec<model::Request>,
+        stop_on_error: bool,
+    ) -> Vec<std::result::Result<model::Response, model::Error>> {
+        ws::Service::batch(&mut self.inner, requests, stop_on_error).await
+    }
+
     #[must_use]
     pub async fn call(&mut self, request: model::Request, mutable: bool) -> ResponseVerifier<'_> {
         let Ok(model::Response::Version {
@@ -176,7 +265,7 @@ impl<'a> ResponseVerifier<'a> {
     fn new(
         response: std::result::Result<model::Response, model::Error>,
         email: &'a mut tokio::sync::mpsc::Receiver<smtp::Payload>,
-        push: &'a mut tokio::sync::broadcast::Receiver<model::Push>,
+        push: &'a mut tokio::sync::broadcast::Receiver<(ws::Seq, model::Push)>,
     ) -> Self {
         Self {
             response,
@@ -229,7 +318,7 @@ pub struct EmailVerifier<'a> {
 impl<'a> EmailVerifier<'a> {
     fn new(
         email: &'a mut tokio::sync::mpsc::Receiver<smtp::Payload>,
-        push: &'a mut tokio::sync::broadcast::Receiver<model::Push>,
+        push: &'a mut tokio::sync::broadcast::Receiver<(ws::Seq, model::Push)>,
     ) -> Self {
         Self {
             email,
@@ -251,20 +340,16 @@ impl<'a> EmailVerifier<'a> {
 }
 
 pub struct PushVerifier<'a> {
-    push: &'a mut tokio::sync::broadcast::Receiver<model::Push>,
+    push: &'a mut tokio::sync::broadcast::Receiver<(ws::Seq, model::Push)>,
 }
 
 impl<'a> PushVerifier<'a> {
-    fn new(push: &'a mut tokio::sync::broadcast::Receiver<model::Push>) -> Self {
+    fn new(push: &'a mut tokio::sync::broadcast::Receiver<(ws::Seq, model::Push)>) -> Self {
         Self { push }
     }
 
     pub fn some(self) -> Result<model::Push> {
-        match self.push.try_recv() {
-            Ok(p) => Ok(p),
-            Err(tokio::sync::broadcast::error::TryRecvError::Empty) => Err(Error::NoMessage),
-            e @ Err(_) => Err(Error::BadChannel(format!("{e:?}"))),
-        }
+        next_push(self.push)?.ok_or(Error::NoMessage)
     }
 
     pub fn none(self) -> Result {
@@ -272,14 +357,23 @@ impl<'a> PushVerifier<'a> {
     }
 }
 
-fn check_empty_push(push: &mut tokio::sync::broadcast::Receiver<model::Push>) -> Result {
+fn next_push(
+    push: &mut tokio::sync::broadcast::Receiver<(ws::Seq, model::Push)>,
+) -> Result<Option<model::Push>> {
     match push.try_recv() {
-        Err(tokio::sync::broadcast::error::TryRecvError::Empty) => Ok(()),
-        Ok(p) => Err(Error::from(p)),
+        Ok((_, p)) => Ok(Some(p)),
+        Err(tokio::sync::broadcast::error::TryRecvError::Empty) => Ok(None),
         e @ Err(_) => Err(Error::BadChannel(format!("{e:?}"))),
     }
 }
 
+fn check_empty_push(push: &mut tokio::sync::broadcast::Receiver<(ws::Seq, model::Push)>) -> Result {
+    match next_push(push)? {
+        None => Ok(()),
+        Some(p) => Err(Error::from(p)),
+    }
+}
+
 fn check_empty_email(email: &mut tokio::sync::mpsc::Receiver<smtp::Payload>) -> Result {
     match email.try_recv() {
         Err(tokio::sync::mpsc::error::TryRecvError::Empty) => Ok(()),
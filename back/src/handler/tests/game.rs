@@ -1,5 +1,5 @@
 use super::{super::model, *};
-use crate::types;
+use crate::{types, ws};
 
 use sqlx::sqlite::SqliteConnectOptions;
 use sqlx::sqlite::SqlitePoolOptions;
@@ -32,6 +32,9 @@ async fn list(pool: SqlitePoolOptions, conn: SqliteConnectOptions) {
                 opponent_score: 0,
                 challenge: false,
                 millis: super::now(),
+                comment: None,
+                venue: None,
+                first_server: None,
             }),
             true,
         )
@@ -45,6 +48,7 @@ async fn list(pool: SqlitePoolOptions, conn: SqliteConnectOptions) {
     else {
         panic!()
     };
+    handler.side_effects().unwrap();
 
     handler
         .call(model::Request::Game(model::request::Game::List), false)
@@ -75,6 +79,9 @@ async fn register(pool: SqlitePoolOptions, conn: SqliteConnectOptions) {
                 opponent_score: 0,
                 challenge: false,
                 millis: super::now(),
+                comment: None,
+                venue: None,
+                first_server: None,
             }),
             true,
         )
@@ -88,6 +95,7 @@ async fn register(pool: SqlitePoolOptions, conn: SqliteConnectOptions) {
     else {
         panic!()
     };
+    handler.side_effects().unwrap();
 
     assert_eq!(updates.len(), 0);
 
@@ -144,6 +152,9 @@ async fn register_to_other_players(pool: SqlitePoolOptions, conn: SqliteConnectO
                 opponent_score: 0,
                 challenge: false,
                 millis: super::now(),
+                comment: None,
+                venue: None,
+                first_server: None,
             }),
             true,
         )
@@ -157,6 +168,7 @@ async fn register_to_other_players(pool: SqlitePoolOptions, conn: SqliteConnectO
     else {
         panic!()
     };
+    handler.side_effects().unwrap();
 
     assert_eq!(updates.len(), 0);
 
@@ -210,6 +222,9 @@ async fn register_many(pool: SqlitePoolOptions, conn: SqliteConnectOptions) {
                     opponent_score: 0,
                     challenge: false,
                     millis: super::now(),
+                    comment: None,
+                    venue: None,
+                    first_server: None,
                 }),
                 true,
             )
@@ -225,6 +240,7 @@ async fn register_many(pool: SqlitePoolOptions, conn: SqliteConnectOptions) {
         } else {
             panic!()
         }
+        handler.side_effects().unwrap();
     }
 
     handler
@@ -251,6 +267,9 @@ async fn register_not_found(pool: SqlitePoolOptions, conn: SqliteConnectOptions)
                 opponent_score: 0,
                 challenge: false,
                 millis: super::now(),
+                comment: None,
+                venue: None,
+                first_server: None,
             }),
             false,
         )
@@ -272,6 +291,9 @@ async fn register_same_player(pool: SqlitePoolOptions, conn: SqliteConnectOption
                 opponent_score: 0,
                 challenge: false,
                 millis: super::now(),
+                comment: None,
+                venue: None,
+                first_server: None,
             }),
             false,
         )
@@ -301,6 +323,9 @@ async fn register_good_score(pool: SqlitePoolOptions, conn: SqliteConnectOptions
                     opponent_score: score,
                     challenge: false,
                     millis: super::now(),
+                    comment: None,
+                    venue: None,
+                    first_server: None,
                 }),
                 true,
             )
@@ -311,6 +336,7 @@ async fn register_good_score(pool: SqlitePoolOptions, conn: SqliteConnectOptions
             .unwrap()
             .some()
             .unwrap();
+        handler.side_effects().unwrap();
 
         handler
             .call(
@@ -321,6 +347,9 @@ async fn register_good_score(pool: SqlitePoolOptions, conn: SqliteConnectOptions
                     opponent_score: 11,
                     challenge: false,
                     millis: super::now(),
+                    comment: None,
+                    venue: None,
+                    first_server: None,
                 }),
                 true,
             )
@@ -331,6 +360,7 @@ async fn register_good_score(pool: SqlitePoolOptions, conn: SqliteConnectOptions
             .unwrap()
             .some()
             .unwrap();
+        handler.side_effects().unwrap();
     }
 
     handler
@@ -342,6 +372,9 @@ async fn register_good_score(pool: SqlitePoolOptions, conn: SqliteConnectOptions
                 opponent_score: 10,
                 challenge: false,
                 millis: super::now(),
+                comment: None,
+                venue: None,
+                first_server: None,
             }),
             true,
         )
@@ -352,6 +385,7 @@ async fn register_good_score(pool: SqlitePoolOptions, conn: SqliteConnectOptions
         .unwrap()
         .some()
         .unwrap();
+    handler.side_effects().unwrap();
 
     handler
         .call(
@@ -362,6 +396,9 @@ async fn register_good_score(pool: SqlitePoolOptions, conn: SqliteConnectOptions
                 opponent_score: 12,
                 challenge: false,
                 millis: super::now(),
+                comment: None,
+                venue: None,
+                first_server: None,
             }),
             true,
         )
@@ -372,6 +409,7 @@ async fn register_good_score(pool: SqlitePoolOptions, conn: SqliteConnectOptions
         .unwrap()
         .some()
         .unwrap();
+    handler.side_effects().unwrap();
 }
 
 #[sqlx::test]
@@ -404,6 +442,9 @@ async fn register_bad_score(pool: SqlitePoolOptions, conn: SqliteConnectOptions)
                     opponent_score,
                     challenge,
                     millis: super::now(),
+                    comment: None,
+                    venue: None,
+                    first_server: None,
                 }),
                 false,
             )
@@ -445,6 +486,9 @@ async fn register_challenge_daily_limit(pool: SqlitePoolOptions, conn: SqliteCon
                 opponent_score: 0,
                 challenge: true,
                 millis: types::Millis::from(millis),
+                comment: None,
+                venue: None,
+                first_server: None,
             }),
             true,
         )
@@ -458,6 +502,7 @@ async fn register_challenge_daily_limit(pool: SqlitePoolOptions, conn: SqliteCon
     else {
         panic!()
     };
+    handler.side_effects().unwrap();
 
     let model::Push::Game(model::push::Game::Registered { .. }) = handler
         .call(
@@ -468,6 +513,9 @@ async fn register_challenge_daily_limit(pool: SqlitePoolOptions, conn: SqliteCon
                 opponent_score: 0,
                 challenge: false,
                 millis: types::Millis::from(millis + 1),
+                comment: None,
+                venue: None,
+                first_server: None,
             }),
             true,
         )
@@ -481,6 +529,7 @@ async fn register_challenge_daily_limit(pool: SqlitePoolOptions, conn: SqliteCon
     else {
         panic!()
     };
+    handler.side_effects().unwrap();
 
     handler
         .call(
@@ -491,6 +540,9 @@ async fn register_challenge_daily_limit(pool: SqlitePoolOptions, conn: SqliteCon
                 opponent_score: 0,
                 challenge: true,
                 millis: types::Millis::from(millis + 1),
+                comment: None,
+                venue: None,
+                first_server: None,
             }),
             false,
         )
@@ -511,6 +563,9 @@ async fn register_challenge_daily_limit(pool: SqlitePoolOptions, conn: SqliteCon
                 opponent_score: 0,
                 challenge: true,
                 millis: types::Millis::from(millis),
+                comment: None,
+                venue: None,
+                first_server: None,
             }),
             false,
         )
@@ -529,6 +584,9 @@ async fn register_challenge_daily_limit(pool: SqlitePoolOptions, conn: SqliteCon
                 opponent_score: 0,
                 challenge: true,
                 millis: types::Millis::from(millis + 1),
+                comment: None,
+                venue: None,
+                first_server: None,
             }),
             true,
         )
@@ -542,6 +600,7 @@ async fn register_challenge_daily_limit(pool: SqlitePoolOptions, conn: SqliteCon
     else {
         panic!()
     };
+    handler.side_effects().unwrap();
 }
 
 #[sqlx::test]
@@ -569,6 +628,9 @@ async fn edit_challenge_daily_limit(pool: SqlitePoolOptions, conn: SqliteConnect
                 opponent_score: 0,
                 challenge: true,
                 millis: types::Millis::from(millis),
+                comment: None,
+                venue: None,
+                first_server: None,
             }),
             true,
         )
@@ -582,6 +644,7 @@ async fn edit_challenge_daily_limit(pool: SqlitePoolOptions, conn: SqliteConnect
     else {
         panic!()
     };
+    handler.side_effects().unwrap();
 
     let game_one = game;
 
@@ -594,6 +657,9 @@ async fn edit_challenge_daily_limit(pool: SqlitePoolOptions, conn: SqliteConnect
                 opponent_score: 0,
                 challenge: true,
                 millis: types::Millis::from(millis + 1),
+                comment: None,
+                venue: None,
+                first_server: None,
             }),
             true,
         )
@@ -607,6 +673,7 @@ async fn edit_challenge_daily_limit(pool: SqlitePoolOptions, conn: SqliteConnect
     else {
         panic!()
     };
+    handler.side_effects().unwrap();
 
     let model::Push::Game(model::push::Game::Registered { game, .. }) = handler
         .call(
@@ -617,6 +684,9 @@ async fn edit_challenge_daily_limit(pool: SqlitePoolOptions, conn: SqliteConnect
                 opponent_score: 0,
                 challenge: false,
                 millis: types::Millis::from(millis + 2),
+                comment: None,
+                venue: None,
+                first_server: None,
             }),
             true,
         )
@@ -630,15 +700,19 @@ async fn edit_challenge_daily_limit(pool: SqlitePoolOptions, conn: SqliteConnect
     else {
         panic!()
     };
+    handler.side_effects().unwrap();
 
     let game_two = game;
 
     handler
         .call(
-            model::Request::Game(model::request::Game::Update(types::Game {
-                challenge: true,
-                ..game_two.clone()
-            })),
+            model::Request::Game(model::request::Game::Update {
+                game: types::Game {
+                    challenge: true,
+                    ..game_two.clone()
+                },
+                reason: None,
+            }),
             false,
         )
         .await
@@ -649,10 +723,13 @@ async fn edit_challenge_daily_limit(pool: SqlitePoolOptions, conn: SqliteConnect
 
     handler
         .call(
-            model::Request::Game(model::request::Game::Update(types::Game {
-                player_two: accepted_two.id,
-                ..game_one.clone()
-            })),
+            model::Request::Game(model::request::Game::Update {
+                game: types::Game {
+                    player_two: accepted_two.id,
+                    ..game_one.clone()
+                },
+                reason: None,
+            }),
             false,
         )
         .await
@@ -688,6 +765,9 @@ async fn delete_game(pool: SqlitePoolOptions, conn: SqliteConnectOptions) {
                     opponent_score: i,
                     challenge: false,
                     millis: types::Millis::from(i64::from(i)),
+                    comment: None,
+                    venue: None,
+                    first_server: None,
                 }),
                 true,
             )
@@ -707,6 +787,7 @@ async fn delete_game(pool: SqlitePoolOptions, conn: SqliteConnectOptions) {
         } else {
             panic!()
         }
+        handler.side_effects().unwrap();
     }
 
     // Clear the table
@@ -729,6 +810,9 @@ async fn delete_game(pool: SqlitePoolOptions, conn: SqliteConnectOptions) {
                     opponent_score: i,
                     challenge: false,
                     millis: types::Millis::from(i64::from(i)),
+                    comment: None,
+                    venue: None,
+                    first_server: None,
                 }),
                 true,
             )
@@ -744,6 +828,7 @@ async fn delete_game(pool: SqlitePoolOptions, conn: SqliteConnectOptions) {
         } else {
             panic!()
         }
+        handler.side_effects().unwrap();
     }
 
     // Delete the ones to be deleted
@@ -754,10 +839,13 @@ async fn delete_game(pool: SqlitePoolOptions, conn: SqliteConnectOptions) {
 
         let model::Push::Game(model::push::Game::Updated { .. }) = handler
             .call(
-                model::Request::Game(model::request::Game::Update(types::Game {
-                    deleted: true,
-                    ..g.clone()
-                })),
+                model::Request::Game(model::request::Game::Update {
+                    game: types::Game {
+                        deleted: true,
+                        ..g.clone()
+                    },
+                    reason: None,
+                }),
                 true,
             )
             .await
@@ -770,6 +858,7 @@ async fn delete_game(pool: SqlitePoolOptions, conn: SqliteConnectOptions) {
         else {
             panic!();
         };
+        handler.side_effects().unwrap();
     }
 
     // Check that the output matches the one created without edits
@@ -893,6 +982,9 @@ async fn random_updates(pool: SqlitePoolOptions, conn: SqliteConnectOptions) {
                     opponent_score: game.score_two,
                     challenge: game.challenge,
                     millis: game.millis,
+                    comment: None,
+                    venue: None,
+                    first_server: None,
                 }),
                 true,
             )
@@ -906,6 +998,7 @@ async fn random_updates(pool: SqlitePoolOptions, conn: SqliteConnectOptions) {
         else {
             panic!()
         };
+        handler.side_effects().unwrap();
     }
 
     let model::Response::Games(expected) = handler
@@ -947,6 +1040,9 @@ async fn random_updates(pool: SqlitePoolOptions, conn: SqliteConnectOptions) {
                     opponent_score: 0,
                     challenge: false,
                     millis: now(),
+                    comment: None,
+                    venue: None,
+                    first_server: None,
                 }),
                 true,
             )
@@ -962,22 +1058,26 @@ async fn random_updates(pool: SqlitePoolOptions, conn: SqliteConnectOptions) {
         } else {
             panic!()
         }
+        handler.side_effects().unwrap();
     }
 
     // Update the games according to the jig
     for (exising, target) in games.into_iter().zip(targets) {
         if let model::Push::Game(model::push::Game::Updated { game, .. }) = handler
             .call(
-                model::Request::Game(model::request::Game::Update(types::Game {
-                    player_one: target.player_one,
-                    player_two: target.player_two,
-                    score_one: i64::from(target.score_one),
-                    score_two: i64::from(target.score_two),
-                    challenge: target.challenge,
-                    deleted: target.deleted,
-                    millis: target.millis,
-                    ..exising
-                })),
+                model::Request::Game(model::request::Game::Update {
+                    game: types::Game {
+                        player_one: target.player_one,
+                        player_two: target.player_two,
+                        score_one: i64::from(target.score_one),
+                        score_two: i64::from(target.score_two),
+                        challenge: target.challenge,
+                        deleted: target.deleted,
+                        millis: target.millis,
+                        ..exising
+                    },
+                    reason: None,
+                }),
                 true,
             )
             .await
@@ -992,6 +1092,7 @@ async fn random_updates(pool: SqlitePoolOptions, conn: SqliteConnectOptions) {
         } else {
             panic!();
         };
+        handler.side_effects().unwrap();
     }
 
     // Check that the output matches the one created without edits
@@ -1037,6 +1138,9 @@ async fn creation_time_does_not_matter(pool: SqlitePoolOptions, conn: SqliteConn
                     opponent_score: i,
                     challenge: false,
                     millis: types::Millis::from(i64::from(i)),
+                    comment: None,
+                    venue: None,
+                    first_server: None,
                 }),
                 true,
             )
@@ -1057,6 +1161,7 @@ async fn creation_time_does_not_matter(pool: SqlitePoolOptions, conn: SqliteConn
         } else {
             panic!()
         }
+        handler.side_effects().unwrap();
     }
 
     sqlx::query!("DELETE FROM games")
@@ -1076,6 +1181,9 @@ async fn creation_time_does_not_matter(pool: SqlitePoolOptions, conn: SqliteConn
                     opponent_score: 9 - i,
                     challenge: false,
                     millis: types::Millis::from(i64::from(9 - i)),
+                    comment: None,
+                    venue: None,
+                    first_server: None,
                 }),
                 true,
             )
@@ -1091,6 +1199,7 @@ async fn creation_time_does_not_matter(pool: SqlitePoolOptions, conn: SqliteConn
         } else {
             panic!()
         }
+        handler.side_effects().unwrap();
     }
 
     let model::Response::Games(response) = handler
@@ -1133,6 +1242,9 @@ async fn history(pool: SqlitePoolOptions, conn: SqliteConnectOptions) {
                 opponent_score: 0,
                 challenge: true,
                 millis: now(),
+                comment: None,
+                venue: None,
+                first_server: None,
             }),
             true,
         )
@@ -1146,16 +1258,20 @@ async fn history(pool: SqlitePoolOptions, conn: SqliteConnectOptions) {
     else {
         panic!()
     };
+    handler.side_effects().unwrap();
 
     let original_game = game;
 
     let model::Push::Game(model::push::Game::Updated { game, .. }) = handler
         .call(
-            model::Request::Game(model::request::Game::Update(types::Game {
-                score_one: 7,
-                score_two: 11,
-                ..original_game.clone()
-            })),
+            model::Request::Game(model::request::Game::Update {
+                game: types::Game {
+                    score_one: 7,
+                    score_two: 11,
+                    ..original_game.clone()
+                },
+                reason: Some(String::from(" Wrong score ")),
+            }),
             true,
         )
         .await
@@ -1168,6 +1284,7 @@ async fn history(pool: SqlitePoolOptions, conn: SqliteConnectOptions) {
     else {
         panic!()
     };
+    handler.side_effects().unwrap();
 
     handler
         .call(model::Request::Game(model::request::Game::List), false)
@@ -1211,6 +1328,8 @@ async fn history(pool: SqlitePoolOptions, conn: SqliteConnectOptions) {
                 deleted: original_game.deleted,
                 millis: original_game.millis,
                 created_ms: types::Millis::from(0),
+                editor: Some(player.id),
+                reason: Some(String::from("Wrong score")),
             }],
         )
         .unwrap()
@@ -1220,6 +1339,218 @@ async fn history(pool: SqlitePoolOptions, conn: SqliteConnectOptions) {
         .unwrap();
 }
 
+#[sqlx::test]
+async fn revert(pool: SqlitePoolOptions, conn: SqliteConnectOptions) {
+    let (player, store, mut handler, _) = init!(pool, conn);
+
+    let accepted = handler
+        .invite_full(&player, &store, ACCEPTED_NAME, ACCEPTED_EMAIL)
+        .await
+        .unwrap();
+
+    let original_game = handler
+        .register((player.id, accepted.id), (11, 5), false, now())
+        .await
+        .unwrap();
+    handler.side_effects().unwrap();
+
+    let other_game = handler
+        .register(
+            (player.id, accepted.id),
+            (11, 3),
+            false,
+            types::Millis::from(i64::from(now()) + 1),
+        )
+        .await
+        .unwrap();
+    handler.side_effects().unwrap();
+
+    let model::Push::Game(model::push::Game::Updated { .. }) = handler
+        .call(
+            model::Request::Game(model::request::Game::Update {
+                game: types::Game {
+                    score_one: 7,
+                    score_two: 11,
+                    ..original_game.clone()
+                },
+                reason: None,
+            }),
+            true,
+        )
+        .await
+        .done()
+        .unwrap()
+        .none()
+        .unwrap()
+        .some()
+        .unwrap()
+    else {
+        panic!()
+    };
+    handler.side_effects().unwrap();
+
+    let history = store.games().history(original_game.id).await.unwrap();
+    assert_eq!(history.len(), 1);
+
+    handler
+        .call(
+            model::Request::Game(model::request::Game::Revert {
+                game: other_game.id,
+                history: history[0].id,
+            }),
+            false,
+        )
+        .await
+        .err(model::Error::Store(store::Error::NotFound))
+        .unwrap();
+
+    let model::Push::Game(model::push::Game::Updated { game, updates }) = handler
+        .call(
+            model::Request::Game(model::request::Game::Revert {
+                game: original_game.id,
+                history: history[0].id,
+            }),
+            true,
+        )
+        .await
+        .done()
+        .unwrap()
+        .none()
+        .unwrap()
+        .some()
+        .unwrap()
+    else {
+        panic!()
+    };
+    handler.side_effects().unwrap();
+
+    assert_eq!(game, original_game);
+    assert_eq!(updates, vec![other_game.clone().into()]);
+
+    handler
+        .call(model::Request::Game(model::request::Game::List), false)
+        .await
+        .ok(model::Response::Games(vec![
+            original_game.into(),
+            other_game.into(),
+        ]))
+        .unwrap()
+        .none()
+        .unwrap()
+        .none()
+        .unwrap();
+
+    let history = store.games().history(original_game.id).await.unwrap();
+    assert_eq!(history.len(), 2);
+    assert_eq!(history[0].editor, Some(player.id));
+}
+
+#[sqlx::test]
+async fn undo(pool: SqlitePoolOptions, conn: SqliteConnectOptions) {
+    let (player, store, mut handler, pool) = init!(pool, conn);
+
+    let accepted = handler
+        .invite_full(&player, &store, ACCEPTED_NAME, ACCEPTED_EMAIL)
+        .await
+        .unwrap();
+
+    let mistake = handler
+        .register((player.id, accepted.id), (11, 5), false, now())
+        .await
+        .unwrap();
+    handler.side_effects().unwrap();
+
+    let game = handler
+        .register(
+            (player.id, accepted.id),
+            (11, 3),
+            false,
+            types::Millis::from(i64::from(now()) + 1),
+        )
+        .await
+        .unwrap();
+    handler.side_effects().unwrap();
+
+    framework::Handler::new(&accepted.email, &store)
+        .await
+        .unwrap()
+        .call(
+            model::Request::Game(model::request::Game::Undo(mistake.id)),
+            false,
+        )
+        .await
+        .err(model::Error::Store(store::Error::Forbidden))
+        .unwrap();
+
+    let model::Push::Game(model::push::Game::Undone {
+        game: undone,
+        updates,
+    }) = handler
+        .call(
+            model::Request::Game(model::request::Game::Undo(mistake.id)),
+            true,
+        )
+        .await
+        .done()
+        .unwrap()
+        .none()
+        .unwrap()
+        .some()
+        .unwrap()
+    else {
+        panic!()
+    };
+    handler.side_effects().unwrap();
+
+    assert_eq!(undone, mistake);
+
+    let game = types::Game {
+        rating_one: skillratings::elo::EloRating::new().rating,
+        rating_two: skillratings::elo::EloRating::new().rating,
+        rating_delta: mistake.rating_delta,
+        ..game
+    };
+    assert_eq!(updates, vec![game.clone().into()]);
+
+    handler
+        .call(model::Request::Game(model::request::Game::List), false)
+        .await
+        .ok(model::Response::Games(vec![game.clone().into()]))
+        .unwrap()
+        .none()
+        .unwrap()
+        .none()
+        .unwrap();
+
+    handler
+        .call(
+            model::Request::Game(model::request::Game::Undo(mistake.id)),
+            false,
+        )
+        .await
+        .err(model::Error::Store(store::Error::NotFound))
+        .unwrap();
+
+    sqlx::query!(
+        "UPDATE games SET created_ms = created_ms - 60 * 60 * 1000 WHERE id = $1",
+        game.id
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    handler
+        .call(
+            model::Request::Game(model::request::Game::Undo(game.id)),
+            false,
+        )
+        .await
+        .err(model::Error::Store(store::Error::InvalidValue(
+            "Games can only be undone shortly after being registered",
+        )))
+        .unwrap();
+}
+
 #[sqlx::test]
 async fn history_only_when_relevant(pool: SqlitePoolOptions, conn: SqliteConnectOptions) {
     let (player, store, mut handler, _) = init!(pool, conn);
@@ -1238,6 +1569,9 @@ async fn history_only_when_relevant(pool: SqlitePoolOptions, conn: SqliteConnect
                 opponent_score: 0,
                 challenge: true,
                 millis: now(),
+                comment: None,
+                venue: None,
+                first_server: None,
             }),
             true,
         )
@@ -1251,6 +1585,7 @@ async fn history_only_when_relevant(pool: SqlitePoolOptions, conn: SqliteConnect
     else {
         panic!()
     };
+    handler.side_effects().unwrap();
 
     let first_game = game;
 
@@ -1263,6 +1598,9 @@ async fn history_only_when_relevant(pool: SqlitePoolOptions, conn: SqliteConnect
                 opponent_score: 0,
                 challenge: false,
                 millis: now(),
+                comment: None,
+                venue: None,
+                first_server: None,
             }),
             true,
         )
@@ -1276,16 +1614,20 @@ async fn history_only_when_relevant(pool: SqlitePoolOptions, conn: SqliteConnect
     else {
         panic!()
     };
+    handler.side_effects().unwrap();
 
     let second_game = game;
     let second_game_id = second_game.id;
 
     let model::Push::Game(model::push::Game::Updated { updates, .. }) = handler
         .call(
-            model::Request::Game(model::request::Game::Update(types::Game {
-                deleted: true,
-                ..first_game.clone()
-            })),
+            model::Request::Game(model::request::Game::Update {
+                game: types::Game {
+                    deleted: true,
+                    ..first_game.clone()
+                },
+                reason: None,
+            }),
             true,
         )
         .await
@@ -1298,6 +1640,7 @@ async fn history_only_when_relevant(pool: SqlitePoolOptions, conn: SqliteConnect
     else {
         panic!()
     };
+    handler.side_effects().unwrap();
     let modified_second_game = updates.into_iter().next().map(types::Game::from).unwrap();
 
     assert_ne!(second_game, modified_second_game);
@@ -1337,6 +1680,153 @@ async fn history_only_when_relevant(pool: SqlitePoolOptions, conn: SqliteConnect
         .unwrap();
 }
 
+#[sqlx::test]
+async fn preview(pool: SqlitePoolOptions, conn: SqliteConnectOptions) {
+    const DAY: i64 = 24 * 60 * 60 * 1000;
+
+    let (player, store, mut handler, _) = init!(pool, conn);
+    let accepted = handler
+        .invite_full(&player, &store, ACCEPTED_NAME, ACCEPTED_EMAIL)
+        .await
+        .unwrap();
+
+    let preview = |millis, challenge| {
+        model::Request::Game(model::request::Game::Preview {
+            player: player.id,
+            opponent: accepted.id,
+            millis: types::Millis::from(millis),
+            challenge,
+        })
+    };
+
+    let default = skillratings::elo::EloRating::new();
+    let delta = skillratings::elo::elo(
+        &default,
+        &default,
+        &skillratings::Outcomes::WIN,
+        &skillratings::elo::EloConfig::new(),
+    )
+    .0
+    .rating
+        - default.rating;
+
+    handler
+        .call(preview(100 * DAY, false), false)
+        .await
+        .ok(model::Response::Preview {
+            rating: default.rating,
+            opponent_rating: default.rating,
+            probability: 0.5,
+            win: delta,
+            loss: -delta,
+        })
+        .unwrap()
+        .none()
+        .unwrap()
+        .none()
+        .unwrap();
+
+    let game = handler
+        .register(
+            (player.id, accepted.id),
+            (11, 0),
+            true,
+            types::Millis::from(100 * DAY),
+        )
+        .await
+        .unwrap();
+    handler.side_effects().unwrap();
+
+    // Only games before the given time count
+    handler
+        .call(preview(100 * DAY - 1, false), false)
+        .await
+        .map_ok(
+            |r| match r {
+                model::Response::Preview {
+                    rating,
+                    opponent_rating,
+                    ..
+                } => (rating, opponent_rating),
+                r => panic!("{r:?}"),
+            },
+            (default.rating, default.rating),
+        )
+        .unwrap()
+        .none()
+        .unwrap()
+        .none()
+        .unwrap();
+
+    handler
+        .call(preview(101 * DAY, false), false)
+        .await
+        .map_ok(
+            |r| match r {
+                model::Response::Preview {
+                    rating,
+                    opponent_rating,
+                    probability,
+                    win,
+                    loss,
+                } => {
+                    assert!(probability > 0.5);
+                    assert!(win > 0.0 && win < delta);
+                    assert!(loss < 0.0 && -loss > delta);
+                    (rating, opponent_rating)
+                }
+                r => panic!("{r:?}"),
+            },
+            (
+                game.rating_one + game.rating_delta,
+                game.rating_two - game.rating_delta,
+            ),
+        )
+        .unwrap()
+        .none()
+        .unwrap()
+        .none()
+        .unwrap();
+
+    handler
+        .call(preview(100 * DAY + 1, true), false)
+        .await
+        .err(model::Error::Store(store::Error::InvalidValue(
+            "Players cannot challenge each other more than once a day",
+        )))
+        .unwrap();
+
+    handler
+        .call(
+            model::Request::Game(model::request::Game::Preview {
+                player: player.id,
+                opponent: player.id,
+                millis: super::now(),
+                challenge: false,
+            }),
+            false,
+        )
+        .await
+        .err(model::Error::Store(store::Error::InvalidValue(
+            "Players cannot be equal",
+        )))
+        .unwrap();
+
+    handler
+        .call(
+            model::Request::Game(model::request::Game::Preview {
+                player: player.id,
+                opponent: 27,
+                millis: super::now(),
+                challenge: false,
+            }),
+            false,
+        )
+        .await
+        .err(model::Error::Store(store::Error::NotFound))
+        .unwrap();
+}
+
 #[sqlx::test]
 async fn forbidden(pool: SqlitePoolOptions, conn: SqliteConnectOptions) {
     let (_, store, mut handler, _) = init!(pool, conn);
@@ -1361,6 +1851,9 @@ async fn forbidden(pool: SqlitePoolOptions, conn: SqliteConnectOptions) {
                 opponent_score: 0,
                 challenge: false,
                 millis: super::now(),
+                comment: None,
+                venue: None,
+                first_server: None,
             }),
             false,
         )
@@ -1376,4 +1869,609 @@ async fn forbidden(pool: SqlitePoolOptions, conn: SqliteConnectOptions) {
         .await
         .err(model::Error::Forbidden)
         .unwrap();
+
+    handler
+        .call(
+            model::Request::Game(model::request::Game::Preview {
+                player: 0,
+                opponent: 0,
+                millis: super::now(),
+                challenge: false,
+            }),
+            false,
+        )
+        .await
+        .err(model::Error::Forbidden)
+        .unwrap();
+}
+
+#[sqlx::test]
+async fn annotations(pool: SqlitePoolOptions, conn: SqliteConnectOptions) {
+    let (player, store, _, _) = init!(pool, conn);
+
+    let store = store.with_venues(vec![String::from("left"), String::from("right")]);
+    let mut handler = framework::Handler::new(&player.email, &store)
+        .await
+        .unwrap();
+
+    let accepted = handler
+        .invite_full(&player, &store, ACCEPTED_NAME, ACCEPTED_EMAIL)
+        .await
+        .unwrap();
+
+    let register = |comment: Option<&str>, venue: Option<&str>, first_server| {
+        model::Request::Game(model::request::Game::Register {
+            player: player.id,
+            opponent: accepted.id,
+            score: 11,
+            opponent_score: 7,
+            challenge: false,
+            millis: now(),
+            comment: comment.map(String::from),
+            venue: venue.map(String::from),
+            first_server,
+        })
+    };
+
+    handler
+        .call(model::Request::Game(model::request::Game::Venues), false)
+        .await
+        .ok(model::Response::Venues(vec![
+            String::from("left"),
+            String::from("right"),
+        ]))
+        .unwrap()
+        .none()
+        .unwrap()
+        .none()
+        .unwrap();
+
+    handler
+        .call(register(None, Some("middle"), None), false)
+        .await
+        .err(model::Error::Store(store::Error::InvalidValue(
+            "Unknown venue",
+        )))
+        .unwrap();
+
+    handler
+        .call(register(None, None, Some(0)), false)
+        .await
+        .err(model::Error::Store(store::Error::InvalidValue(
+            "The first server must be one of the players",
+        )))
+        .unwrap();
+
+    handler
+        .call(register(Some(&"a".repeat(257)), None, None), false)
+        .await
+        .err(model::Error::Store(store::Error::InvalidValue(
+            "Comments cannot be longer than 256 characters",
+        )))
+        .unwrap();
+
+    let model::Push::Game(model::push::Game::Registered { game, .. }) = handler
+        .call(
+            register(Some("  Good game  "), Some("left"), Some(accepted.id)),
+            true,
+        )
+        .await
+        .done()
+        .unwrap()
+        .none()
+        .unwrap()
+        .some()
+        .unwrap()
+    else {
+        panic!()
+    };
+    handler.side_effects().unwrap();
+
+    assert_eq!(game.comment.as_deref(), Some("Good game"));
+    assert_eq!(game.venue.as_deref(), Some("left"));
+    assert_eq!(game.first_server, Some(accepted.id));
+
+    handler
+        .register(
+            (player.id, accepted.id),
+            (11, 3),
+            false,
+            types::Millis::from(i64::from(now()) + 1),
+        )
+        .await
+        .unwrap();
+    handler.side_effects().unwrap();
+
+    handler
+        .call(
+            model::Request::Game(model::request::Game::ListVenue(String::from("left"))),
+            false,
+        )
+        .await
+        .ok(model::Response::Games(vec![game.clone().into()]))
+        .unwrap()
+        .none()
+        .unwrap()
+        .none()
+        .unwrap();
+
+    let model::Push::Game(model::push::Game::Updated { game, .. }) = handler
+        .call(
+            model::Request::Game(model::request::Game::Update {
+                game: types::Game {
+                    comment: Some(String::from("  ")),
+                    venue: Some(String::from("right")),
+                    ..game
+                },
+                reason: None,
+            }),
+            true,
+        )
+        .await
+        .done()
+        .unwrap()
+        .none()
+        .unwrap()
+        .some()
+        .unwrap()
+    else {
+        panic!()
+    };
+    handler.side_effects().unwrap();
+
+    assert_eq!(game.comment, None);
+    assert_eq!(game.venue.as_deref(), Some("right"));
+
+    handler
+        .call(
+            model::Request::Game(model::request::Game::ListVenue(String::from("left"))),
+            false,
+        )
+        .await
+        .ok(model::Response::Games(Vec::new()))
+        .unwrap()
+        .none()
+        .unwrap()
+        .none()
+        .unwrap();
+
+    // Games keep a venue that is no longer configured until the venue itself is changed
+    let store = store.with_venues(vec![String::from("left")]);
+    let mut handler = framework::Handler::new(&player.email, &store)
+        .await
+        .unwrap();
+
+    let model::Push::Game(model::push::Game::Updated { game, .. }) = handler
+        .call(
+            model::Request::Game(model::request::Game::Update {
+                game: types::Game {
+                    comment: Some(String::from("Moved")),
+                    ..game
+                },
+                reason: None,
+            }),
+            true,
+        )
+        .await
+        .done()
+        .unwrap()
+        .none()
+        .unwrap()
+        .some()
+        .unwrap()
+    else {
+        panic!()
+    };
+    handler.side_effects().unwrap();
+
+    assert_eq!(game.comment.as_deref(), Some("Moved"));
+    assert_eq!(game.venue.as_deref(), Some("right"));
+
+    handler
+        .call(
+            model::Request::Game(model::request::Game::Update {
+                game: types::Game {
+                    venue: Some(String::from("middle")),
+                    ..game
+                },
+                reason: None,
+            }),
+            false,
+        )
+        .await
+        .err(model::Error::Store(store::Error::InvalidValue(
+            "Unknown venue",
+        )))
+        .unwrap();
+}
+
+#[sqlx::test]
+async fn points(pool: SqlitePoolOptions, conn: SqliteConnectOptions) {
+    let (player, store, mut handler, _) = init!(pool, conn);
+
+    let accepted = handler
+        .invite_full(&player, &store, ACCEPTED_NAME, ACCEPTED_EMAIL)
+        .await
+        .unwrap();
+
+    let game = handler
+        .register((player.id, accepted.id), (11, 9), false, now())
+        .await
+        .unwrap();
+    handler.side_effects().unwrap();
+
+    let one = types::Point::Won(types::Side::One);
+    let two = types::Point::Won(types::Side::Two);
+
+    let mut points = vec![types::Point::Serve(types::Side::One)];
+    points.extend([two; 2]);
+    points.push(types::Point::Serve(types::Side::Two));
+    points.extend([two; 3]);
+    points.extend([one; 7]);
+    points.extend([two; 4]);
+    points.extend([one; 4]);
+
+    let record = |points: Vec<types::Point>| {
+        model::Request::Game(model::request::Game::Record {
+            game: game.id,
+            points,
+        })
+    };
+
+    handler
+        .call(
+            model::Request::Game(model::request::Game::Points(game.id)),
+            false,
+        )
+        .await
+        .err(model::Error::Store(store::Error::NotFound))
+        .unwrap();
+
+    handler
+        .call(record(points[1..].to_vec()), true)
+        .await
+        .done()
+        .unwrap()
+        .none()
+        .unwrap()
+        .some()
+        .unwrap();
+
+    handler
+        .call(record(points[..points.len() - 1].to_vec()), false)
+        .await
+        .err(model::Error::Store(store::Error::InvalidValue(
+            "Points must add up to the game score",
+        )))
+        .unwrap();
+
+    let mut lost_last = points.clone();
+    lost_last.swap(5, points.len() - 1);
+    handler
+        .call(record(lost_last), false)
+        .await
+        .err(model::Error::Store(store::Error::InvalidValue(
+            "The last point must be won by the winner",
+        )))
+        .unwrap();
+
+    let mut double_serve = points.clone();
+    double_serve.insert(1, types::Point::Serve(types::Side::One));
+    handler
+        .call(record(double_serve), false)
+        .await
+        .err(model::Error::Store(store::Error::InvalidValue(
+            "The serve can only change over to the other side",
+        )))
+        .unwrap();
+
+    let model::Push::Game(model::push::Game::PointsRecorded {
+        game: id,
+        player_one,
+        player_two,
+    }) = handler
+        .call(record(points.clone()), true)
+        .await
+        .done()
+        .unwrap()
+        .none()
+        .unwrap()
+        .some()
+        .unwrap()
+    else {
+        panic!()
+    };
+    assert_eq!(id, game.id);
+    assert_eq!((player_one, player_two), (game.player_one, game.player_two));
+
+    handler
+        .call(
+            model::Request::Game(model::request::Game::Points(game.id)),
+            false,
+        )
+        .await
+        .ok(model::Response::Points(types::Points {
+            game: game.id,
+            points,
+            longest_run_one: 7,
+            longest_run_two: 5,
+        }))
+        .unwrap()
+        .none()
+        .unwrap()
+        .none()
+        .unwrap();
+
+    handler
+        .call(
+            model::Request::Game(model::request::Game::Update {
+                game: types::Game {
+                    score_two: 8,
+                    ..game
+                },
+                reason: None,
+            }),
+            false,
+        )
+        .await
+        .err(model::Error::Store(store::Error::InvalidValue(
+            "Points must add up to the game score",
+        )))
+        .unwrap();
+
+    handler
+        .call(record(Vec::new()), true)
+        .await
+        .done()
+        .unwrap()
+        .none()
+        .unwrap()
+        .some()
+        .unwrap();
+
+    handler
+        .call(
+            model::Request::Game(model::request::Game::Points(game.id)),
+            false,
+        )
+        .await
+        .err(model::Error::Store(store::Error::NotFound))
+        .unwrap();
+}
+
+#[sqlx::test]
+async fn protocol_versions(pool: SqlitePoolOptions, conn: SqliteConnectOptions) {
+    let (player, store, mut handler, _) = init!(pool, conn);
+
+    let accepted = handler
+        .invite_full(&player, &store, ACCEPTED_NAME, ACCEPTED_EMAIL)
+        .await
+        .unwrap();
+
+    let game = handler
+        .register((player.id, accepted.id), (11, 7), false, now())
+        .await
+        .unwrap();
+    handler.side_effects().unwrap();
+
+    let fields = |presented: &serde_json::Value| -> Vec<usize> {
+        presented
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|tuple| tuple.as_array().unwrap().len())
+            .collect()
+    };
+
+    let mut legacy = framework::Handler::new(&player.email, &store)
+        .await
+        .unwrap()
+        .with_version(crate::handler::MIN_VERSION);
+    assert_eq!(handler.rejects(), None);
+    assert_eq!(legacy.rejects(), None);
+
+    // Older clients send the bare game, without annotations, when updating it
+    let mut bare = serde_json::to_value(types::Game {
+        score_one: 11,
+        score_two: 9,
+        ..game
+    })
+    .unwrap();
+    bare.as_object_mut()
+        .unwrap()
+        .retain(|key, _| !matches!(key.as_str(), "comment" | "venue" | "firstServer"));
+    let update = serde_json::json!({ "game": { "update": bare } });
+
+    let model::Push::Game(model::push::Game::Updated { game, .. }) = legacy
+        .call(serde_json::from_value(update).unwrap(), true)
+        .await
+        .done()
+        .unwrap()
+        .none()
+        .unwrap()
+        .some()
+        .unwrap()
+    else {
+        panic!()
+    };
+    legacy.side_effects().unwrap();
+    assert_eq!(game.score_two, 9);
+
+    let games = model::Response::Games(vec![game.clone().into()]);
+    let latest = handler.present_response(games.clone()).unwrap();
+    let older = legacy.present_response(games).unwrap();
+    assert_eq!(fields(&latest["games"]), vec![15]);
+    assert_eq!(fields(&older["games"]), vec![12]);
+
+    let history = legacy
+        .call(
+            model::Request::Game(model::request::Game::History(game.id)),
+            false,
+        )
+        .await
+        .raw()
+        .unwrap();
+    let latest = handler.present_response(history.clone()).unwrap();
+    let older = legacy.present_response(history).unwrap();
+    assert_eq!(fields(&latest["history"]), vec![12]);
+    assert_eq!(fields(&older["history"]), vec![10]);
+
+    let push = model::Push::Game(model::push::Game::Updated {
+        game: game.clone(),
+        updates: vec![game.into()],
+    });
+    let latest = handler.present_push(push.clone()).unwrap().unwrap();
+    let older = legacy.present_push(push).unwrap().unwrap();
+    assert_eq!(fields(&latest["game"]["updated"]["updates"]), vec![15]);
+    assert_eq!(fields(&older["game"]["updated"]["updates"]), vec![12]);
+
+    legacy
+        .call(model::Request::Version, false)
+        .await
+        .map_ok(
+            |r| match r {
+                model::Response::Version {
+                    server, protocol, ..
+                } => (server, protocol),
+                r => panic!("{r:?}"),
+            },
+            (crate::handler::VERSION, crate::handler::MIN_VERSION),
+        )
+        .unwrap()
+        .none()
+        .unwrap()
+        .none()
+        .unwrap();
+
+    for version in [crate::handler::MIN_VERSION - 1, u32::MAX] {
+        let outdated = framework::Handler::new(&player.email, &store)
+            .await
+            .unwrap()
+            .with_version(version);
+
+        assert_eq!(
+            outdated.rejects(),
+            Some(ws::Close::outdated("Outdated client, please reload"))
+        );
+    }
+}
+
+#[sqlx::test]
+async fn protocol_pushes(pool: SqlitePoolOptions, conn: SqliteConnectOptions) {
+    let (player, store, mut handler, _) = init!(pool, conn);
+
+    let accepted = handler
+        .invite_full(&player, &store, ACCEPTED_NAME, ACCEPTED_EMAIL)
+        .await
+        .unwrap();
+
+    let game = handler
+        .register((player.id, accepted.id), (11, 7), false, now())
+        .await
+        .unwrap();
+    handler.side_effects().unwrap();
+
+    let legacy = framework::Handler::new(&player.email, &store)
+        .await
+        .unwrap()
+        .with_version(crate::handler::MIN_VERSION);
+
+    let renamed = model::Push::Player(model::push::Player::Renamed {
+        player: player.id,
+        old: String::from(ACCEPTED_NAME),
+        new: String::from(INVITED_NAME),
+    });
+    assert_eq!(
+        legacy.present_push(renamed.clone()).unwrap(),
+        handler.present_push(renamed).unwrap()
+    );
+
+    // Older clients get games without their annotations
+    let registered = legacy
+        .present_push(model::Push::Game(model::push::Game::Registered {
+            game: game.clone(),
+            updates: vec![game.clone().into()],
+        }))
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        registered["game"]["registered"]["game"]
+            .as_object()
+            .unwrap()
+            .len(),
+        12
+    );
+
+    // And know undone games as deleted ones
+    let undone = legacy
+        .present_push(model::Push::Game(model::push::Game::Undone {
+            game: game.clone(),
+            updates: Vec::new(),
+        }))
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        undone["game"]["updated"]["game"]["deleted"],
+        serde_json::Value::Bool(true)
+    );
+    assert_eq!(
+        undone["game"]["updated"]["game"].as_object().unwrap().len(),
+        12
+    );
+
+    let live = types::LiveGame {
+        scorer: player.id,
+        player_one: player.id,
+        player_two: accepted.id,
+        score_one: 0,
+        score_two: 0,
+        points: Vec::new(),
+        started_ms: now(),
+    };
+
+    let unknown = [
+        model::Push::Game(model::push::Game::PointsRecorded {
+            game: game.id,
+            player_one: game.player_one,
+            player_two: game.player_two,
+        }),
+        model::Push::Season(model::push::Season::Created {
+            season: types::Season {
+                id: 1,
+                name: String::from("season"),
+                start_ms: types::Millis::from(0),
+                end_ms: now(),
+                reset: 0.0,
+                created_ms: now(),
+            },
+            updates: vec![game.clone().into()],
+        }),
+        model::Push::Tournament(model::push::Tournament::Created {
+            tournament: types::Tournament {
+                id: 1,
+                name: String::from("tournament"),
+                format: types::Format::RoundRobin,
+                groups: 0,
+                advancing: 1,
+                created_ms: now(),
+            },
+            fixtures: Vec::new(),
+        }),
+        model::Push::Achievement(model::push::Achievement::Updated {
+            unlocked: Vec::new(),
+            revoked: Vec::new(),
+        }),
+        model::Push::Streak(model::push::Streak::Started(types::Streak {
+            player: player.id,
+            current: 1,
+            longest_win: 1,
+            longest_loss: 0,
+        })),
+        model::Push::Live(model::push::LiveGame::Started(live)),
+    ];
+
+    for push in unknown {
+        assert!(handler.present_push(push.clone()).unwrap().is_some());
+        assert_eq!(legacy.present_push(push).unwrap(), None);
+    }
 }
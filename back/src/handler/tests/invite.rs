@@ -1,5 +1,5 @@
 use super::{super::model, *};
-use crate::{mailbox, smtp};
+use crate::{mailbox, smtp, ws};
 
 use sqlx::sqlite::SqliteConnectOptions;
 use sqlx::sqlite::SqlitePoolOptions;
@@ -155,6 +155,77 @@ async fn reject(pool: SqlitePoolOptions, conn: SqliteConnectOptions) {
     assert_eq!(invited, uninvited);
 }
 
+#[sqlx::test]
+async fn reject_closes(pool: SqlitePoolOptions, conn: SqliteConnectOptions) {
+    let (player, store, mut handler, _) = init!(pool, conn);
+    let invited = handler.invite(INVITED_NAME, INVITED_EMAIL).await.unwrap();
+
+    let mut handler = framework::Handler::pending(&invited.email, &store)
+        .await
+        .unwrap();
+
+    let push = handler
+        .call(model::Request::Invite(model::request::Invite::Reject), true)
+        .await
+        .done()
+        .unwrap()
+        .some(smtp::Payload::InviteOutcome {
+            inviter: mailbox::Proto {
+                name: player.name.clone(),
+                email: player.email.clone(),
+            },
+            invitee: mailbox::Proto {
+                name: invited.name.clone(),
+                email: invited.email.clone(),
+            },
+            accepted: false,
+        })
+        .unwrap()
+        .some()
+        .unwrap();
+
+    assert_eq!(
+        handler.closes(&push),
+        Some(ws::Close::revoked("Invitation withdrawn"))
+    );
+}
+
+#[sqlx::test]
+async fn accept_reconnects(pool: SqlitePoolOptions, conn: SqliteConnectOptions) {
+    let (player, store, mut handler, _) = init!(pool, conn);
+    let invited = handler.invite(INVITED_NAME, INVITED_EMAIL).await.unwrap();
+
+    let mut pending = framework::Handler::pending(&invited.email, &store)
+        .await
+        .unwrap();
+
+    let push = pending
+        .call(model::Request::Invite(model::request::Invite::Accept), true)
+        .await
+        .done()
+        .unwrap()
+        .some(smtp::Payload::InviteOutcome {
+            inviter: mailbox::Proto {
+                name: player.name.clone(),
+                email: player.email.clone(),
+            },
+            invitee: mailbox::Proto {
+                name: invited.name.clone(),
+                email: invited.email.clone(),
+            },
+            accepted: true,
+        })
+        .unwrap()
+        .some()
+        .unwrap();
+
+    assert_eq!(
+        pending.closes(&push),
+        Some(ws::Close::reconnect("Invitation accepted"))
+    );
+    assert_eq!(handler.closes(&push), None);
+}
+
 #[sqlx::test]
 async fn cancel(pool: SqlitePoolOptions, conn: SqliteConnectOptions) {
     let mut handler = init!(pool, conn).2;
@@ -179,6 +250,35 @@ async fn cancel(pool: SqlitePoolOptions, conn: SqliteConnectOptions) {
     assert_eq!(invited, uninvited);
 }
 
+#[sqlx::test]
+async fn cancel_closes(pool: SqlitePoolOptions, conn: SqliteConnectOptions) {
+    let (_, store, mut handler, _) = init!(pool, conn);
+    let invited = handler.invite(INVITED_NAME, INVITED_EMAIL).await.unwrap();
+
+    let pending = framework::Handler::pending(&invited.email, &store)
+        .await
+        .unwrap();
+
+    let push = handler
+        .call(
+            model::Request::Invite(model::request::Invite::Cancel(invited.id)),
+            true,
+        )
+        .await
+        .done()
+        .unwrap()
+        .none()
+        .unwrap()
+        .some()
+        .unwrap();
+
+    assert_eq!(handler.closes(&push), None);
+    assert_eq!(
+        pending.closes(&push),
+        Some(ws::Close::revoked("Invitation withdrawn"))
+    );
+}
+
 #[sqlx::test]
 async fn cancel_not_found(pool: SqlitePoolOptions, conn: SqliteConnectOptions) {
     let mut handler = init!(pool, conn).2;
@@ -0,0 +1,525 @@
+use super::{super::model, *};
+use crate::types;
+
+use sqlx::sqlite::SqliteConnectOptions;
+use sqlx::sqlite::SqlitePoolOptions;
+
+const DAY: i64 = 24 * 60 * 60 * 1000;
+const MIN_GAMES: i64 = super::super::MIN_GAMES;
+
+#[sqlx::test]
+async fn current(pool: SqlitePoolOptions, conn: SqliteConnectOptions) {
+    let (player, store, mut handler, _) = init!(pool, conn);
+
+    let accepted = handler
+        .invite_full(&player, &store, ACCEPTED_NAME, ACCEPTED_EMAIL)
+        .await
+        .unwrap();
+
+    let other = handler
+        .invite_full(&player, &store, INVITED_NAME, INVITED_EMAIL)
+        .await
+        .unwrap();
+
+    let mut last = None;
+    for i in 0..MIN_GAMES {
+        last = Some(
+            handler
+                .register(
+                    (player.id, accepted.id),
+                    (11, 0),
+                    false,
+                    types::Millis::from(100 * DAY + i * 1000),
+                )
+                .await
+                .unwrap(),
+        );
+        handler.side_effects().unwrap();
+    }
+    let last = last.unwrap();
+
+    let rating_one = last.rating_one + last.rating_delta;
+    let rating_two = last.rating_two - last.rating_delta;
+
+    handler
+        .call(
+            model::Request::Leaderboard(model::request::Leaderboard::Get { at: None }),
+            false,
+        )
+        .await
+        .ok(model::Response::Leaderboard {
+            min_games: MIN_GAMES,
+            standings: vec![
+                types::StandingTuple(
+                    player.id,
+                    Some(1),
+                    rating_one,
+                    MIN_GAMES,
+                    Some(1),
+                    Some(rating_one),
+                ),
+                types::StandingTuple(
+                    accepted.id,
+                    Some(2),
+                    rating_two,
+                    MIN_GAMES,
+                    Some(2),
+                    Some(rating_two),
+                ),
+                types::StandingTuple(
+                    other.id,
+                    None,
+                    skillratings::elo::EloRating::new().rating,
+                    0,
+                    None,
+                    None,
+                ),
+            ],
+        })
+        .unwrap()
+        .none()
+        .unwrap()
+        .none()
+        .unwrap();
+}
+
+#[sqlx::test]
+async fn past(pool: SqlitePoolOptions, conn: SqliteConnectOptions) {
+    let (player, store, mut handler, _) = init!(pool, conn);
+
+    let accepted = handler
+        .invite_full(&player, &store, ACCEPTED_NAME, ACCEPTED_EMAIL)
+        .await
+        .unwrap();
+
+    let mut first_day = None;
+    for i in 1..MIN_GAMES {
+        first_day = Some(
+            handler
+                .register(
+                    (player.id, accepted.id),
+                    (11, 0),
+                    false,
+                    types::Millis::from(100 * DAY + i * 1000),
+                )
+                .await
+                .unwrap(),
+        );
+        handler.side_effects().unwrap();
+    }
+    let first_day = first_day.unwrap();
+
+    let second_day = handler
+        .register(
+            (player.id, accepted.id),
+            (11, 0),
+            false,
+            types::Millis::from(101 * DAY),
+        )
+        .await
+        .unwrap();
+    handler.side_effects().unwrap();
+
+    let first_day = (
+        first_day.rating_one + first_day.rating_delta,
+        first_day.rating_two - first_day.rating_delta,
+    );
+    let second_day = (
+        second_day.rating_one + second_day.rating_delta,
+        second_day.rating_two - second_day.rating_delta,
+    );
+
+    handler
+        .call(
+            model::Request::Leaderboard(model::request::Leaderboard::Get {
+                at: Some(types::Millis::from(100 * DAY + 5)),
+            }),
+            false,
+        )
+        .await
+        .ok(model::Response::Leaderboard {
+            min_games: MIN_GAMES,
+            standings: vec![
+                types::StandingTuple(player.id, None, first_day.0, MIN_GAMES - 1, None, None),
+                types::StandingTuple(accepted.id, None, first_day.1, MIN_GAMES - 1, None, None),
+            ],
+        })
+        .unwrap()
+        .none()
+        .unwrap()
+        .none()
+        .unwrap();
+
+    handler
+        .call(
+            model::Request::Leaderboard(model::request::Leaderboard::Get {
+                at: Some(types::Millis::from(107 * DAY)),
+            }),
+            false,
+        )
+        .await
+        .ok(model::Response::Leaderboard {
+            min_games: MIN_GAMES,
+            standings: vec![
+                types::StandingTuple(
+                    player.id,
+                    Some(1),
+                    second_day.0,
+                    MIN_GAMES,
+                    None,
+                    Some(first_day.0),
+                ),
+                types::StandingTuple(
+                    accepted.id,
+                    Some(2),
+                    second_day.1,
+                    MIN_GAMES,
+                    None,
+                    Some(first_day.1),
+                ),
+            ],
+        })
+        .unwrap()
+        .none()
+        .unwrap()
+        .none()
+        .unwrap();
+
+    handler
+        .register(
+            (accepted.id, player.id),
+            (11, 0),
+            false,
+            types::Millis::from(100 * DAY),
+        )
+        .await
+        .unwrap();
+    handler.side_effects().unwrap();
+
+    handler
+        .call(
+            model::Request::Leaderboard(model::request::Leaderboard::Get {
+                at: Some(types::Millis::from(107 * DAY)),
+            }),
+            false,
+        )
+        .await
+        .map_ok(
+            |r| {
+                let model::Response::Leaderboard { standings, .. } = r else {
+                    panic!()
+                };
+                standings.into_iter().map(|s| s.3).collect::<Vec<_>>()
+            },
+            vec![MIN_GAMES + 1, MIN_GAMES + 1],
+        )
+        .unwrap()
+        .none()
+        .unwrap()
+        .none()
+        .unwrap();
+}
+
+#[sqlx::test]
+async fn simulate(pool: SqlitePoolOptions, conn: SqliteConnectOptions) {
+    let (player, store, mut handler, pool) = init!(pool, conn);
+
+    let accepted = handler
+        .invite_full(&player, &store, ACCEPTED_NAME, ACCEPTED_EMAIL)
+        .await
+        .unwrap();
+
+    let game = handler
+        .register(
+            (player.id, accepted.id),
+            (11, 0),
+            false,
+            types::Millis::from(100 * DAY),
+        )
+        .await
+        .unwrap();
+    handler.side_effects().unwrap();
+
+    let simulate = |k_factor| {
+        model::Request::Leaderboard(model::request::Leaderboard::Simulate {
+            k_factor,
+            challenge_multiplier: None,
+        })
+    };
+
+    handler
+        .call(simulate(None), false)
+        .await
+        .err(model::Error::Forbidden)
+        .unwrap();
+
+    super::set_admin(&pool, player.id).await.unwrap();
+
+    let current = (
+        game.rating_one + game.rating_delta,
+        game.rating_two - game.rating_delta,
+    );
+
+    handler
+        .call(simulate(None), false)
+        .await
+        .ok(model::Response::Simulation {
+            min_games: MIN_GAMES,
+            parameters: types::RatingParameters {
+                k_factor: 32.0,
+                challenge_multiplier: 3.0,
+            },
+            comparisons: vec![
+                types::ComparisonTuple(player.id, 1, None, current.0, None, current.0),
+                types::ComparisonTuple(accepted.id, 1, None, current.1, None, current.1),
+            ],
+            movers: Vec::new(),
+        })
+        .unwrap()
+        .none()
+        .unwrap()
+        .none()
+        .unwrap();
+
+    let default = skillratings::elo::EloRating::new().rating;
+    let delta = skillratings::elo::elo(
+        &skillratings::elo::EloRating { rating: default },
+        &skillratings::elo::EloRating { rating: default },
+        &skillratings::Outcomes::WIN,
+        &skillratings::elo::EloConfig { k: 16.0 },
+    )
+    .0
+    .rating
+        - default;
+
+    handler
+        .call(simulate(Some(16.0)), false)
+        .await
+        .ok(model::Response::Simulation {
+            min_games: MIN_GAMES,
+            parameters: types::RatingParameters {
+                k_factor: 16.0,
+                challenge_multiplier: 3.0,
+            },
+            comparisons: vec![
+                types::ComparisonTuple(player.id, 1, None, current.0, None, default + delta),
+                types::ComparisonTuple(accepted.id, 1, None, current.1, None, default - delta),
+            ],
+            movers: vec![player.id, accepted.id],
+        })
+        .unwrap()
+        .none()
+        .unwrap()
+        .none()
+        .unwrap();
+
+    handler
+        .call(simulate(Some(-1.0)), false)
+        .await
+        .err(model::Error::Store(store::Error::InvalidValue(
+            "The K-factor must be positive",
+        )))
+        .unwrap();
+}
+
+#[sqlx::test]
+async fn forbidden(pool: SqlitePoolOptions, conn: SqliteConnectOptions) {
+    let (_, store, mut handler, _) = init!(pool, conn);
+    let invited = handler.invite(INVITED_NAME, INVITED_EMAIL).await.unwrap();
+
+    let mut handler = framework::Handler::pending(&invited.email, &store)
+        .await
+        .unwrap();
+
+    handler
+        .call(
+            model::Request::Leaderboard(model::request::Leaderboard::Get { at: None }),
+            false,
+        )
+        .await
+        .err(model::Error::Forbidden)
+        .unwrap();
+
+    handler
+        .call(
+            model::Request::Leaderboard(model::request::Leaderboard::Simulate {
+                k_factor: None,
+                challenge_multiplier: None,
+            }),
+            false,
+        )
+        .await
+        .err(model::Error::Forbidden)
+        .unwrap();
+}
+
+#[sqlx::test]
+async fn snapshot(pool: SqlitePoolOptions, conn: SqliteConnectOptions) {
+    let (player, store, mut handler, pool) = init!(pool, conn);
+
+    let accepted = handler
+        .invite_full(&player, &store, ACCEPTED_NAME, ACCEPTED_EMAIL)
+        .await
+        .unwrap();
+
+    let three_days_ago = i64::from(now()) - 3 * DAY;
+    for i in 0..MIN_GAMES {
+        handler
+            .register(
+                (player.id, accepted.id),
+                (11, 0),
+                false,
+                types::Millis::from(three_days_ago + i * 1000),
+            )
+            .await
+            .unwrap();
+        handler.side_effects().unwrap();
+    }
+
+    let count = || async {
+        sqlx::query!(r#"SELECT COUNT(*) AS "count!: i64" FROM snapshots"#)
+            .map(|r| r.count)
+            .fetch_one(&pool)
+            .await
+            .unwrap()
+    };
+
+    handler
+        .call(
+            model::Request::Leaderboard(model::request::Leaderboard::Get {
+                at: Some(types::Millis::from(i64::from(now()) - 2 * DAY)),
+            }),
+            false,
+        )
+        .await
+        .map_ok(|_| (), ())
+        .unwrap()
+        .none()
+        .unwrap()
+        .none()
+        .unwrap();
+
+    assert_eq!(count().await, 0);
+
+    crate::handler::snapshot(&store).await.unwrap();
+    assert_eq!(count().await, 2);
+
+    crate::handler::snapshot(&store).await.unwrap();
+    assert_eq!(count().await, 2);
+}
+
+#[sqlx::test]
+async fn rebuild_snapshots(pool: SqlitePoolOptions, conn: SqliteConnectOptions) {
+    let (player, store, mut handler, pool) = init!(pool, conn);
+
+    let accepted = handler
+        .invite_full(&player, &store, ACCEPTED_NAME, ACCEPTED_EMAIL)
+        .await
+        .unwrap();
+
+    let three_days_ago = i64::from(now()) - 3 * DAY;
+    let mut games = Vec::new();
+    for i in 0..MIN_GAMES {
+        games.push(
+            handler
+                .register(
+                    (player.id, accepted.id),
+                    (11, 0),
+                    false,
+                    types::Millis::from(three_days_ago + i * 1000),
+                )
+                .await
+                .unwrap(),
+        );
+        handler.side_effects().unwrap();
+    }
+
+    // A snapshot from before the games, which changes to them do not invalidate
+    let five_days_ago = three_days_ago - 2 * DAY;
+    sqlx::query!(
+        r#"
+        INSERT INTO snapshots (
+            day,
+            min_games,
+            player,
+            rank,
+            rating,
+            games
+        ) VALUES (
+            $1,
+            $2,
+            $3,
+            NULL,
+            0,
+            0
+        )
+        "#,
+        five_days_ago - five_days_ago.rem_euclid(DAY),
+        MIN_GAMES,
+        player.id,
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let snapshots = || async {
+        sqlx::query!(
+            r#"
+            SELECT
+                day,
+                player AS "player: types::Id",
+                rating
+            FROM
+                snapshots
+            ORDER BY
+                day,
+                player
+            "#
+        )
+        .map(|r| (r.day, r.player, r.rating))
+        .fetch_all(&pool)
+        .await
+        .unwrap()
+    };
+
+    // Every day since the latest snapshot is filled in, from the first one with games
+    crate::handler::snapshot(&store).await.unwrap();
+    let before = snapshots().await;
+    assert_eq!(before.len(), 1 + 3 * 2);
+
+    let model::Push::Game(model::push::Game::Updated { .. }) = handler
+        .call(
+            model::Request::Game(model::request::Game::Update {
+                game: types::Game {
+                    score_one: 0,
+                    score_two: 11,
+                    ..games[0].clone()
+                },
+                reason: None,
+            }),
+            true,
+        )
+        .await
+        .done()
+        .unwrap()
+        .none()
+        .unwrap()
+        .some()
+        .unwrap()
+    else {
+        panic!();
+    };
+    handler.side_effects().unwrap();
+
+    assert_eq!(snapshots().await, &before[..1]);
+
+    // The days invalidated by the edit are rebuilt with the new ratings
+    crate::handler::snapshot(&store).await.unwrap();
+    let after = snapshots().await;
+    assert_eq!(after.len(), before.len());
+    assert_eq!(after[0], before[0]);
+    for (after, before) in after.iter().zip(&before).skip(1) {
+        assert_eq!((after.0, after.1), (before.0, before.1));
+        assert_ne!(after.2, before.2);
+    }
+}
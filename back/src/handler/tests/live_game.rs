@@ -0,0 +1,278 @@
+use super::{super::model, *};
+use crate::types;
+
+use sqlx::sqlite::SqliteConnectOptions;
+use sqlx::sqlite::SqlitePoolOptions;
+
+fn request(request: model::request::LiveGame) -> model::Request {
+    model::Request::LiveGame(request)
+}
+
+fn point(side: types::Side) -> model::Request {
+    request(model::request::LiveGame::Point { side })
+}
+
+#[sqlx::test]
+async fn score_and_finish(pool: SqlitePoolOptions, conn: SqliteConnectOptions) {
+    let (player, store, mut handler, pool) = init!(pool, conn);
+
+    let accepted = handler
+        .invite_full(&player, &store, ACCEPTED_NAME, ACCEPTED_EMAIL)
+        .await
+        .unwrap();
+
+    handler
+        .call(request(model::request::LiveGame::List), false)
+        .await
+        .ok(model::Response::LiveGames(Vec::new()))
+        .unwrap()
+        .none()
+        .unwrap()
+        .none()
+        .unwrap();
+
+    let start = request(model::request::LiveGame::Start {
+        player: player.id,
+        opponent: accepted.id,
+    });
+
+    let model::Push::Live(model::push::LiveGame::Started(live)) = handler
+        .call(start.clone(), false)
+        .await
+        .done()
+        .unwrap()
+        .none()
+        .unwrap()
+        .some()
+        .unwrap()
+    else {
+        panic!()
+    };
+
+    assert_eq!(live.scorer, player.id);
+    assert_eq!((live.score_one, live.score_two), (0, 0));
+
+    handler
+        .call(start, false)
+        .await
+        .err(model::Error::Store(store::Error::AlreadyExists))
+        .unwrap();
+
+    let mut points = Vec::new();
+    for side in std::iter::repeat(types::Side::Two)
+        .take(3)
+        .chain(std::iter::repeat(types::Side::One).take(12))
+    {
+        let model::Push::Live(model::push::LiveGame::Updated(live)) = handler
+            .call(point(side), false)
+            .await
+            .done()
+            .unwrap()
+            .none()
+            .unwrap()
+            .some()
+            .unwrap()
+        else {
+            panic!()
+        };
+
+        points.push(types::Point::Won(side));
+        assert_eq!(live.points, points);
+    }
+
+    handler
+        .call(point(types::Side::One), false)
+        .await
+        .err(model::Error::Store(store::Error::InvalidValue(
+            "Games cannot have a score larger than 12",
+        )))
+        .unwrap();
+
+    let model::Push::Live(model::push::LiveGame::Updated(live)) = handler
+        .call(request(model::request::LiveGame::Undo), false)
+        .await
+        .done()
+        .unwrap()
+        .none()
+        .unwrap()
+        .some()
+        .unwrap()
+    else {
+        panic!()
+    };
+
+    points.pop();
+    assert_eq!((live.score_one, live.score_two), (11, 3));
+    assert_eq!(live.points, points);
+
+    // A restarted server picks up where it left off
+    let restarted = store::Store::from(pool.clone());
+    restarted.live_games().load().await.unwrap();
+    assert_eq!(restarted.live_games().list().await, vec![live.clone()]);
+
+    let model::Push::Game(model::push::Game::Registered { game, .. }) = handler
+        .call(request(model::request::LiveGame::Finish), true)
+        .await
+        .done()
+        .unwrap()
+        .none()
+        .unwrap()
+        .some()
+        .unwrap()
+    else {
+        panic!()
+    };
+
+    assert_eq!(
+        (
+            game.player_one,
+            game.player_two,
+            game.score_one,
+            game.score_two
+        ),
+        (player.id, accepted.id, 11, 3)
+    );
+
+    let model::Push::Live(model::push::LiveGame::Finished {
+        live: finished,
+        game: id,
+    }) = handler.push().unwrap()
+    else {
+        panic!()
+    };
+
+    assert_eq!(finished, live);
+    assert_eq!(id, game.id);
+    handler.side_effects().unwrap();
+
+    handler
+        .call(
+            model::Request::Game(model::request::Game::Points(game.id)),
+            false,
+        )
+        .await
+        .ok(model::Response::Points(types::Points {
+            game: game.id,
+            points,
+            longest_run_one: 11,
+            longest_run_two: 3,
+        }))
+        .unwrap()
+        .none()
+        .unwrap()
+        .none()
+        .unwrap();
+
+    handler
+        .call(request(model::request::LiveGame::List), false)
+        .await
+        .ok(model::Response::LiveGames(Vec::new()))
+        .unwrap()
+        .none()
+        .unwrap()
+        .none()
+        .unwrap();
+
+    handler
+        .call(request(model::request::LiveGame::Finish), false)
+        .await
+        .err(model::Error::Store(store::Error::NotFound))
+        .unwrap();
+}
+
+#[sqlx::test]
+async fn cancel(pool: SqlitePoolOptions, conn: SqliteConnectOptions) {
+    let (player, store, mut handler, _) = init!(pool, conn);
+
+    let accepted = handler
+        .invite_full(&player, &store, ACCEPTED_NAME, ACCEPTED_EMAIL)
+        .await
+        .unwrap();
+
+    handler
+        .call(
+            request(model::request::LiveGame::Start {
+                player: player.id,
+                opponent: player.id,
+            }),
+            false,
+        )
+        .await
+        .err(model::Error::Store(store::Error::InvalidValue(
+            "Players cannot be equal",
+        )))
+        .unwrap();
+
+    let model::Push::Live(model::push::LiveGame::Started(live)) = handler
+        .call(
+            request(model::request::LiveGame::Start {
+                player: accepted.id,
+                opponent: player.id,
+            }),
+            false,
+        )
+        .await
+        .done()
+        .unwrap()
+        .none()
+        .unwrap()
+        .some()
+        .unwrap()
+    else {
+        panic!()
+    };
+
+    handler
+        .call(request(model::request::LiveGame::Undo), false)
+        .await
+        .err(model::Error::Store(store::Error::InvalidValue(
+            "There are no points to undo",
+        )))
+        .unwrap();
+
+    handler
+        .call(request(model::request::LiveGame::Finish), false)
+        .await
+        .err(model::Error::Store(store::Error::InvalidValue(
+            "Scores cannot be equal",
+        )))
+        .unwrap();
+
+    let model::Push::Live(model::push::LiveGame::Cancelled(cancelled)) = handler
+        .call(request(model::request::LiveGame::Cancel), false)
+        .await
+        .done()
+        .unwrap()
+        .none()
+        .unwrap()
+        .some()
+        .unwrap()
+    else {
+        panic!()
+    };
+
+    assert_eq!(cancelled, live);
+
+    handler
+        .call(request(model::request::LiveGame::Cancel), false)
+        .await
+        .err(model::Error::Store(store::Error::NotFound))
+        .unwrap();
+}
+
+#[sqlx::test]
+async fn forbidden(pool: SqlitePoolOptions, conn: SqliteConnectOptions) {
+    let (_, store, mut handler, _) = init!(pool, conn);
+
+    let invited = handler.invite(INVITED_NAME, INVITED_EMAIL).await.unwrap();
+
+    let mut handler = framework::Handler::pending(&invited.email, &store)
+        .await
+        .unwrap();
+
+    handler
+        .call(request(model::request::LiveGame::List), false)
+        .await
+        .err(model::Error::Forbidden)
+        .unwrap();
+}
@@ -16,9 +16,17 @@ macro_rules! init {
     }};
 }
 
+mod achievement;
+mod audit;
+mod batch;
 mod game;
 mod invite;
+mod leaderboard;
+mod live_game;
 mod player;
+mod season;
+mod subscription;
+mod tournament;
 
 use crate::{store, types};
 
@@ -61,6 +69,23 @@ async fn add_test_user(pool: &sqlx::sqlite::SqlitePool) -> sqlx::Result<types::P
     .await
 }
 
+async fn set_admin(pool: &sqlx::sqlite::SqlitePool, id: types::Id) -> sqlx::Result<()> {
+    sqlx::query!(
+        r#"
+        UPDATE
+            players
+        SET
+            admin = TRUE
+        WHERE
+            id = $1
+        "#,
+        id,
+    )
+    .execute(pool)
+    .await
+    .map(|_| ())
+}
+
 fn now() -> types::Millis {
     // allow(clippy::cast_possible_truncation): This is just a test
     #[allow(clippy::cast_possible_truncation)]
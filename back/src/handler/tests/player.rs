@@ -258,6 +258,508 @@ async fn repeated_input(pool: SqlitePoolOptions, conn: SqliteConnectOptions) {
         .unwrap();
 }
 
+#[sqlx::test]
+async fn rating_history(pool: SqlitePoolOptions, conn: SqliteConnectOptions) {
+    const DAY: i64 = 24 * 60 * 60 * 1000;
+
+    let (player, store, mut handler, _) = init!(pool, conn);
+    let accepted = handler
+        .invite_full(&player, &store, ACCEPTED_NAME, ACCEPTED_EMAIL)
+        .await
+        .unwrap();
+
+    let first = handler
+        .register(
+            (player.id, accepted.id),
+            (11, 0),
+            false,
+            types::Millis::from(100 * DAY),
+        )
+        .await
+        .unwrap();
+    handler.side_effects().unwrap();
+    let second = handler
+        .register(
+            (accepted.id, player.id),
+            (11, 5),
+            false,
+            types::Millis::from(100 * DAY + 1000),
+        )
+        .await
+        .unwrap();
+    handler.side_effects().unwrap();
+    let third = handler
+        .register(
+            (player.id, accepted.id),
+            (11, 3),
+            false,
+            types::Millis::from(102 * DAY),
+        )
+        .await
+        .unwrap();
+    handler.side_effects().unwrap();
+
+    let steps = [
+        (
+            first.millis,
+            first.rating_one,
+            first.rating_one + first.rating_delta,
+        ),
+        (
+            second.millis,
+            second.rating_two,
+            second.rating_two - second.rating_delta,
+        ),
+        (
+            third.millis,
+            third.rating_one,
+            third.rating_one + third.rating_delta,
+        ),
+    ];
+
+    handler
+        .call(
+            model::Request::Player(model::request::Player::RatingHistory {
+                id: player.id,
+                from: None,
+                to: None,
+                resolution: types::Resolution::Game,
+                season: None,
+            }),
+            false,
+        )
+        .await
+        .ok(model::Response::RatingHistory(
+            steps
+                .map(|(millis, before, after)| {
+                    types::RatingPointTuple(millis, after, before.max(after), before.min(after), 1)
+                })
+                .into_iter()
+                .collect(),
+        ))
+        .unwrap()
+        .none()
+        .unwrap()
+        .none()
+        .unwrap();
+
+    handler
+        .call(
+            model::Request::Player(model::request::Player::RatingHistory {
+                id: player.id,
+                from: None,
+                to: None,
+                resolution: types::Resolution::Day,
+                season: None,
+            }),
+            false,
+        )
+        .await
+        .ok(model::Response::RatingHistory(vec![
+            types::RatingPointTuple(
+                types::Millis::from(100 * DAY),
+                steps[1].2,
+                steps[0].1.max(steps[0].2).max(steps[1].2),
+                steps[0].1.min(steps[0].2).min(steps[1].2),
+                2,
+            ),
+            types::RatingPointTuple(
+                types::Millis::from(102 * DAY),
+                steps[2].2,
+                steps[2].1.max(steps[2].2),
+                steps[2].1.min(steps[2].2),
+                1,
+            ),
+        ]))
+        .unwrap()
+        .none()
+        .unwrap()
+        .none()
+        .unwrap();
+
+    handler
+        .call(
+            model::Request::Player(model::request::Player::RatingHistory {
+                id: player.id,
+                from: Some(types::Millis::from(100 * DAY + 1)),
+                to: Some(types::Millis::from(102 * DAY)),
+                resolution: types::Resolution::Week,
+                season: None,
+            }),
+            false,
+        )
+        .await
+        .ok(model::Response::RatingHistory(vec![
+            types::RatingPointTuple(
+                // Day 100 since the epoch was a Saturday, so the week started on day 95
+                types::Millis::from(95 * DAY),
+                steps[1].2,
+                steps[1].1.max(steps[1].2),
+                steps[1].1.min(steps[1].2),
+                1,
+            ),
+        ]))
+        .unwrap()
+        .none()
+        .unwrap()
+        .none()
+        .unwrap();
+
+    handler
+        .call(
+            model::Request::Player(model::request::Player::RatingHistory {
+                id: 27,
+                from: None,
+                to: None,
+                resolution: types::Resolution::Game,
+                season: None,
+            }),
+            false,
+        )
+        .await
+        .err(model::Error::Store(store::Error::NotFound))
+        .unwrap();
+}
+
+#[sqlx::test]
+async fn suggest_opponents(pool: SqlitePoolOptions, conn: SqliteConnectOptions) {
+    let (player, store, mut handler, _) = init!(pool, conn);
+    let accepted = handler
+        .invite_full(&player, &store, ACCEPTED_NAME, ACCEPTED_EMAIL)
+        .await
+        .unwrap();
+    let other = handler
+        .invite_full(&player, &store, "other", "other@email.com")
+        .await
+        .unwrap();
+
+    let game = handler
+        .register((player.id, accepted.id), (11, 0), true, super::now())
+        .await
+        .unwrap();
+    handler.side_effects().unwrap();
+
+    // Having just played a challenge, the untouched opponent comes first
+    handler
+        .call(
+            model::Request::Player(model::request::Player::SuggestOpponents),
+            false,
+        )
+        .await
+        .map_ok(
+            |r| match r {
+                model::Response::Suggestions(suggestions) => suggestions
+                    .into_iter()
+                    .map(|s| (s.0, s.1, s.2, s.3))
+                    .collect::<Vec<_>>(),
+                r => panic!("{r:?}"),
+            },
+            vec![
+                (
+                    other.id,
+                    skillratings::elo::EloRating::new().rating,
+                    None,
+                    true,
+                ),
+                (
+                    accepted.id,
+                    game.rating_two - game.rating_delta,
+                    Some(game.millis),
+                    false,
+                ),
+            ],
+        )
+        .unwrap()
+        .none()
+        .unwrap()
+        .none()
+        .unwrap();
+}
+
+// Suggestions and registration share the rule for challenging each other once a day
+#[sqlx::test]
+async fn suggested_challenges(pool: SqlitePoolOptions, conn: SqliteConnectOptions) {
+    async fn challengeable(
+        handler: &mut framework::Handler<super::super::access::Regular>,
+        opponent: types::Id,
+    ) -> bool {
+        let model::Response::Suggestions(suggestions) = handler
+            .call(
+                model::Request::Player(model::request::Player::SuggestOpponents),
+                false,
+            )
+            .await
+            .raw()
+            .unwrap()
+        else {
+            panic!()
+        };
+
+        suggestions.into_iter().find(|s| s.0 == opponent).unwrap().3
+    }
+
+    let (player, store, mut handler, _) = init!(pool, conn);
+    let accepted = handler
+        .invite_full(&player, &store, ACCEPTED_NAME, ACCEPTED_EMAIL)
+        .await
+        .unwrap();
+
+    let game = handler
+        .register((player.id, accepted.id), (11, 0), true, super::now())
+        .await
+        .unwrap();
+    handler.side_effects().unwrap();
+
+    assert!(!challengeable(&mut handler, accepted.id).await);
+    handler
+        .call(
+            model::Request::Game(model::request::Game::Register {
+                player: player.id,
+                opponent: accepted.id,
+                score: 11,
+                opponent_score: 0,
+                challenge: true,
+                millis: super::now(),
+                comment: None,
+                venue: None,
+                first_server: None,
+            }),
+            false,
+        )
+        .await
+        .err(model::Error::Store(store::Error::InvalidValue(
+            "Players cannot challenge each other more than once a day",
+        )))
+        .unwrap();
+
+    // Once the challenge becomes a regular game, both allow challenging again
+    let model::Push::Game(model::push::Game::Updated { .. }) = handler
+        .call(
+            model::Request::Game(model::request::Game::Update {
+                game: types::Game {
+                    challenge: false,
+                    ..game
+                },
+                reason: None,
+            }),
+            true,
+        )
+        .await
+        .done()
+        .unwrap()
+        .none()
+        .unwrap()
+        .some()
+        .unwrap()
+    else {
+        panic!()
+    };
+    handler.side_effects().unwrap();
+
+    assert!(challengeable(&mut handler, accepted.id).await);
+    handler
+        .register((player.id, accepted.id), (11, 0), true, super::now())
+        .await
+        .unwrap();
+    handler.side_effects().unwrap();
+
+    assert!(!challengeable(&mut handler, accepted.id).await);
+}
+
+#[sqlx::test]
+async fn streaks(pool: SqlitePoolOptions, conn: SqliteConnectOptions) {
+    let (player, store, mut handler, _) = init!(pool, conn);
+    let accepted = handler
+        .invite_full(&player, &store, ACCEPTED_NAME, ACCEPTED_EMAIL)
+        .await
+        .unwrap();
+
+    let streak_pushes = |pushes: Vec<model::Push>| {
+        pushes
+            .into_iter()
+            .filter_map(|p| match p {
+                model::Push::Streak(streak) => Some(format!("{streak:?}")),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let start = i64::from(super::now()) - 100;
+    let mut side_effects = Vec::new();
+    for i in 0..3 {
+        handler
+            .register(
+                (player.id, accepted.id),
+                (11, 4),
+                false,
+                types::Millis::from(start + i * 10),
+            )
+            .await
+            .unwrap();
+        side_effects.extend(handler.side_effects().unwrap());
+    }
+
+    let winning = types::Streak {
+        player: player.id,
+        current: 3,
+        longest_win: 3,
+        longest_loss: 0,
+    };
+    let losing = types::Streak {
+        player: accepted.id,
+        current: -3,
+        longest_win: 0,
+        longest_loss: 3,
+    };
+
+    assert_eq!(
+        streak_pushes(side_effects),
+        vec![
+            format!("{:?}", model::push::Streak::Started(winning.clone())),
+            format!("{:?}", model::push::Streak::Started(losing.clone())),
+        ]
+    );
+
+    handler
+        .call(
+            model::Request::Player(model::request::Player::Streaks { season: None }),
+            false,
+        )
+        .await
+        .ok(model::Response::Streaks(vec![
+            winning.clone().into(),
+            losing.clone().into(),
+        ]))
+        .unwrap()
+        .none()
+        .unwrap()
+        .none()
+        .unwrap();
+
+    // Rewriting history in the middle breaks the streaks
+    handler
+        .register(
+            (accepted.id, player.id),
+            (11, 4),
+            false,
+            types::Millis::from(start + 15),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(
+        streak_pushes(handler.side_effects().unwrap()),
+        vec![
+            format!("{:?}", model::push::Streak::Broken(winning)),
+            format!("{:?}", model::push::Streak::Broken(losing)),
+        ]
+    );
+
+    handler
+        .call(
+            model::Request::Player(model::request::Player::Streaks { season: None }),
+            false,
+        )
+        .await
+        .ok(model::Response::Streaks(vec![
+            types::StreakTuple(player.id, 1, 2, 1),
+            types::StreakTuple(accepted.id, -1, 1, 2),
+        ]))
+        .unwrap()
+        .none()
+        .unwrap()
+        .none()
+        .unwrap();
+
+    // Replaying from the start agrees with replaying from the change
+    assert!(store.streaks().refresh(None).await.unwrap().is_empty());
+}
+
+#[sqlx::test]
+async fn streaks_fail_with_their_game(pool: SqlitePoolOptions, conn: SqliteConnectOptions) {
+    let (player, store, mut handler, pool) = init!(pool, conn);
+    let accepted = handler
+        .invite_full(&player, &store, ACCEPTED_NAME, ACCEPTED_EMAIL)
+        .await
+        .unwrap();
+
+    sqlx::query!(
+        r#"
+        CREATE TRIGGER
+            broken_streaks
+        BEFORE INSERT ON
+            streaks
+        BEGIN
+            SELECT RAISE(ABORT, 'broken');
+        END
+        "#
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let register = model::Request::Game(model::request::Game::Register {
+        player: player.id,
+        opponent: accepted.id,
+        score: 11,
+        opponent_score: 4,
+        challenge: false,
+        millis: super::now(),
+        comment: None,
+        venue: None,
+        first_server: None,
+    });
+
+    // Failing to replay the streaks fails the whole write
+    assert!(matches!(
+        handler.call(register.clone(), false).await.raw(),
+        Err(model::Error::Store(store::Error::Query(_)))
+    ));
+    assert!(handler.side_effects().unwrap().is_empty());
+
+    handler
+        .call(model::Request::Game(model::request::Game::List), false)
+        .await
+        .ok(model::Response::Games(Vec::new()))
+        .unwrap()
+        .none()
+        .unwrap()
+        .none()
+        .unwrap();
+
+    sqlx::query!("DROP TRIGGER broken_streaks")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    handler
+        .call(register, true)
+        .await
+        .done()
+        .unwrap()
+        .none()
+        .unwrap()
+        .some()
+        .unwrap();
+    handler.side_effects().unwrap();
+
+    handler
+        .call(
+            model::Request::Player(model::request::Player::Streaks { season: None }),
+            false,
+        )
+        .await
+        .ok(model::Response::Streaks(vec![
+            types::StreakTuple(player.id, 1, 1, 0),
+            types::StreakTuple(accepted.id, -1, 0, 1),
+        ]))
+        .unwrap()
+        .none()
+        .unwrap()
+        .none()
+        .unwrap();
+}
+
 #[sqlx::test]
 async fn forbidden(pool: SqlitePoolOptions, conn: SqliteConnectOptions) {
     let (_, store, mut handler, _) = init!(pool, conn);
@@ -281,4 +783,37 @@ async fn forbidden(pool: SqlitePoolOptions, conn: SqliteConnectOptions) {
         .await
         .err(model::Error::Forbidden)
         .unwrap();
+
+    handler
+        .call(
+            model::Request::Player(model::request::Player::RatingHistory {
+                id: 0,
+                from: None,
+                to: None,
+                resolution: types::Resolution::Game,
+                season: None,
+            }),
+            false,
+        )
+        .await
+        .err(model::Error::Forbidden)
+        .unwrap();
+
+    handler
+        .call(
+            model::Request::Player(model::request::Player::SuggestOpponents),
+            false,
+        )
+        .await
+        .err(model::Error::Forbidden)
+        .unwrap();
+
+    handler
+        .call(
+            model::Request::Player(model::request::Player::Streaks { season: None }),
+            false,
+        )
+        .await
+        .err(model::Error::Forbidden)
+        .unwrap();
 }
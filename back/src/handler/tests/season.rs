@@ -0,0 +1,519 @@
+use super::{super::model, *};
+use crate::types;
+
+use sqlx::sqlite::SqliteConnectOptions;
+use sqlx::sqlite::SqlitePoolOptions;
+
+const DAY: i64 = 24 * 60 * 60 * 1000;
+
+fn create(name: &str, start: i64, end: i64, reset: f64) -> model::Request {
+    model::Request::Season(model::request::Season::Create {
+        name: String::from(name),
+        start: types::Millis::from(start),
+        end: types::Millis::from(end),
+        reset,
+    })
+}
+
+#[sqlx::test]
+async fn create_and_delete(pool: SqlitePoolOptions, conn: SqliteConnectOptions) {
+    let (player, store, mut handler, pool) = init!(pool, conn);
+    super::set_admin(&pool, player.id).await.unwrap();
+
+    let accepted = handler
+        .invite_full(&player, &store, ACCEPTED_NAME, ACCEPTED_EMAIL)
+        .await
+        .unwrap();
+
+    let first = handler
+        .register(
+            (player.id, accepted.id),
+            (11, 0),
+            false,
+            types::Millis::from(100 * DAY),
+        )
+        .await
+        .unwrap();
+    handler.side_effects().unwrap();
+
+    let second = handler
+        .register(
+            (player.id, accepted.id),
+            (11, 0),
+            false,
+            types::Millis::from(150 * DAY),
+        )
+        .await
+        .unwrap();
+    handler.side_effects().unwrap();
+
+    assert!((second.rating_one - (first.rating_one + first.rating_delta)).abs() <= f64::EPSILON);
+
+    let model::Push::Season(model::push::Season::Created { season, updates }) = handler
+        .call(create("first", 101 * DAY, 200 * DAY, 1.0), true)
+        .await
+        .done()
+        .unwrap()
+        .none()
+        .unwrap()
+        .some()
+        .unwrap()
+    else {
+        panic!()
+    };
+    handler.side_effects().unwrap();
+
+    assert_eq!(season.name, "first");
+    assert_eq!(updates.len(), 1);
+
+    let updated = types::Game::from(updates.into_iter().next().unwrap());
+    assert_eq!(updated.id, second.id);
+    assert!(
+        (updated.rating_one - skillratings::elo::EloRating::new().rating).abs() <= f64::EPSILON
+    );
+    assert!(
+        (updated.rating_two - skillratings::elo::EloRating::new().rating).abs() <= f64::EPSILON
+    );
+
+    handler
+        .call(model::Request::Season(model::request::Season::List), false)
+        .await
+        .ok(model::Response::Seasons(vec![season.clone().into()]))
+        .unwrap()
+        .none()
+        .unwrap()
+        .none()
+        .unwrap();
+
+    let model::Push::Season(model::push::Season::Deleted {
+        season: deleted,
+        updates,
+    }) = handler
+        .call(
+            model::Request::Season(model::request::Season::Delete(season.id)),
+            true,
+        )
+        .await
+        .done()
+        .unwrap()
+        .none()
+        .unwrap()
+        .some()
+        .unwrap()
+    else {
+        panic!()
+    };
+    handler.side_effects().unwrap();
+
+    assert_eq!(deleted, season);
+    assert_eq!(updates, vec![types::GameTuple::from(second)]);
+
+    handler
+        .call(model::Request::Season(model::request::Season::List), false)
+        .await
+        .ok(model::Response::Seasons(Vec::new()))
+        .unwrap()
+        .none()
+        .unwrap()
+        .none()
+        .unwrap();
+}
+
+#[sqlx::test]
+async fn standings(pool: SqlitePoolOptions, conn: SqliteConnectOptions) {
+    let (player, store, mut handler, pool) = init!(pool, conn);
+    super::set_admin(&pool, player.id).await.unwrap();
+
+    let accepted = handler
+        .invite_full(&player, &store, ACCEPTED_NAME, ACCEPTED_EMAIL)
+        .await
+        .unwrap();
+
+    let first = handler
+        .register(
+            (player.id, accepted.id),
+            (11, 0),
+            false,
+            types::Millis::from(100 * DAY),
+        )
+        .await
+        .unwrap();
+    handler.side_effects().unwrap();
+
+    handler
+        .register(
+            (player.id, accepted.id),
+            (11, 0),
+            false,
+            types::Millis::from(150 * DAY),
+        )
+        .await
+        .unwrap();
+    handler.side_effects().unwrap();
+
+    let model::Push::Season(model::push::Season::Created { season, updates }) = handler
+        .call(create("first", 101 * DAY, 200 * DAY, 1.0), true)
+        .await
+        .done()
+        .unwrap()
+        .none()
+        .unwrap()
+        .some()
+        .unwrap()
+    else {
+        panic!()
+    };
+    handler.side_effects().unwrap();
+
+    let second = types::Game::from(updates.into_iter().next().unwrap());
+
+    handler
+        .call(
+            model::Request::Season(model::request::Season::Games(season.id)),
+            false,
+        )
+        .await
+        .ok(model::Response::Games(vec![second.clone().into()]))
+        .unwrap()
+        .none()
+        .unwrap()
+        .none()
+        .unwrap();
+
+    handler
+        .call(
+            model::Request::Season(model::request::Season::Standings(season.id)),
+            false,
+        )
+        .await
+        .ok(model::Response::Leaderboard {
+            min_games: super::super::MIN_GAMES,
+            standings: vec![
+                types::StandingTuple(
+                    player.id,
+                    None,
+                    second.rating_one + second.rating_delta,
+                    1,
+                    None,
+                    None,
+                ),
+                types::StandingTuple(
+                    accepted.id,
+                    None,
+                    second.rating_two - second.rating_delta,
+                    1,
+                    None,
+                    None,
+                ),
+            ],
+        })
+        .unwrap()
+        .none()
+        .unwrap()
+        .none()
+        .unwrap();
+
+    let all_time = (
+        first.rating_one + first.rating_delta,
+        first.rating_two - first.rating_delta,
+    );
+    let delta = skillratings::elo::elo(
+        &skillratings::elo::EloRating { rating: all_time.0 },
+        &skillratings::elo::EloRating { rating: all_time.1 },
+        &skillratings::Outcomes::WIN,
+        &skillratings::elo::EloConfig::new(),
+    )
+    .0
+    .rating
+        - all_time.0;
+
+    handler
+        .call(
+            model::Request::Leaderboard(model::request::Leaderboard::AllTime),
+            false,
+        )
+        .await
+        .ok(model::Response::Leaderboard {
+            min_games: super::super::MIN_GAMES,
+            standings: vec![
+                types::StandingTuple(player.id, None, all_time.0 + delta, 2, None, None),
+                types::StandingTuple(accepted.id, None, all_time.1 - delta, 2, None, None),
+            ],
+        })
+        .unwrap()
+        .none()
+        .unwrap()
+        .none()
+        .unwrap();
+}
+
+#[sqlx::test]
+async fn archive(pool: SqlitePoolOptions, conn: SqliteConnectOptions) {
+    let (player, store, mut handler, pool) = init!(pool, conn);
+    super::set_admin(&pool, player.id).await.unwrap();
+
+    let accepted = handler
+        .invite_full(&player, &store, ACCEPTED_NAME, ACCEPTED_EMAIL)
+        .await
+        .unwrap();
+
+    handler
+        .register(
+            (player.id, accepted.id),
+            (11, 0),
+            false,
+            types::Millis::from(150 * DAY),
+        )
+        .await
+        .unwrap();
+    handler.side_effects().unwrap();
+
+    let model::Push::Season(model::push::Season::Created { season, .. }) = handler
+        .call(create("first", 101 * DAY, 200 * DAY, 1.0), true)
+        .await
+        .done()
+        .unwrap()
+        .none()
+        .unwrap()
+        .some()
+        .unwrap()
+    else {
+        panic!()
+    };
+    handler.side_effects().unwrap();
+
+    let count = || async {
+        sqlx::query!(r#"SELECT COUNT(*) AS "count!: i64" FROM standings"#)
+            .map(|r| r.count)
+            .fetch_one(&pool)
+            .await
+            .unwrap()
+    };
+
+    let standings = handler
+        .call(
+            model::Request::Season(model::request::Season::Standings(season.id)),
+            false,
+        )
+        .await
+        .raw()
+        .unwrap();
+
+    // Reading the standings of an ended season never archives them
+    assert_eq!(count().await, 0);
+
+    crate::handler::snapshot(&store).await.unwrap();
+    assert_eq!(count().await, 2);
+
+    crate::handler::snapshot(&store).await.unwrap();
+    assert_eq!(count().await, 2);
+
+    handler
+        .call(
+            model::Request::Season(model::request::Season::Standings(season.id)),
+            false,
+        )
+        .await
+        .ok(standings)
+        .unwrap()
+        .none()
+        .unwrap()
+        .none()
+        .unwrap();
+}
+
+#[sqlx::test]
+async fn scoped_stats(pool: SqlitePoolOptions, conn: SqliteConnectOptions) {
+    let (player, store, mut handler, pool) = init!(pool, conn);
+    super::set_admin(&pool, player.id).await.unwrap();
+
+    let accepted = handler
+        .invite_full(&player, &store, ACCEPTED_NAME, ACCEPTED_EMAIL)
+        .await
+        .unwrap();
+
+    handler
+        .register(
+            (player.id, accepted.id),
+            (11, 0),
+            false,
+            types::Millis::from(100 * DAY),
+        )
+        .await
+        .unwrap();
+    handler.side_effects().unwrap();
+
+    handler
+        .register(
+            (accepted.id, player.id),
+            (11, 0),
+            false,
+            types::Millis::from(150 * DAY),
+        )
+        .await
+        .unwrap();
+    handler.side_effects().unwrap();
+
+    let model::Push::Season(model::push::Season::Created { season, updates }) = handler
+        .call(create("first", 101 * DAY, 200 * DAY, 1.0), true)
+        .await
+        .done()
+        .unwrap()
+        .none()
+        .unwrap()
+        .some()
+        .unwrap()
+    else {
+        panic!()
+    };
+    handler.side_effects().unwrap();
+
+    let second = types::Game::from(updates.into_iter().next().unwrap());
+    let (before, after) = (second.rating_two, second.rating_two - second.rating_delta);
+
+    handler
+        .call(
+            model::Request::Player(model::request::Player::RatingHistory {
+                id: player.id,
+                from: None,
+                to: None,
+                resolution: types::Resolution::Game,
+                season: Some(season.id),
+            }),
+            false,
+        )
+        .await
+        .ok(model::Response::RatingHistory(vec![
+            types::RatingPointTuple(
+                second.millis,
+                after,
+                before.max(after),
+                before.min(after),
+                1,
+            ),
+        ]))
+        .unwrap()
+        .none()
+        .unwrap()
+        .none()
+        .unwrap();
+
+    // The win before the season does not count towards its streaks
+    handler
+        .call(
+            model::Request::Player(model::request::Player::Streaks {
+                season: Some(season.id),
+            }),
+            false,
+        )
+        .await
+        .ok(model::Response::Streaks(vec![
+            types::StreakTuple(player.id, -1, 0, 1),
+            types::StreakTuple(accepted.id, 1, 1, 0),
+        ]))
+        .unwrap()
+        .none()
+        .unwrap()
+        .none()
+        .unwrap();
+
+    handler
+        .call(
+            model::Request::Player(model::request::Player::Streaks { season: Some(27) }),
+            false,
+        )
+        .await
+        .err(model::Error::Store(store::Error::NotFound))
+        .unwrap();
+}
+
+#[sqlx::test]
+async fn invalid_input(pool: SqlitePoolOptions, conn: SqliteConnectOptions) {
+    let (player, _, mut handler, pool) = init!(pool, conn);
+    super::set_admin(&pool, player.id).await.unwrap();
+
+    handler
+        .call(create(WHITE_SPACE, 0, DAY, 1.0), false)
+        .await
+        .err(model::Error::Store(store::Error::BlankValue("name")))
+        .unwrap();
+
+    handler
+        .call(create("season", DAY, DAY, 1.0), false)
+        .await
+        .err(model::Error::Store(store::Error::InvalidValue(
+            "Seasons must end after they start",
+        )))
+        .unwrap();
+
+    handler
+        .call(create("season", 0, DAY, 1.5), false)
+        .await
+        .err(model::Error::Store(store::Error::InvalidValue(
+            "Season resets must be between 0 and 1",
+        )))
+        .unwrap();
+
+    let model::Push::Season(model::push::Season::Created { .. }) = handler
+        .call(create("season", 0, 2 * DAY, 0.5), true)
+        .await
+        .done()
+        .unwrap()
+        .none()
+        .unwrap()
+        .some()
+        .unwrap()
+    else {
+        panic!()
+    };
+    handler.side_effects().unwrap();
+
+    handler
+        .call(create("other", DAY, 3 * DAY, 0.5), false)
+        .await
+        .err(model::Error::Store(store::Error::InvalidValue(
+            "Seasons cannot overlap",
+        )))
+        .unwrap();
+
+    handler
+        .call(
+            model::Request::Season(model::request::Season::Delete(27)),
+            false,
+        )
+        .await
+        .err(model::Error::Store(store::Error::NotFound))
+        .unwrap();
+}
+
+#[sqlx::test]
+async fn forbidden(pool: SqlitePoolOptions, conn: SqliteConnectOptions) {
+    let (_, store, mut handler, _) = init!(pool, conn);
+
+    handler
+        .call(create("season", 0, DAY, 1.0), false)
+        .await
+        .err(model::Error::Forbidden)
+        .unwrap();
+
+    handler
+        .call(
+            model::Request::Season(model::request::Season::Delete(0)),
+            false,
+        )
+        .await
+        .err(model::Error::Forbidden)
+        .unwrap();
+
+    let invited = handler.invite(INVITED_NAME, INVITED_EMAIL).await.unwrap();
+
+    let mut handler = framework::Handler::pending(&invited.email, &store)
+        .await
+        .unwrap();
+
+    handler
+        .call(model::Request::Season(model::request::Season::List), false)
+        .await
+        .err(model::Error::Forbidden)
+        .unwrap();
+}
@@ -0,0 +1,156 @@
+use super::{super::model, *};
+
+use sqlx::sqlite::SqliteConnectOptions;
+use sqlx::sqlite::SqlitePoolOptions;
+
+fn request(request: model::request::Subscription) -> model::Request {
+    model::Request::Subscription(request)
+}
+
+#[sqlx::test]
+async fn filter_games(pool: SqlitePoolOptions, conn: SqliteConnectOptions) {
+    let (player, store, mut handler, _) = init!(pool, conn);
+
+    let accepted = handler
+        .invite_full(&player, &store, ACCEPTED_NAME, ACCEPTED_EMAIL)
+        .await
+        .unwrap();
+
+    let game = handler
+        .register((player.id, accepted.id), (11, 3), false, super::now())
+        .await
+        .unwrap();
+    handler.side_effects().unwrap();
+
+    let registered = model::Push::Game(model::push::Game::Registered {
+        game: game.clone(),
+        updates: Vec::new(),
+    });
+    // The other player only shows up in the games whose ratings changed
+    let updated = model::Push::Game(model::push::Game::Updated {
+        game: game.clone(),
+        updates: vec![types::Game {
+            player_two: accepted.id + 1,
+            ..game.clone()
+        }
+        .into()],
+    });
+    let recorded = model::Push::Game(model::push::Game::PointsRecorded {
+        game: game.id,
+        player_one: game.player_one,
+        player_two: game.player_two,
+    });
+    let renamed = model::Push::Player(model::push::Player::Renamed {
+        player: player.id,
+        old: String::from(TESTER_NAME),
+        new: String::from("new"),
+    });
+
+    handler
+        .call(request(model::request::Subscription::List), false)
+        .await
+        .ok(model::Response::Topics(
+            model::push::Topic::DEFAULT.to_vec(),
+        ))
+        .unwrap()
+        .none()
+        .unwrap()
+        .none()
+        .unwrap();
+
+    assert!(handler.accepts(&registered));
+    assert!(handler.accepts(&renamed));
+
+    handler
+        .call(
+            request(model::request::Subscription::Unsubscribe(
+                model::push::Topic::DEFAULT.to_vec(),
+            )),
+            false,
+        )
+        .await
+        .ok(model::Response::Topics(Vec::new()))
+        .unwrap()
+        .none()
+        .unwrap()
+        .none()
+        .unwrap();
+
+    assert!(!handler.accepts(&registered));
+    assert!(!handler.accepts(&renamed));
+
+    handler
+        .call(
+            request(model::request::Subscription::Subscribe(vec![
+                model::push::Topic::GamesOf(accepted.id + 1),
+            ])),
+            false,
+        )
+        .await
+        .ok(model::Response::Topics(vec![model::push::Topic::GamesOf(
+            accepted.id + 1,
+        )]))
+        .unwrap()
+        .none()
+        .unwrap()
+        .none()
+        .unwrap();
+
+    assert!(!handler.accepts(&registered));
+    assert!(handler.accepts(&updated));
+    assert!(!handler.accepts(&recorded));
+
+    handler
+        .call(
+            request(model::request::Subscription::Subscribe(vec![
+                model::push::Topic::GamesOf(accepted.id),
+            ])),
+            false,
+        )
+        .await
+        .ok(model::Response::Topics(vec![
+            model::push::Topic::GamesOf(accepted.id),
+            model::push::Topic::GamesOf(accepted.id + 1),
+        ]))
+        .unwrap()
+        .none()
+        .unwrap()
+        .none()
+        .unwrap();
+
+    assert!(handler.accepts(&registered));
+    assert!(handler.accepts(&recorded));
+    assert!(!handler.accepts(&renamed));
+}
+
+#[sqlx::test]
+async fn pending(pool: SqlitePoolOptions, conn: SqliteConnectOptions) {
+    let (_, store, mut handler, _) = init!(pool, conn);
+
+    let invited = handler.invite(INVITED_NAME, INVITED_EMAIL).await.unwrap();
+
+    let mut handler = framework::Handler::pending(&invited.email, &store)
+        .await
+        .unwrap();
+
+    handler
+        .call(
+            request(model::request::Subscription::Unsubscribe(vec![
+                model::push::Topic::Games,
+            ])),
+            false,
+        )
+        .await
+        .map_ok(
+            |r| match r {
+                model::Response::Topics(topics) => topics.contains(&model::push::Topic::Games),
+                r => panic!("{r:?}"),
+            },
+            false,
+        )
+        .unwrap()
+        .none()
+        .unwrap()
+        .none()
+        .unwrap();
+}
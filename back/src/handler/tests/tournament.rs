@@ -0,0 +1,774 @@
+use super::{super::model, *};
+use crate::types;
+
+use sqlx::sqlite::SqliteConnectOptions;
+use sqlx::sqlite::SqlitePoolOptions;
+
+const OTHER_NAME: &str = "other";
+const OTHER_EMAIL: &str = "other@email.com";
+const ANOTHER_NAME: &str = "another";
+const ANOTHER_EMAIL: &str = "another@email.com";
+
+fn create(format: types::Format, players: Vec<types::Id>, groups: i64) -> model::Request {
+    model::Request::Tournament(model::request::Tournament::Create {
+        name: String::from("tournament"),
+        format,
+        players,
+        groups,
+        advancing: 1,
+    })
+}
+
+fn link(fixture: types::Id, game: types::Id) -> model::Request {
+    model::Request::Tournament(model::request::Tournament::Link { fixture, game })
+}
+
+#[sqlx::test]
+async fn round_robin(pool: SqlitePoolOptions, conn: SqliteConnectOptions) {
+    let (player, store, mut handler, pool) = init!(pool, conn);
+    super::set_admin(&pool, player.id).await.unwrap();
+
+    let accepted = handler
+        .invite_full(&player, &store, ACCEPTED_NAME, ACCEPTED_EMAIL)
+        .await
+        .unwrap();
+    let other = handler
+        .invite_full(&player, &store, OTHER_NAME, OTHER_EMAIL)
+        .await
+        .unwrap();
+
+    let model::Push::Tournament(model::push::Tournament::Created {
+        tournament,
+        fixtures,
+    }) = handler
+        .call(
+            create(
+                types::Format::RoundRobin,
+                vec![player.id, accepted.id, other.id],
+                0,
+            ),
+            true,
+        )
+        .await
+        .done()
+        .unwrap()
+        .none()
+        .unwrap()
+        .some()
+        .unwrap()
+    else {
+        panic!()
+    };
+
+    assert_eq!(tournament.format, types::Format::RoundRobin);
+    assert_eq!((tournament.groups, tournament.advancing), (1, 1));
+    assert_eq!(
+        fixtures,
+        vec![
+            types::FixtureTuple(
+                1,
+                1,
+                Some(0),
+                0,
+                0,
+                Some(accepted.id),
+                Some(other.id),
+                None,
+                None
+            ),
+            types::FixtureTuple(
+                2,
+                1,
+                Some(0),
+                1,
+                0,
+                Some(player.id),
+                Some(other.id),
+                None,
+                None
+            ),
+            types::FixtureTuple(
+                3,
+                1,
+                Some(0),
+                2,
+                0,
+                Some(player.id),
+                Some(accepted.id),
+                None,
+                None
+            ),
+        ]
+    );
+
+    handler
+        .call(
+            model::Request::Tournament(model::request::Tournament::List),
+            false,
+        )
+        .await
+        .ok(model::Response::Tournaments(vec![tournament.into()]))
+        .unwrap()
+        .none()
+        .unwrap()
+        .none()
+        .unwrap();
+
+    handler
+        .call(
+            model::Request::Tournament(model::request::Tournament::Fixtures(1)),
+            false,
+        )
+        .await
+        .ok(model::Response::Fixtures(fixtures))
+        .unwrap()
+        .none()
+        .unwrap()
+        .none()
+        .unwrap();
+}
+
+#[sqlx::test]
+async fn single_elimination(pool: SqlitePoolOptions, conn: SqliteConnectOptions) {
+    let (player, store, mut handler, pool) = init!(pool, conn);
+    super::set_admin(&pool, player.id).await.unwrap();
+
+    let accepted = handler
+        .invite_full(&player, &store, ACCEPTED_NAME, ACCEPTED_EMAIL)
+        .await
+        .unwrap();
+    let other = handler
+        .invite_full(&player, &store, OTHER_NAME, OTHER_EMAIL)
+        .await
+        .unwrap();
+
+    let model::Push::Tournament(model::push::Tournament::Created {
+        tournament,
+        fixtures,
+    }) = handler
+        .call(
+            create(
+                types::Format::SingleElimination,
+                vec![player.id, accepted.id, other.id],
+                0,
+            ),
+            true,
+        )
+        .await
+        .done()
+        .unwrap()
+        .none()
+        .unwrap()
+        .some()
+        .unwrap()
+    else {
+        panic!()
+    };
+
+    // The top seed gets a bye straight into the final
+    assert_eq!(
+        fixtures,
+        vec![
+            types::FixtureTuple(
+                1,
+                1,
+                None,
+                0,
+                0,
+                Some(player.id),
+                None,
+                None,
+                Some(player.id)
+            ),
+            types::FixtureTuple(
+                2,
+                1,
+                None,
+                0,
+                1,
+                Some(accepted.id),
+                Some(other.id),
+                None,
+                None
+            ),
+            types::FixtureTuple(3, 1, None, 1, 0, Some(player.id), None, None, None),
+        ]
+    );
+
+    handler
+        .call(link(3, 1), false)
+        .await
+        .err(model::Error::Store(store::Error::InvalidValue(
+            "Fixture is still waiting for its players",
+        )))
+        .unwrap();
+
+    let semi_final = handler
+        .register((other.id, accepted.id), (11, 5), false, super::now())
+        .await
+        .unwrap();
+    handler.side_effects().unwrap();
+
+    handler
+        .call(link(2, semi_final.id), true)
+        .await
+        .done()
+        .unwrap()
+        .none()
+        .unwrap()
+        .some()
+        .map(|p| {
+            assert_eq!(
+                format!("{p:?}"),
+                format!(
+                    "{:?}",
+                    model::Push::Tournament(model::push::Tournament::Updated {
+                        tournament: tournament.clone(),
+                        fixtures: vec![
+                            types::FixtureTuple(
+                                2,
+                                1,
+                                None,
+                                0,
+                                1,
+                                Some(accepted.id),
+                                Some(other.id),
+                                Some(semi_final.id),
+                                Some(other.id),
+                            ),
+                            types::FixtureTuple(
+                                3,
+                                1,
+                                None,
+                                1,
+                                0,
+                                Some(player.id),
+                                Some(other.id),
+                                None,
+                                None,
+                            ),
+                        ],
+                    })
+                )
+            );
+        })
+        .unwrap();
+
+    handler
+        .call(link(2, semi_final.id), false)
+        .await
+        .err(model::Error::Store(store::Error::InvalidValue(
+            "Fixture has already been decided",
+        )))
+        .unwrap();
+
+    handler
+        .call(link(3, semi_final.id), false)
+        .await
+        .err(model::Error::Store(store::Error::InvalidValue(
+            "Game players do not match the fixture",
+        )))
+        .unwrap();
+
+    let final_game = handler
+        .register((player.id, other.id), (7, 11), false, super::now())
+        .await
+        .unwrap();
+    handler.side_effects().unwrap();
+
+    handler
+        .call(link(3, final_game.id), true)
+        .await
+        .done()
+        .unwrap()
+        .none()
+        .unwrap()
+        .some()
+        .map(|p| {
+            assert_eq!(
+                format!("{p:?}"),
+                format!(
+                    "{:?}",
+                    model::Push::Tournament(model::push::Tournament::Updated {
+                        tournament: tournament.clone(),
+                        fixtures: vec![types::FixtureTuple(
+                            3,
+                            1,
+                            None,
+                            1,
+                            0,
+                            Some(player.id),
+                            Some(other.id),
+                            Some(final_game.id),
+                            Some(other.id),
+                        )],
+                    })
+                )
+            );
+        })
+        .unwrap();
+
+    handler
+        .call(
+            model::Request::Game(model::request::Game::Update {
+                game: types::Game {
+                    deleted: true,
+                    ..final_game
+                },
+                reason: None,
+            }),
+            false,
+        )
+        .await
+        .err(model::Error::Store(store::Error::InvalidValue(
+            "Games linked to a tournament cannot change their outcome",
+        )))
+        .unwrap();
+}
+
+#[sqlx::test]
+async fn group_knockout(pool: SqlitePoolOptions, conn: SqliteConnectOptions) {
+    let (player, store, mut handler, pool) = init!(pool, conn);
+    super::set_admin(&pool, player.id).await.unwrap();
+
+    let accepted = handler
+        .invite_full(&player, &store, ACCEPTED_NAME, ACCEPTED_EMAIL)
+        .await
+        .unwrap();
+    let other = handler
+        .invite_full(&player, &store, OTHER_NAME, OTHER_EMAIL)
+        .await
+        .unwrap();
+    let another = handler
+        .invite_full(&player, &store, ANOTHER_NAME, ANOTHER_EMAIL)
+        .await
+        .unwrap();
+
+    let model::Push::Tournament(model::push::Tournament::Created {
+        tournament,
+        fixtures,
+    }) = handler
+        .call(
+            create(
+                types::Format::GroupKnockout,
+                vec![player.id, accepted.id, other.id, another.id],
+                2,
+            ),
+            true,
+        )
+        .await
+        .done()
+        .unwrap()
+        .none()
+        .unwrap()
+        .some()
+        .unwrap()
+    else {
+        panic!()
+    };
+
+    // Snake seeding puts the first and last seeds together
+    assert_eq!(
+        fixtures,
+        vec![
+            types::FixtureTuple(
+                1,
+                1,
+                Some(0),
+                0,
+                0,
+                Some(player.id),
+                Some(another.id),
+                None,
+                None
+            ),
+            types::FixtureTuple(
+                2,
+                1,
+                Some(1),
+                0,
+                0,
+                Some(accepted.id),
+                Some(other.id),
+                None,
+                None
+            ),
+            types::FixtureTuple(3, 1, None, 0, 0, None, None, None, None),
+        ]
+    );
+
+    let first = handler
+        .register((player.id, another.id), (5, 11), false, super::now())
+        .await
+        .unwrap();
+    handler.side_effects().unwrap();
+
+    handler
+        .call(link(1, first.id), true)
+        .await
+        .done()
+        .unwrap()
+        .none()
+        .unwrap()
+        .some()
+        .unwrap();
+
+    let second = handler
+        .register((accepted.id, other.id), (11, 5), false, super::now())
+        .await
+        .unwrap();
+    handler.side_effects().unwrap();
+
+    handler
+        .call(link(2, second.id), true)
+        .await
+        .done()
+        .unwrap()
+        .none()
+        .unwrap()
+        .some()
+        .map(|p| {
+            assert_eq!(
+                format!("{p:?}"),
+                format!(
+                    "{:?}",
+                    model::Push::Tournament(model::push::Tournament::Updated {
+                        tournament: tournament.clone(),
+                        fixtures: vec![
+                            types::FixtureTuple(
+                                2,
+                                1,
+                                Some(1),
+                                0,
+                                0,
+                                Some(accepted.id),
+                                Some(other.id),
+                                Some(second.id),
+                                Some(accepted.id),
+                            ),
+                            types::FixtureTuple(
+                                3,
+                                1,
+                                None,
+                                0,
+                                0,
+                                Some(another.id),
+                                Some(accepted.id),
+                                None,
+                                None,
+                            ),
+                        ],
+                    })
+                )
+            );
+        })
+        .unwrap();
+}
+
+#[sqlx::test]
+async fn invalid_input(pool: SqlitePoolOptions, conn: SqliteConnectOptions) {
+    let (player, store, mut handler, pool) = init!(pool, conn);
+    super::set_admin(&pool, player.id).await.unwrap();
+
+    let accepted = handler
+        .invite_full(&player, &store, ACCEPTED_NAME, ACCEPTED_EMAIL)
+        .await
+        .unwrap();
+
+    handler
+        .call(
+            model::Request::Tournament(model::request::Tournament::Create {
+                name: String::from(WHITE_SPACE),
+                format: types::Format::RoundRobin,
+                players: vec![player.id, accepted.id],
+                groups: 0,
+                advancing: 0,
+            }),
+            false,
+        )
+        .await
+        .err(model::Error::Store(store::Error::BlankValue("name")))
+        .unwrap();
+
+    handler
+        .call(create(types::Format::RoundRobin, vec![player.id], 0), false)
+        .await
+        .err(model::Error::Store(store::Error::InvalidValue(
+            "Tournaments need at least two players",
+        )))
+        .unwrap();
+
+    handler
+        .call(
+            create(
+                types::Format::SingleElimination,
+                vec![player.id, accepted.id, player.id],
+                0,
+            ),
+            false,
+        )
+        .await
+        .err(model::Error::Store(store::Error::InvalidValue(
+            "Players cannot join a tournament more than once",
+        )))
+        .unwrap();
+
+    handler
+        .call(
+            create(
+                types::Format::GroupKnockout,
+                vec![player.id, accepted.id],
+                1,
+            ),
+            false,
+        )
+        .await
+        .err(model::Error::Store(store::Error::InvalidValue(
+            "Group stages need at least two groups",
+        )))
+        .unwrap();
+
+    handler
+        .call(
+            create(
+                types::Format::GroupKnockout,
+                vec![player.id, accepted.id],
+                2,
+            ),
+            false,
+        )
+        .await
+        .err(model::Error::Store(store::Error::InvalidValue(
+            "Groups need more players than advance from them",
+        )))
+        .unwrap();
+
+    handler
+        .call(
+            create(types::Format::RoundRobin, vec![player.id, 27], 0),
+            false,
+        )
+        .await
+        .err(model::Error::Store(store::Error::NotFound))
+        .unwrap();
+
+    handler
+        .call(link(27, 27), false)
+        .await
+        .err(model::Error::Store(store::Error::NotFound))
+        .unwrap();
+}
+
+#[sqlx::test]
+async fn forbidden(pool: SqlitePoolOptions, conn: SqliteConnectOptions) {
+    let (player, store, mut handler, pool) = init!(pool, conn);
+
+    let accepted = handler
+        .invite_full(&player, &store, ACCEPTED_NAME, ACCEPTED_EMAIL)
+        .await
+        .unwrap();
+
+    handler
+        .call(
+            create(types::Format::RoundRobin, vec![player.id, accepted.id], 0),
+            false,
+        )
+        .await
+        .err(model::Error::Forbidden)
+        .unwrap();
+
+    super::set_admin(&pool, player.id).await.unwrap();
+
+    let model::Push::Tournament(model::push::Tournament::Created {
+        tournament,
+        fixtures,
+    }) = handler
+        .call(
+            create(types::Format::RoundRobin, vec![player.id, accepted.id], 0),
+            true,
+        )
+        .await
+        .done()
+        .unwrap()
+        .none()
+        .unwrap()
+        .some()
+        .unwrap()
+    else {
+        panic!()
+    };
+
+    let game = handler
+        .register((player.id, accepted.id), (11, 0), false, now())
+        .await
+        .unwrap();
+    handler.side_effects().unwrap();
+
+    sqlx::query!(
+        r#"
+        UPDATE
+            players
+        SET
+            admin = FALSE
+        WHERE
+            id = $1
+        "#,
+        player.id,
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    handler
+        .call(link(fixtures[0].0, game.id), false)
+        .await
+        .err(model::Error::Forbidden)
+        .unwrap();
+
+    handler
+        .call(
+            model::Request::Tournament(model::request::Tournament::Delete(tournament.id)),
+            false,
+        )
+        .await
+        .err(model::Error::Forbidden)
+        .unwrap();
+
+    super::set_admin(&pool, player.id).await.unwrap();
+
+    handler
+        .call(
+            model::Request::Tournament(model::request::Tournament::Delete(tournament.id)),
+            true,
+        )
+        .await
+        .done()
+        .unwrap()
+        .none()
+        .unwrap()
+        .some()
+        .map(|p| {
+            assert_eq!(
+                format!("{p:?}"),
+                format!(
+                    "{:?}",
+                    model::Push::Tournament(model::push::Tournament::Deleted(tournament))
+                )
+            );
+        })
+        .unwrap();
+
+    let invited = handler.invite(INVITED_NAME, INVITED_EMAIL).await.unwrap();
+
+    let mut handler = framework::Handler::pending(&invited.email, &store)
+        .await
+        .unwrap();
+
+    handler
+        .call(
+            model::Request::Tournament(model::request::Tournament::List),
+            false,
+        )
+        .await
+        .err(model::Error::Forbidden)
+        .unwrap();
+}
+
+#[sqlx::test]
+async fn linked_games(pool: SqlitePoolOptions, conn: SqliteConnectOptions) {
+    let (player, store, mut handler, pool) = init!(pool, conn);
+    super::set_admin(&pool, player.id).await.unwrap();
+
+    let accepted = handler
+        .invite_full(&player, &store, ACCEPTED_NAME, ACCEPTED_EMAIL)
+        .await
+        .unwrap();
+
+    let model::Push::Tournament(model::push::Tournament::Created {
+        tournament,
+        fixtures,
+    }) = handler
+        .call(
+            create(types::Format::RoundRobin, vec![player.id, accepted.id], 0),
+            true,
+        )
+        .await
+        .done()
+        .unwrap()
+        .none()
+        .unwrap()
+        .some()
+        .unwrap()
+    else {
+        panic!()
+    };
+
+    let early = handler
+        .register(
+            (player.id, accepted.id),
+            (11, 5),
+            false,
+            types::Millis::from(i64::from(tournament.created_ms) - 1),
+        )
+        .await
+        .unwrap();
+    handler.side_effects().unwrap();
+
+    handler
+        .call(link(fixtures[0].0, early.id), false)
+        .await
+        .err(model::Error::Store(store::Error::InvalidValue(
+            "Games played before the tournament cannot be linked",
+        )))
+        .unwrap();
+
+    let game = handler
+        .register((player.id, accepted.id), (5, 11), false, super::now())
+        .await
+        .unwrap();
+    handler.side_effects().unwrap();
+
+    let games = handler
+        .call(model::Request::Game(model::request::Game::List), false)
+        .await
+        .raw()
+        .unwrap();
+
+    handler
+        .call(link(fixtures[0].0, game.id), true)
+        .await
+        .done()
+        .unwrap()
+        .none()
+        .unwrap()
+        .some()
+        .unwrap();
+
+    // Tournament games are rated like any other game, so linking leaves the ratings alone
+    handler
+        .call(model::Request::Game(model::request::Game::List), false)
+        .await
+        .ok(games)
+        .unwrap()
+        .none()
+        .unwrap()
+        .none()
+        .unwrap();
+
+    handler
+        .call(
+            model::Request::Game(model::request::Game::Update {
+                game: types::Game {
+                    millis: early.millis,
+                    ..game
+                },
+                reason: None,
+            }),
+            false,
+        )
+        .await
+        .err(model::Error::Store(store::Error::InvalidValue(
+            "Games linked to a tournament cannot be moved before it",
+        )))
+        .unwrap();
+}
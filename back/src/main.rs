@@ -85,6 +85,100 @@ async fn initialize(db: std::path::PathBuf, count: u16) -> std::process::ExitCod
     }
 }
 
+async fn simulate(
+    store: &store::Store,
+    k_factor: Option<f64>,
+    challenge_multiplier: Option<f64>,
+) -> std::process::ExitCode {
+    let (parameters, comparisons) =
+        match handler::simulate(store, k_factor, challenge_multiplier).await {
+            Ok(simulation) => simulation,
+            Err(error) => {
+                tracing::error!(?error, "Failed to simulate ratings");
+                return std::process::ExitCode::FAILURE;
+            }
+        };
+
+    let names = match store.players().list().await {
+        Ok(players) => players
+            .into_iter()
+            .map(|p| (p.id, p.name))
+            .collect::<std::collections::HashMap<_, _>>(),
+        Err(error) => {
+            tracing::error!(?error, "Failed to list players");
+            return std::process::ExitCode::FAILURE;
+        }
+    };
+    let name = |id| names.get(&id).map_or("", String::as_str);
+    let rank = |rank: Option<i64>| rank.map_or_else(|| String::from("-"), |r| r.to_string());
+
+    println!(
+        "K-factor: {}, challenge multiplier: {}",
+        parameters.k_factor, parameters.challenge_multiplier
+    );
+    println!(
+        "{:<32} {:>5} {:>5} {:>8} {:>5} {:>8}",
+        "Player", "Games", "Rank", "Rating", "Rank", "Rating"
+    );
+    for comparison in &comparisons {
+        println!(
+            "{:<32} {:>5} {:>5} {:>8.1} {:>5} {:>8.1}",
+            name(comparison.player),
+            comparison.games,
+            rank(comparison.rank),
+            comparison.rating,
+            rank(comparison.simulated_rank),
+            comparison.simulated_rating,
+        );
+    }
+
+    println!();
+    println!("Biggest movers:");
+    for player in handler::movers(&comparisons, handler::MOVERS) {
+        if let Some(comparison) = comparisons.iter().find(|c| c.player == player) {
+            println!(
+                "{:<32} {:>+8.1}",
+                name(player),
+                comparison.simulated_rating - comparison.rating
+            );
+        }
+    }
+
+    std::process::ExitCode::SUCCESS
+}
+
+async fn admin(store: &store::Store, email: &str, admin: bool) -> std::process::ExitCode {
+    match store.players().set_admin(email, admin).await {
+        Ok(id) => {
+            tracing::info!(%id, %email, %admin, "Updated admin access");
+            std::process::ExitCode::SUCCESS
+        }
+        Err(error) => {
+            tracing::error!(?error, %email, "Failed to update admin access");
+            std::process::ExitCode::FAILURE
+        }
+    }
+}
+
+// Snapshots are taken and ended seasons archived right after midnight, so that reading the
+// leaderboard or the standings never writes
+async fn snapshot(store: store::Store) {
+    const DAY: std::time::Duration = std::time::Duration::from_secs(24 * 60 * 60);
+
+    loop {
+        if let Err(error) = handler::snapshot(&store).await {
+            tracing::error!(?error, "Failed to snapshot the leaderboard and the seasons");
+        }
+
+        let elapsed = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(std::time::Duration::ZERO, |now| {
+                std::time::Duration::from_secs(now.as_secs() % DAY.as_secs())
+            });
+        tokio::time::sleep(DAY - elapsed).await;
+    }
+}
+
 async fn async_main(args: args::Args) -> std::process::ExitCode {
     #[cfg(feature = "local")]
     if let Some(count) = args.init {
@@ -104,7 +198,7 @@ async fn async_main(args: args::Args) -> std::process::ExitCode {
     }
 
     let store = match store::Store::new(&args.db).await {
-        Ok(store) => store,
+        Ok(store) => store.with_venues(args.venues),
         Err(error) => {
             tracing::error!(?error, db = ?args.db, "Failed to open store");
             return std::process::ExitCode::FAILURE;
@@ -116,11 +210,24 @@ async fn async_main(args: args::Args) -> std::process::ExitCode {
         return std::process::ExitCode::FAILURE;
     }
 
+    match args.command {
+        Some(args::Command::Simulate {
+            k_factor,
+            challenge_multiplier,
+        }) => return simulate(&store, k_factor, challenge_multiplier).await,
+        Some(args::Command::Admin { email, revoke }) => {
+            return admin(&store, &email, !revoke).await
+        }
+        None => {}
+    }
+
     if let Err(error) = handler::refresh(&store).await {
         tracing::error!(?error, db = ?args.db, "Failed to refresh database");
         return std::process::ExitCode::FAILURE;
     }
 
+    tokio::spawn(snapshot(store.clone()));
+
     let broadcaster = handler::Broadcaster::new();
 
     // TODO: Make this not a dynamic dispatch
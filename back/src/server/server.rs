@@ -36,6 +36,12 @@ impl Server {
     }
 }
 
+// Declared by clients when connecting
+#[derive(Debug, serde::Deserialize)]
+struct Connect {
+    version: Option<u32>,
+}
+
 fn route<S>(store: store::Store, broadcaster: handler::Broadcaster, smtp: S) -> axum::Router
 where
     S: smtp::Smtp,
@@ -44,35 +50,69 @@ where
         store: store::Store,
         broadcaster: handler::Broadcaster,
         smtp: S,
+        sessions: ws::Sessions,
     ) -> axum::routing::MethodRouter<()> {
         axum::routing::get(
             |upgrade: axum::extract::WebSocketUpgrade,
-             axum::Extension(user): axum::Extension<handler::UserAccess>| async {
-                upgrade.on_upgrade(|socket| async {
-                    macro_rules! serve {
-                        ($user: expr) => {{
-                            let handler = handler::Handler::new($user, store, broadcaster, smtp);
-                            let socket = ws::Layer::<M, _>::new(socket, handler);
-                            socket.serve().await;
-                        }};
-                    }
+             axum::extract::Query(connect): axum::extract::Query<Connect>,
+             axum::Extension(user): axum::Extension<handler::UserAccess>| async move {
+                let version = connect.version.unwrap_or(handler::MIN_VERSION);
 
-                    match user {
-                        handler::UserAccess::Regular(user) => serve!(user),
-                        handler::UserAccess::Pending(user) => serve!(user),
-                    }
-                })
+                upgrade
+                    .protocols([ws::DEFLATE])
+                    .on_upgrade(move |socket| async move {
+                        macro_rules! serve {
+                            ($user: expr) => {{
+                                let handler =
+                                    handler::Handler::new($user, store, broadcaster, smtp)
+                                        .with_version(version);
+                                let socket = ws::Layer::<M, _>::new(socket, handler, sessions);
+                                socket.serve().await;
+                            }};
+                        }
+
+                        match user {
+                            handler::UserAccess::Regular(user) => serve!(user),
+                            handler::UserAccess::Pending(user) => serve!(user),
+                        }
+                    })
             },
         )
     }
 
+    // Shared between modes so a client can resume a session on either route
+    let sessions = ws::Sessions::new();
+
     axum::Router::new()
         .route(
             "/ws/text",
-            upgrade::<String, S>(store.clone(), broadcaster.clone(), smtp.clone()),
+            upgrade::<String, S>(
+                store.clone(),
+                broadcaster.clone(),
+                smtp.clone(),
+                sessions.clone(),
+            ),
         )
         .route(
             "/ws/binary",
-            upgrade::<Vec<u8>, S>(store, broadcaster, smtp),
+            upgrade::<Vec<u8>, S>(
+                store.clone(),
+                broadcaster.clone(),
+                smtp.clone(),
+                sessions.clone(),
+            ),
+        )
+        .route(
+            "/ws/cbor",
+            upgrade::<ws::Cbor, S>(
+                store.clone(),
+                broadcaster.clone(),
+                smtp.clone(),
+                sessions.clone(),
+            ),
+        )
+        .route(
+            "/ws/compact",
+            upgrade::<ws::Compact, S>(store, broadcaster, smtp, sessions),
         )
 }
@@ -2,6 +2,8 @@
 pub enum Error {
     #[error("Failed to perform query: {0:?}")]
     Query(sqlx::Error),
+    #[error("Failed to serialize snapshot: {0}")]
+    Snapshot(serde_json::Error),
     #[error("Field `{0}` cannot be blank")]
     BlankValue(&'static str),
     #[error("Entry already exists")]
@@ -10,6 +12,8 @@ pub enum Error {
     InvalidValue(&'static str),
     #[error("Not found")]
     NotFound,
+    #[error("Forbidden")]
+    Forbidden,
 }
 
 impl From<sqlx::Error> for Error {
@@ -0,0 +1,329 @@
+use super::super::error::Error;
+use crate::types;
+
+type Result<T = ()> = std::result::Result<T, Error>;
+
+const STREAK: i64 = 10;
+const CENTURION: i64 = 100;
+
+pub struct Achievements<'a> {
+    store: &'a super::Store,
+}
+
+impl<'a> From<&'a super::Store> for Achievements<'a> {
+    fn from(store: &'a super::Store) -> Self {
+        Self { store }
+    }
+}
+
+impl Achievements<'_> {
+    #[tracing::instrument(skip(self))]
+    pub async fn list(&self) -> Result<Vec<types::Achievement>> {
+        sqlx::query_as!(
+            types::Achievement,
+            r#"
+            SELECT
+                id,
+                player,
+                kind AS "kind: types::AchievementKind",
+                game,
+                created_ms AS "created_ms: types::Millis"
+            FROM
+                achievements
+            ORDER BY
+                created_ms ASC
+            "#
+        )
+        .fetch_all(&self.store.pool)
+        .await
+        .map_err(Error::from)
+    }
+
+    // Game writes evaluate the achievements along with them, so rebuilding them is only needed to
+    // check that they hold
+    #[cfg(test)]
+    #[tracing::instrument(skip(self))]
+    pub async fn evaluate(
+        &self,
+        from: Option<types::Millis>,
+    ) -> Result<(Vec<types::Achievement>, Vec<types::Achievement>)> {
+        let mut tx = self.store.pool.begin().await?;
+        let (unlocked, revoked) = Self::execute_evaluate(from, &mut tx).await?;
+        tx.commit().await?;
+
+        if !unlocked.is_empty() || !revoked.is_empty() {
+            self.store.update_version();
+        }
+
+        Ok((unlocked, revoked))
+    }
+}
+
+impl Achievements<'_> {
+    // Achievements unlocked before `from`, which is the earliest point in the history that
+    // changed, still hold. The ones after it are derived again, so edits and deletions revoke the
+    // ones that no longer hold and unlock the ones that now do
+    pub(super) async fn execute_evaluate(
+        from: Option<types::Millis>,
+        tx: &mut sqlx::Transaction<'static, sqlx::Sqlite>,
+    ) -> Result<(Vec<types::Achievement>, Vec<types::Achievement>)> {
+        let (tallies, locked) = match from {
+            Some(from) => (
+                Self::tallies(from, tx).await?,
+                Self::locked(from, tx.as_mut()).await?,
+            ),
+            None => Default::default(),
+        };
+
+        let games = super::games::Games::list_since(from, tx.as_mut()).await?;
+        let mut expected = evaluate(&games, tallies, &locked);
+
+        let stored = sqlx::query_as!(
+            types::Achievement,
+            r#"
+            SELECT
+                achievements.id,
+                achievements.player,
+                achievements.kind AS "kind: types::AchievementKind",
+                achievements.game,
+                achievements.created_ms AS "created_ms: types::Millis"
+            FROM
+                achievements
+            INNER JOIN
+                games
+            ON
+                games.id = achievements.game
+            WHERE
+                $1 IS NULL
+                OR games.millis >= $1
+            "#,
+            from,
+        )
+        .fetch_all(tx.as_mut())
+        .await?;
+
+        let mut revoked = Vec::new();
+        for achievement in stored {
+            if expected.get(&(achievement.player, achievement.kind)) == Some(&achievement.game) {
+                expected.remove(&(achievement.player, achievement.kind));
+            } else {
+                sqlx::query!(
+                    r#"
+                    DELETE FROM
+                        achievements
+                    WHERE
+                        id = $1
+                    "#,
+                    achievement.id,
+                )
+                .execute(tx.as_mut())
+                .await?;
+                revoked.push(achievement);
+            }
+        }
+
+        let mut unlocked = Vec::with_capacity(expected.len());
+        for ((player, kind), game) in expected {
+            unlocked.push(
+                sqlx::query_as!(
+                    types::Achievement,
+                    r#"
+                    INSERT INTO achievements (
+                        player,
+                        kind,
+                        game
+                    ) VALUES (
+                        $1,
+                        $2,
+                        $3
+                    ) RETURNING
+                        id,
+                        player,
+                        kind AS "kind: types::AchievementKind",
+                        game,
+                        created_ms AS "created_ms: types::Millis"
+                    "#,
+                    player,
+                    kind,
+                    game,
+                )
+                .fetch_one(tx.as_mut())
+                .await?,
+            );
+        }
+
+        Ok((unlocked, revoked))
+    }
+
+    async fn tallies(
+        before: types::Millis,
+        tx: &mut sqlx::Transaction<'static, sqlx::Sqlite>,
+    ) -> Result<std::collections::HashMap<types::Id, Tally>> {
+        let streaks = super::streaks::Streaks::before(before, tx.as_mut()).await?;
+
+        sqlx::query!(
+            r#"
+            WITH
+                unified AS (
+                    SELECT
+                        id,
+                        player_one AS player,
+                        rating_one + rating_delta AS rating,
+                        millis
+                    FROM
+                        games
+                    WHERE
+                        millis < $1
+                        AND NOT deleted
+                    UNION ALL
+                        SELECT
+                            id,
+                            player_two AS player,
+                            rating_two - rating_delta AS rating,
+                            millis
+                        FROM
+                            games
+                        WHERE
+                            millis < $1
+                            AND NOT deleted
+                ),
+                latest AS (
+                    SELECT
+                        player,
+                        rating,
+                        ROW_NUMBER() OVER (
+                            PARTITION BY player
+                            ORDER BY millis DESC, id DESC
+                        ) AS position,
+                        COUNT(*) OVER (PARTITION BY player) AS games
+                    FROM
+                        unified
+                )
+            SELECT
+                player AS "player!: types::Id",
+                rating AS "rating!: f64",
+                games AS "games!: i64"
+            FROM
+                latest
+            WHERE
+                position = 1
+            "#,
+            before,
+        )
+        .map(|r| {
+            let tally = Tally {
+                games: r.games,
+                streak: streaks.get(&r.player).map_or(0, |s| s.current.max(0)),
+                rating: r.rating,
+            };
+            (r.player, tally)
+        })
+        .fetch_all(tx.as_mut())
+        .await
+        .map(|tallies| tallies.into_iter().collect())
+        .map_err(Error::from)
+    }
+
+    async fn locked<'c, 'e, E>(
+        before: types::Millis,
+        executor: E,
+    ) -> Result<std::collections::BTreeSet<(types::Id, types::AchievementKind)>>
+    where
+        'c: 'e,
+        E: 'e + sqlx::Executor<'c, Database = sqlx::Sqlite>,
+    {
+        sqlx::query!(
+            r#"
+            SELECT
+                achievements.player,
+                achievements.kind AS "kind: types::AchievementKind"
+            FROM
+                achievements
+            INNER JOIN
+                games
+            ON
+                games.id = achievements.game
+            WHERE
+                games.millis < $1
+            "#,
+            before,
+        )
+        .map(|r| (r.player, r.kind))
+        .fetch_all(executor)
+        .await
+        .map(|locked| locked.into_iter().collect())
+        .map_err(Error::from)
+    }
+}
+
+#[derive(Debug, Default)]
+struct Tally {
+    games: i64,
+    streak: i64,
+    rating: f64,
+}
+
+fn evaluate(
+    games: &[types::Game],
+    mut tallies: std::collections::HashMap<types::Id, Tally>,
+    locked: &std::collections::BTreeSet<(types::Id, types::AchievementKind)>,
+) -> std::collections::BTreeMap<(types::Id, types::AchievementKind), types::Id> {
+    let mut unlocked = std::collections::BTreeMap::new();
+
+    for game in games {
+        let (winner, loser, loser_rating) = if game.score_one > game.score_two {
+            (game.player_one, game.player_two, game.rating_two)
+        } else {
+            (game.player_two, game.player_one, game.rating_one)
+        };
+
+        tallies.entry(game.player_one).or_default().rating = game.rating_one;
+        tallies.entry(game.player_two).or_default().rating = game.rating_two;
+
+        let best = tallies
+            .iter()
+            .all(|(&id, tally)| id == loser || tally.rating < loser_rating);
+
+        let mut unlock = |player, kind| {
+            if !locked.contains(&(player, kind)) {
+                unlocked.entry((player, kind)).or_insert(game.id);
+            }
+        };
+
+        if best {
+            unlock(winner, types::AchievementKind::BeatTheBest);
+        }
+
+        if game.score_one.min(game.score_two) == 0 {
+            unlock(winner, types::AchievementKind::Shutout);
+        }
+
+        if game.score_one.max(game.score_two) > 11 {
+            unlock(winner, types::AchievementKind::TieBreak);
+        }
+
+        unlock(winner, types::AchievementKind::FirstWin);
+
+        let tally = tallies.entry(winner).or_default();
+        tally.games += 1;
+        tally.streak += 1;
+        if tally.streak >= STREAK {
+            unlock(winner, types::AchievementKind::WinStreak);
+        }
+        if tally.games >= CENTURION {
+            unlock(winner, types::AchievementKind::Centurion);
+        }
+
+        let tally = tallies.entry(loser).or_default();
+        tally.games += 1;
+        tally.streak = 0;
+        if tally.games >= CENTURION {
+            unlock(loser, types::AchievementKind::Centurion);
+        }
+
+        tallies.entry(game.player_one).or_default().rating = game.rating_one + game.rating_delta;
+        tallies.entry(game.player_two).or_default().rating = game.rating_two - game.rating_delta;
+    }
+
+    unlocked
+}
@@ -0,0 +1,105 @@
+use super::super::error::Error;
+use crate::types;
+
+type Result<T = ()> = std::result::Result<T, Error>;
+
+pub struct Audit<'a> {
+    store: &'a super::Store,
+}
+
+impl<'a> From<&'a super::Store> for Audit<'a> {
+    fn from(store: &'a super::Store) -> Self {
+        Self { store }
+    }
+}
+
+impl Audit<'_> {
+    #[tracing::instrument(skip(self))]
+    pub async fn list(
+        &self,
+        actor: Option<types::Id>,
+        entity: Option<types::Entity>,
+        entity_id: Option<types::Id>,
+    ) -> Result<Vec<types::Audit>> {
+        sqlx::query_as!(
+            types::Audit,
+            r#"
+            SELECT
+                id,
+                actor,
+                action AS "action: types::AuditAction",
+                entity AS "entity: types::Entity",
+                entity_id,
+                before,
+                after,
+                created_ms AS "created_ms: types::Millis"
+            FROM
+                audit
+            WHERE
+                ($1 IS NULL OR actor = $1)
+                AND ($2 IS NULL OR entity = $2)
+                AND ($3 IS NULL OR entity_id = $3)
+            ORDER BY
+                created_ms DESC,
+                id DESC
+            "#,
+            actor,
+            entity,
+            entity_id,
+        )
+        .fetch_all(&self.store.pool)
+        .await
+        .map_err(Error::from)
+    }
+
+    pub(super) async fn record<'c, 'e, E>(
+        actor: Option<types::Id>,
+        action: types::AuditAction,
+        (entity, entity_id): (types::Entity, types::Id),
+        (before, after): (Option<String>, Option<String>),
+        executor: E,
+    ) -> Result
+    where
+        'c: 'e,
+        E: 'e + sqlx::Executor<'c, Database = sqlx::Sqlite>,
+    {
+        sqlx::query!(
+            r#"
+            INSERT INTO audit (
+                actor,
+                action,
+                entity,
+                entity_id,
+                before,
+                after
+            ) VALUES (
+                $1,
+                $2,
+                $3,
+                $4,
+                $5,
+                $6
+            )
+            "#,
+            actor,
+            action,
+            entity,
+            entity_id,
+            before,
+            after,
+        )
+        .execute(executor)
+        .await
+        .map(|_| ())
+        .map_err(Error::from)
+    }
+
+    pub(super) fn snapshot<T>(value: &T) -> Result<Option<String>>
+    where
+        T: serde::Serialize,
+    {
+        serde_json::to_string(value)
+            .map(Some)
+            .map_err(Error::Snapshot)
+    }
+}
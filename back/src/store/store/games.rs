@@ -3,6 +3,10 @@ use crate::types;
 
 type Result<T = ()> = std::result::Result<T, Error>;
 
+// The `comment` column has a CHECK on this same length and the validation error states it, so all
+// must be changed together
+const MAX_COMMENT: usize = 256;
+
 pub struct Games<'a> {
     store: &'a super::Store,
 }
@@ -22,22 +26,658 @@ impl Games<'_> {
         Self::list_games(&self.store.pool).await
     }
 
+    #[tracing::instrument(skip(self))]
+    pub async fn list_venue(&self, venue: &str) -> Result<Vec<types::Game>> {
+        sqlx::query_as!(
+            types::Game,
+            r#"
+            SELECT
+                id,
+                player_one,
+                player_two,
+                score_one,
+                score_two,
+                rating_one,
+                rating_two,
+                rating_delta,
+                challenge,
+                deleted,
+                millis AS "millis: types::Millis",
+                created_ms AS "created_ms: types::Millis",
+                comment,
+                venue,
+                first_server
+            FROM
+                games
+            WHERE
+                venue = $1
+            ORDER BY
+                millis ASC
+            "#,
+            venue,
+        )
+        .fetch_all(&self.store.pool)
+        .await
+        .map_err(Error::from)
+    }
+
+    #[must_use]
+    pub fn venues(&self) -> &[String] {
+        &self.store.venues
+    }
+
+    // allow(clippy::too_many_arguments): The registrar is recorded to allow undoing the game
+    #[allow(clippy::too_many_arguments)]
     #[tracing::instrument(skip(self, rating_updater))]
     pub async fn register<F>(
+        &self,
+        registrar: types::Id,
+        players: (types::Id, types::Id),
+        scores: (u8, u8),
+        challenge: bool,
+        millis: types::Millis,
+        annotations: (Option<&str>, Option<&str>, Option<types::Id>),
+        default_rating: f64,
+        rating_updater: F,
+    ) -> Result<(types::Game, Vec<types::Game>, types::Derived)>
+    where
+        F: Copy + Fn(f64, f64, bool, bool) -> f64,
+    {
+        let mut tx = self.store.pool.begin().await?;
+
+        let (game, updates, derived) = Self::execute_register(
+            registrar,
+            players,
+            scores,
+            challenge,
+            millis,
+            annotations,
+            &self.store.venues,
+            default_rating,
+            rating_updater,
+            &mut tx,
+        )
+        .await?;
+
+        tx.commit().await?;
+
+        self.store.update_version();
+
+        Ok((game, updates, derived))
+    }
+
+    #[tracing::instrument(skip(self, rating_updater))]
+    pub async fn update<F>(
+        &self,
+        game: types::Game,
+        editor: (types::Id, Option<&str>),
+        default_rating: f64,
+        rating_updater: F,
+    ) -> Result<(types::Game, Vec<types::Game>, types::Derived)>
+    where
+        F: Copy + Fn(f64, f64, bool, bool) -> f64,
+    {
+        let mut tx = self.store.pool.begin().await?;
+
+        let (game, updates, derived) = Self::execute_update(
+            game,
+            editor,
+            &self.store.venues,
+            default_rating,
+            rating_updater,
+            &mut tx,
+        )
+        .await?;
+
+        tx.commit().await?;
+
+        self.store.update_version();
+
+        Ok((game, updates, derived))
+    }
+
+    #[tracing::instrument(skip(self, rating_updater))]
+    pub async fn undo<F>(
+        &self,
+        id: types::Id,
+        registrar: types::Id,
+        window: i64,
+        default_rating: f64,
+        rating_updater: F,
+    ) -> Result<(types::Game, Vec<types::Game>, types::Derived)>
+    where
+        F: Copy + Fn(f64, f64, bool, bool) -> f64,
+    {
+        let mut tx = self.store.pool.begin().await?;
+
+        let registered = sqlx::query!(
+            r#"
+            SELECT
+                id,
+                player_one,
+                player_two,
+                score_one,
+                score_two,
+                rating_one,
+                rating_two,
+                rating_delta,
+                challenge,
+                deleted,
+                millis AS "millis: types::Millis",
+                created_ms AS "created_ms: types::Millis",
+                comment,
+                venue,
+                first_server,
+                registrar
+            FROM
+                games
+            WHERE
+                id = $1
+            "#,
+            id,
+        )
+        .fetch_one(tx.as_mut())
+        .await?;
+
+        if registered.registrar != Some(registrar) {
+            return Err(Error::Forbidden);
+        }
+
+        let game = types::Game {
+            id: registered.id,
+            player_one: registered.player_one,
+            player_two: registered.player_two,
+            score_one: registered.score_one,
+            score_two: registered.score_two,
+            rating_one: registered.rating_one,
+            rating_two: registered.rating_two,
+            rating_delta: registered.rating_delta,
+            challenge: registered.challenge,
+            deleted: registered.deleted,
+            millis: registered.millis,
+            created_ms: registered.created_ms,
+            comment: registered.comment,
+            venue: registered.venue,
+            first_server: registered.first_server,
+        };
+
+        if i64::from(types::Millis::now()) - i64::from(game.created_ms) > window {
+            return Err(Error::InvalidValue(
+                "Games can only be undone shortly after being registered",
+            ));
+        }
+
+        super::tournaments::Tournaments::validate_update(
+            &types::Game {
+                deleted: true,
+                ..game.clone()
+            },
+            tx.as_mut(),
+        )
+        .await?;
+
+        sqlx::query!(
+            r#"
+            DELETE FROM
+                games
+            WHERE
+                id = $1
+            "#,
+            id,
+        )
+        .execute(tx.as_mut())
+        .await?;
+
+        let (updates, derived) =
+            Self::execute_refresh(Some(game.millis), default_rating, rating_updater, &mut tx)
+                .await?;
+
+        super::audit::Audit::record(
+            Some(registrar),
+            types::AuditAction::UndoGame,
+            (types::Entity::Game, game.id),
+            (super::audit::Audit::snapshot(&game)?, None),
+            tx.as_mut(),
+        )
+        .await?;
+
+        tx.commit().await?;
+
+        self.store.update_version();
+
+        Ok((game, updates, derived))
+    }
+
+    #[tracing::instrument(skip(self, rating_updater))]
+    pub async fn revert<F>(
+        &self,
+        (game, history): (types::Id, types::Id),
+        editor: types::Id,
+        default_rating: f64,
+        rating_updater: F,
+    ) -> Result<(types::Game, Vec<types::Game>, types::Derived)>
+    where
+        F: Copy + Fn(f64, f64, bool, bool) -> f64,
+    {
+        let mut tx = self.store.pool.begin().await?;
+
+        let revision = sqlx::query!(
+            r#"
+            SELECT
+                player_one,
+                player_two,
+                score_one,
+                score_two,
+                challenge,
+                deleted,
+                millis AS "millis: types::Millis"
+            FROM
+                history
+            WHERE
+                id = $1
+                AND game = $2
+            "#,
+            history,
+            game,
+        )
+        .fetch_one(tx.as_mut())
+        .await?;
+
+        let current = sqlx::query_as!(
+            types::Game,
+            r#"
+            SELECT
+                id,
+                player_one,
+                player_two,
+                score_one,
+                score_two,
+                rating_one,
+                rating_two,
+                rating_delta,
+                challenge,
+                deleted,
+                millis AS "millis: types::Millis",
+                created_ms AS "created_ms: types::Millis",
+                comment,
+                venue,
+                first_server
+            FROM
+                games
+            WHERE
+                id = $1
+            "#,
+            game,
+        )
+        .fetch_one(tx.as_mut())
+        .await?;
+
+        let before = super::audit::Audit::snapshot(&current)?;
+
+        let game = types::Game {
+            player_one: revision.player_one,
+            player_two: revision.player_two,
+            score_one: revision.score_one,
+            score_two: revision.score_two,
+            challenge: revision.challenge,
+            deleted: revision.deleted,
+            millis: revision.millis,
+            ..current
+        };
+
+        let (game, updates, derived) = Self::execute_update(
+            game,
+            (editor, None),
+            &self.store.venues,
+            default_rating,
+            rating_updater,
+            &mut tx,
+        )
+        .await?;
+
+        super::audit::Audit::record(
+            Some(editor),
+            types::AuditAction::RevertGame,
+            (types::Entity::Game, game.id),
+            (before, super::audit::Audit::snapshot(&game)?),
+            tx.as_mut(),
+        )
+        .await?;
+
+        tx.commit().await?;
+
+        self.store.update_version();
+
+        Ok((game, updates, derived))
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn history(&self, game: types::Id) -> Result<Vec<types::History>> {
+        sqlx::query_as!(
+            types::History,
+            r#"
+            SELECT
+                id,
+                game,
+                player_one,
+                player_two,
+                score_one,
+                score_two,
+                challenge,
+                deleted,
+                millis AS "millis: types::Millis",
+                created_ms AS "created_ms: types::Millis",
+                editor,
+                reason
+            FROM
+                history
+            WHERE
+                game = $1
+            ORDER BY
+                created_ms DESC
+            "#,
+            game,
+        )
+        .fetch_all(&self.store.pool)
+        .await
+        .map_err(Error::from)
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn points(&self, game: types::Id) -> Result<types::Points> {
+        let log = sqlx::query_scalar!(
+            r#"
+            SELECT
+                log
+            FROM
+                points
+            WHERE
+                game = $1
+            "#,
+            game,
+        )
+        .fetch_one(&self.store.pool)
+        .await?;
+
+        let points = decode_points(&log)?;
+        let (longest_run_one, longest_run_two) = longest_runs(&points);
+
+        Ok(types::Points {
+            game,
+            points,
+            longest_run_one,
+            longest_run_two,
+        })
+    }
+
+    // An empty log removes the points recorded for the game
+    // Returns the players of the game, so the change can be routed to them
+    #[tracing::instrument(skip(self))]
+    pub async fn record_points(
+        &self,
+        game: types::Id,
+        points: &[types::Point],
+    ) -> Result<(types::Id, types::Id)> {
+        let mut tx = self.store.pool.begin().await?;
+
+        let players = Self::execute_record_points(game, points, &mut tx).await?;
+
+        tx.commit().await?;
+
+        self.store.update_version();
+
+        Ok(players)
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn rating_history(
+        &self,
+        player: types::Id,
+        (from, to): (Option<types::Millis>, Option<types::Millis>),
+        resolution: types::Resolution,
+        season: Option<types::Id>,
+    ) -> Result<Vec<types::RatingPoint>> {
+        let mut tx = self.store.pool.begin().await?;
+
+        let (from, to) = match season {
+            Some(season) => {
+                let season = super::seasons::Seasons::get(season, tx.as_mut()).await?;
+                (
+                    Some(from.map_or(season.start_ms, |from| from.max(season.start_ms))),
+                    Some(to.map_or(season.end_ms, |to| to.min(season.end_ms))),
+                )
+            }
+            None => (from, to),
+        };
+
+        sqlx::query_as!(
+            super::Id,
+            r#"
+            SELECT
+                id
+            FROM
+                players
+            WHERE
+                id = $1
+            "#,
+            player,
+        )
+        .fetch_one(tx.as_mut())
+        .await?;
+
+        let steps = sqlx::query_as!(
+            RatingStep,
+            r#"
+            SELECT
+                millis AS "millis!: types::Millis",
+                CASE
+                    WHEN player_one = $1 THEN rating_one
+                    ELSE rating_two
+                END AS "before!: f64",
+                CASE
+                    WHEN player_one = $1 THEN rating_one + rating_delta
+                    ELSE rating_two - rating_delta
+                END AS "after!: f64"
+            FROM
+                games
+            WHERE
+                (player_one = $1 OR player_two = $1)
+                AND NOT deleted
+                AND ($2 IS NULL OR millis >= $2)
+                AND ($3 IS NULL OR millis < $3)
+            ORDER BY
+                millis ASC
+            "#,
+            player,
+            from,
+            to,
+        )
+        .fetch_all(tx.as_mut())
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(bucket_ratings(steps, resolution))
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn ratings_at(
         &self,
         (player_one, player_two): (types::Id, types::Id),
+        millis: types::Millis,
+        challenge: bool,
+        default_rating: f64,
+    ) -> Result<(f64, f64)> {
+        if player_one == player_two {
+            return Err(Error::InvalidValue("Players cannot be equal"));
+        }
+
+        let mut tx = self.store.pool.begin().await?;
+
+        let found = sqlx::query_scalar!(
+            r#"
+            SELECT
+                COUNT(*) AS "count!: i64"
+            FROM
+                players
+            WHERE
+                id IN ($1, $2)
+            "#,
+            player_one,
+            player_two,
+        )
+        .fetch_one(tx.as_mut())
+        .await?;
+
+        if found != 2 {
+            return Err(Error::NotFound);
+        }
+
+        if challenge {
+            Self::validate_challenge(player_one, player_two, millis, None, tx.as_mut()).await?;
+        }
+
+        let last_ratings = Self::last_ratings(millis, tx.as_mut()).await?;
+        let resets = super::seasons::Seasons::resets(tx.as_mut()).await?;
+
+        tx.commit().await?;
+
+        let ratings = Ratings::new(last_ratings, resets, default_rating);
+
+        Ok((
+            ratings.get(player_one, millis),
+            ratings.get(player_two, millis),
+        ))
+    }
+
+    pub(super) async fn list_games<'c, 'e, E>(executor: E) -> Result<Vec<types::Game>>
+    where
+        'c: 'e,
+        E: 'e + sqlx::Executor<'c, Database = sqlx::Sqlite>,
+    {
+        sqlx::query_as!(
+            types::Game,
+            r#"
+            SELECT
+                id,
+                player_one,
+                player_two,
+                score_one,
+                score_two,
+                rating_one,
+                rating_two,
+                rating_delta,
+                challenge,
+                deleted,
+                millis AS "millis: types::Millis",
+                created_ms AS "created_ms: types::Millis",
+                comment,
+                venue,
+                first_server
+            FROM
+                games
+            ORDER BY
+                millis ASC
+            "#
+        )
+        .fetch_all(executor)
+        .await
+        .map_err(Error::from)
+    }
+
+    // Only the games that still count, in the order they are replayed
+    pub(super) async fn list_since<'c, 'e, E>(
+        from: Option<types::Millis>,
+        executor: E,
+    ) -> Result<Vec<types::Game>>
+    where
+        'c: 'e,
+        E: 'e + sqlx::Executor<'c, Database = sqlx::Sqlite>,
+    {
+        sqlx::query_as!(
+            types::Game,
+            r#"
+            SELECT
+                id,
+                player_one,
+                player_two,
+                score_one,
+                score_two,
+                rating_one,
+                rating_two,
+                rating_delta,
+                challenge,
+                deleted,
+                millis AS "millis: types::Millis",
+                created_ms AS "created_ms: types::Millis",
+                comment,
+                venue,
+                first_server
+            FROM
+                games
+            WHERE
+                NOT deleted
+                AND ($1 IS NULL OR millis >= $1)
+            ORDER BY
+                millis ASC,
+                id ASC
+            "#,
+            from,
+        )
+        .fetch_all(executor)
+        .await
+        .map_err(Error::from)
+    }
+
+    #[tracing::instrument(skip(self, rating_updater))]
+    pub async fn refresh<F>(
+        &self,
+        default_rating: f64,
+        rating_updater: F,
+    ) -> Result<Vec<types::Game>>
+    where
+        F: Copy + Fn(f64, f64, bool, bool) -> f64,
+    {
+        let mut tx = self.store.pool.begin().await?;
+        let (games, derived) =
+            Self::execute_refresh(None, default_rating, rating_updater, &mut tx).await?;
+        tx.commit().await?;
+
+        if !games.is_empty() || !derived.is_empty() {
+            self.store.update_version();
+        }
+
+        Ok(games)
+    }
+}
+
+impl Games<'_> {
+    // allow(clippy::too_many_arguments): The registrar is recorded to allow undoing the game
+    #[allow(clippy::too_many_arguments)]
+    pub(super) async fn execute_register<F>(
+        registrar: types::Id,
+        (player_one, player_two): (types::Id, types::Id),
         (score_one, score_two): (u8, u8),
         challenge: bool,
         millis: types::Millis,
+        (comment, venue, first_server): (Option<&str>, Option<&str>, Option<types::Id>),
+        venues: &[String],
         default_rating: f64,
         rating_updater: F,
-    ) -> Result<(types::Game, Vec<types::Game>)>
+        tx: &mut sqlx::Transaction<'static, sqlx::Sqlite>,
+    ) -> Result<(types::Game, Vec<types::Game>, types::Derived)>
     where
         F: Copy + Fn(f64, f64, bool, bool) -> f64,
     {
-        validate_game(player_one, player_two, score_one, score_two)?;
+        let comment = normalize(comment);
+        let venue = normalize(venue);
 
-        let mut tx = self.store.pool.begin().await?;
+        validate_game(
+            (player_one, player_two),
+            (score_one, score_two),
+            (comment, venue, first_server),
+            venues,
+        )?;
 
         if challenge {
             Self::validate_challenge(player_one, player_two, millis, None, tx.as_mut()).await?;
@@ -55,7 +695,11 @@ impl Games<'_> {
                 rating_one,
                 rating_two,
                 rating_delta,
-                millis
+                millis,
+                registrar,
+                comment,
+                venue,
+                first_server
             ) VALUES (
                 $1,
                 $2,
@@ -65,7 +709,11 @@ impl Games<'_> {
                 0,
                 0,
                 0,
-                $6
+                $6,
+                $7,
+                $8,
+                $9,
+                $10
             )
             RETURNING
                 id,
@@ -79,7 +727,10 @@ impl Games<'_> {
                 challenge,
                 deleted,
                 millis AS "millis: types::Millis",
-                created_ms AS "created_ms: types::Millis"
+                created_ms AS "created_ms: types::Millis",
+                comment,
+                venue,
+                first_server
             "#,
             player_one,
             player_two,
@@ -87,43 +738,149 @@ impl Games<'_> {
             score_two,
             challenge,
             millis,
+            registrar,
+            comment,
+            venue,
+            first_server,
         )
         .fetch_one(tx.as_mut())
         .await?;
 
-        let mut updates =
-            Self::execute_refresh(Some(millis), default_rating, rating_updater, &mut tx).await?;
+        let (mut updates, derived) =
+            Self::execute_refresh(Some(millis), default_rating, rating_updater, tx).await?;
 
         let game = match updates.iter().position(|g| g.id == game.id) {
             Some(idx) => updates.swap_remove(idx),
             None => game,
         };
 
-        tx.commit().await?;
+        Ok((game, updates, derived))
+    }
 
-        self.store.update_version();
+    pub(super) async fn execute_record_points(
+        game: types::Id,
+        points: &[types::Point],
+        tx: &mut sqlx::Transaction<'static, sqlx::Sqlite>,
+    ) -> Result<(types::Id, types::Id)> {
+        let played = sqlx::query!(
+            r#"
+            SELECT
+                player_one,
+                player_two,
+                score_one,
+                score_two
+            FROM
+                games
+            WHERE
+                id = $1
+            "#,
+            game,
+        )
+        .fetch_one(tx.as_mut())
+        .await?;
 
-        Ok((game, updates))
+        if points.is_empty() {
+            sqlx::query!(
+                r#"
+                DELETE FROM
+                    points
+                WHERE
+                    game = $1
+                "#,
+                game,
+            )
+            .execute(tx.as_mut())
+            .await?;
+        } else {
+            validate_points(played.score_one, played.score_two, points)?;
+
+            let log = encode_points(points);
+            sqlx::query!(
+                r#"
+                INSERT INTO points (
+                    game,
+                    log
+                ) VALUES (
+                    $1,
+                    $2
+                ) ON CONFLICT(game) DO UPDATE SET
+                    log = excluded.log
+                "#,
+                game,
+                log,
+            )
+            .execute(tx.as_mut())
+            .await?;
+        }
+
+        Ok((played.player_one, played.player_two))
     }
 
-    #[tracing::instrument(skip(self, rating_updater))]
-    pub async fn update<F>(
-        &self,
+    async fn execute_update<F>(
         game: types::Game,
+        (editor, reason): (types::Id, Option<&str>),
+        venues: &[String],
         default_rating: f64,
         rating_updater: F,
-    ) -> Result<(types::Game, Vec<types::Game>)>
+        tx: &mut sqlx::Transaction<'static, sqlx::Sqlite>,
+    ) -> Result<(types::Game, Vec<types::Game>, types::Derived)>
     where
         F: Copy + Fn(f64, f64, bool, bool) -> f64,
     {
+        let game = types::Game {
+            comment: normalize(game.comment.as_deref()).map(String::from),
+            venue: normalize(game.venue.as_deref()).map(String::from),
+            ..game
+        };
+
+        let old = sqlx::query!(
+            r#"
+            SELECT
+                millis AS "millis: types::Millis",
+                venue
+            FROM
+                games
+            WHERE
+                id = $1
+            "#,
+            game.id,
+        )
+        .fetch_one(tx.as_mut())
+        .await?;
+
+        // Venues can be removed from the configuration, so games keep the venue they were
+        // registered with unless it changes
+        let venue = game
+            .venue
+            .as_deref()
+            .filter(|&v| old.venue.as_deref() != Some(v));
+
         validate_game(
-            game.player_one,
-            game.player_two,
-            game.score_one,
-            game.score_two,
+            (game.player_one, game.player_two),
+            (game.score_one, game.score_two),
+            (game.comment.as_deref(), venue, game.first_server),
+            venues,
         )?;
 
-        let mut tx = self.store.pool.begin().await?;
+        let reason = normalize(reason);
+
+        let log = sqlx::query_scalar!(
+            r#"
+            SELECT
+                log
+            FROM
+                points
+            WHERE
+                game = $1
+            "#,
+            game.id,
+        )
+        .fetch_optional(tx.as_mut())
+        .await?;
+
+        if let Some(log) = log {
+            validate_points(game.score_one, game.score_two, &decode_points(&log)?)?;
+        }
 
         if game.challenge && !game.deleted {
             Self::validate_challenge(
@@ -136,20 +893,8 @@ impl Games<'_> {
             .await?;
         }
 
-        let old_millis = sqlx::query_scalar!(
-            r#"
-            SELECT
-                millis AS "millis: types::Millis"
-            FROM
-                games
-            WHERE
-                id = $1
-            "#,
-            game.id,
-        )
-        .fetch_one(tx.as_mut())
-        .await?;
-
+        super::tournaments::Tournaments::validate_update(&game, tx.as_mut()).await?;
+
         let new_millis = sqlx::query_scalar!(
             r#"
             UPDATE games
@@ -160,7 +905,12 @@ impl Games<'_> {
                 score_two = $5,
                 challenge = $6,
                 deleted = $7,
-                millis = $8
+                millis = $8,
+                editor = $9,
+                reason = $10,
+                comment = $11,
+                venue = $12,
+                first_server = $13
             WHERE
                 id = $1
             RETURNING
@@ -174,135 +924,68 @@ impl Games<'_> {
             game.challenge,
             game.deleted,
             game.millis,
+            editor,
+            reason,
+            game.comment,
+            game.venue,
+            game.first_server,
         )
         .fetch_one(tx.as_mut())
         .await?;
 
-        let mut updates = Self::execute_refresh(
-            Some(old_millis.min(new_millis)),
-            default_rating,
-            rating_updater,
-            &mut tx,
-        )
-        .await?;
+        let from = old.millis.min(new_millis);
+
+        let (mut updates, derived) =
+            Self::execute_refresh(Some(from), default_rating, rating_updater, tx).await?;
 
         let game = match updates.iter().position(|g| g.id == game.id) {
             Some(idx) => updates.swap_remove(idx),
             None => game,
         };
 
-        tx.commit().await?;
-
-        self.store.update_version();
-
-        Ok((game, updates))
-    }
-
-    #[tracing::instrument(skip(self))]
-    pub async fn history(&self, game: types::Id) -> Result<Vec<types::History>> {
-        sqlx::query_as!(
-            types::History,
-            r#"
-            SELECT
-                id,
-                game,
-                player_one,
-                player_two,
-                score_one,
-                score_two,
-                challenge,
-                deleted,
-                millis AS "millis: types::Millis",
-                created_ms AS "created_ms: types::Millis"
-            FROM
-                history
-            WHERE
-                game = $1
-            ORDER BY
-                created_ms DESC
-            "#,
-            game,
-        )
-        .fetch_all(&self.store.pool)
-        .await
-        .map_err(Error::from)
-    }
-
-    async fn list_games<'c, 'e, E>(executor: E) -> Result<Vec<types::Game>>
-    where
-        'c: 'e,
-        E: 'e + sqlx::Executor<'c, Database = sqlx::Sqlite>,
-    {
-        sqlx::query_as!(
-            types::Game,
-            r#"
-            SELECT
-                id,
-                player_one,
-                player_two,
-                score_one,
-                score_two,
-                rating_one,
-                rating_two,
-                rating_delta,
-                challenge,
-                deleted,
-                millis AS "millis: types::Millis",
-                created_ms AS "created_ms: types::Millis"
-            FROM
-                games
-            ORDER BY
-                millis ASC
-            "#
-        )
-        .fetch_all(executor)
-        .await
-        .map_err(Error::from)
-    }
-
-    #[tracing::instrument(skip(self, rating_updater))]
-    pub async fn refresh<F>(
-        &self,
-        default_rating: f64,
-        rating_updater: F,
-    ) -> Result<Vec<types::Game>>
-    where
-        F: Copy + Fn(f64, f64, bool, bool) -> f64,
-    {
-        let mut tx = self.store.pool.begin().await?;
-        let games = Self::execute_refresh(None, default_rating, rating_updater, &mut tx).await?;
-        tx.commit().await?;
-
-        if !games.is_empty() {
-            self.store.update_version();
-        }
-
-        Ok(games)
+        Ok((game, updates, derived))
     }
-}
 
-impl Games<'_> {
-    async fn execute_refresh<F>(
+    // Whatever is derived from the games is refreshed along with their ratings, so that it never
+    // falls behind the games it was derived from
+    pub(super) async fn execute_refresh<F>(
         from: Option<types::Millis>,
         default_rating: f64,
         rating_updater: F,
         tx: &mut sqlx::Transaction<'static, sqlx::Sqlite>,
-    ) -> Result<Vec<types::Game>>
+    ) -> Result<(Vec<types::Game>, types::Derived)>
     where
         F: Copy + Fn(f64, f64, bool, bool) -> f64,
     {
         let updates = Self::build_updates(from, default_rating, rating_updater, tx).await?;
 
-        if let Some(mut query) = build_update_query(&updates) {
+        if from.is_some() || !updates.is_empty() {
+            super::leaderboard::Leaderboard::invalidate(from, tx).await?;
+            super::seasons::Seasons::invalidate(from, tx).await?;
+        }
+
+        let updates = if let Some(mut query) = build_update_query(&updates) {
             query
                 .build_query_as()
                 .persistent(false)
                 .fetch_all(tx.as_mut())
-                .await
-                .map_err(Into::into)
+                .await?
         } else {
-            Ok(Vec::new())
-        }
+            Vec::new()
+        };
+
+        let streaks = super::streaks::Streaks::execute_refresh(from, tx).await?;
+        let (unlocked, revoked) =
+            super::achievements::Achievements::execute_evaluate(from, tx).await?;
+
+        Ok((
+            updates,
+            types::Derived {
+                streaks,
+                unlocked,
+                revoked,
+            },
+        ))
     }
 
     async fn build_updates<F>(
@@ -320,43 +1003,19 @@ impl Games<'_> {
             };
         }
 
-        let (updates, mut last_ratings) = Self::prepare_updates(from, tx).await?;
+        let (updates, last_ratings) = Self::prepare_updates(from, tx).await?;
+        let resets = super::seasons::Seasons::resets(tx.as_mut()).await?;
+        let mut ratings = Ratings::new(last_ratings, resets, default_rating);
 
         Ok(updates
             .into_iter()
             .filter_map(|game| {
-                let rating_one = last_ratings
-                    .get(&game.player_one)
-                    .copied()
-                    .unwrap_or(default_rating);
-                let rating_two = last_ratings
-                    .get(&game.player_two)
-                    .copied()
-                    .unwrap_or(default_rating);
-
-                let rating_delta = if game.deleted {
-                    0.0
-                } else {
-                    rating_updater(
-                        rating_one,
-                        rating_two,
-                        game.score_one > game.score_two,
-                        game.challenge,
-                    )
-                };
-
-                last_ratings.insert(game.player_one, rating_one + rating_delta);
-                last_ratings.insert(game.player_two, rating_two - rating_delta);
-
-                (f64_ne!(rating_one, game.rating_one)
-                    || f64_ne!(rating_two, game.rating_two)
-                    || f64_ne!(rating_delta, game.rating_delta))
-                .then_some(RatingUpdate {
-                    id: game.id,
-                    rating_one,
-                    rating_two,
-                    rating_delta,
-                })
+                let update = ratings.apply(&game, rating_updater);
+
+                (f64_ne!(update.rating_one, game.rating_one)
+                    || f64_ne!(update.rating_two, game.rating_two)
+                    || f64_ne!(update.rating_delta, game.rating_delta))
+                .then_some(update)
             })
             .collect())
     }
@@ -364,7 +1023,10 @@ impl Games<'_> {
     async fn prepare_updates(
         from: Option<types::Millis>,
         tx: &mut sqlx::Transaction<'static, sqlx::Sqlite>,
-    ) -> Result<(Vec<types::Game>, std::collections::HashMap<types::Id, f64>)> {
+    ) -> Result<(
+        Vec<types::Game>,
+        std::collections::HashMap<types::Id, (f64, types::Millis)>,
+    )> {
         if let Some(from) = from {
             let updates = sqlx::query_as!(
                 types::Game,
@@ -381,7 +1043,10 @@ impl Games<'_> {
                     challenge,
                     deleted,
                     millis AS "millis: types::Millis",
-                    created_ms AS "created_ms: types::Millis"
+                    created_ms AS "created_ms: types::Millis",
+                    comment,
+                    venue,
+                    first_server
                 FROM
                     games
                 WHERE
@@ -394,56 +1059,7 @@ impl Games<'_> {
             .fetch_all(tx.as_mut())
             .await?;
 
-            let last_ratings = sqlx::query!(
-                r#"
-                WITH
-                    ratings AS (
-                        SELECT
-                            player_one,
-                            player_two,
-                            rating_one + rating_delta AS rating_one,
-                            rating_two - rating_delta AS rating_two,
-                            MAX(millis) AS millis
-                        FROM
-                            games
-                        WHERE
-                            millis < $1
-                            AND NOT deleted
-                        GROUP BY
-                            player_one,
-                            player_two
-                    ),
-                    unified AS (
-                        SELECT
-                            player_one AS player,
-                            rating_one AS rating,
-                            millis
-                        FROM
-                            ratings
-                        UNION
-                            SELECT
-                                player_two AS player,
-                                rating_two AS rating,
-                                millis
-                            FROM
-                                ratings
-                    )
-                SELECT
-                    player AS "player!: types::Id",
-                    rating AS "rating!: f64",
-                    MAX(millis) AS "millis!: types::Millis"
-                FROM
-                    unified
-                GROUP BY
-                    player
-                "#,
-                from,
-            )
-            .map(|r| (r.player, r.rating))
-            .fetch_all(tx.as_mut())
-            .await?
-            .into_iter()
-            .collect();
+            let last_ratings = Self::last_ratings(from, tx.as_mut()).await?;
 
             Ok((updates, last_ratings))
         } else {
@@ -453,6 +1069,66 @@ impl Games<'_> {
         }
     }
 
+    async fn last_ratings<'c, 'e, E>(
+        before: types::Millis,
+        executor: E,
+    ) -> Result<std::collections::HashMap<types::Id, (f64, types::Millis)>>
+    where
+        'c: 'e,
+        E: 'e + sqlx::Executor<'c, Database = sqlx::Sqlite>,
+    {
+        sqlx::query!(
+            r#"
+            WITH
+                ratings AS (
+                    SELECT
+                        player_one,
+                        player_two,
+                        rating_one + rating_delta AS rating_one,
+                        rating_two - rating_delta AS rating_two,
+                        MAX(millis) AS millis
+                    FROM
+                        games
+                    WHERE
+                        millis < $1
+                        AND NOT deleted
+                    GROUP BY
+                        player_one,
+                        player_two
+                ),
+                unified AS (
+                    SELECT
+                        player_one AS player,
+                        rating_one AS rating,
+                        millis
+                    FROM
+                        ratings
+                    UNION
+                        SELECT
+                            player_two AS player,
+                            rating_two AS rating,
+                            millis
+                        FROM
+                            ratings
+                )
+            SELECT
+                player AS "player!: types::Id",
+                rating AS "rating!: f64",
+                MAX(millis) AS "millis!: types::Millis"
+            FROM
+                unified
+            GROUP BY
+                player
+            "#,
+            before,
+        )
+        .map(|r| (r.player, (r.rating, r.millis)))
+        .fetch_all(executor)
+        .await
+        .map(|r| r.into_iter().collect())
+        .map_err(Error::from)
+    }
+
     async fn validate_challenge<'c, 'e, E>(
         player_one: types::Id,
         player_two: types::Id,
@@ -464,52 +1140,9 @@ impl Games<'_> {
         'c: 'e,
         E: 'e + sqlx::Executor<'c, Database = sqlx::Sqlite>,
     {
-        let millis = i64::from(millis);
-        let challenged_today = if let Some(ignore) = ignore {
-            sqlx::query!(
-                r#"
-                SELECT
-                    id
-                FROM
-                    games
-                WHERE
-                    challenge
-                    AND NOT deleted
-                    AND player_one IN ($1, $2)
-                    AND player_two IN ($1, $2)
-                    AND STRFTIME('%Y%m%d', $3 / 1000, 'unixepoch') = STRFTIME('%Y%m%d', millis / 1000, 'unixepoch')
-                    AND id <> $4
-                "#,
-                player_one,
-                player_two,
-                millis,
-                ignore,
-            )
-            .fetch_optional(executor)
-            .await?
-            .is_some()
-        } else {
-            sqlx::query!(
-                r#"
-                SELECT
-                    id
-                FROM
-                    games
-                WHERE
-                    challenge
-                    AND NOT deleted
-                    AND player_one IN ($1, $2)
-                    AND player_two IN ($1, $2)
-                    AND STRFTIME('%Y%m%d', $3 / 1000, 'unixepoch') = STRFTIME('%Y%m%d', millis / 1000, 'unixepoch')
-                "#,
-                player_one,
-                player_two,
-                millis,
-            )
-            .fetch_optional(executor)
+        let challenged_today = Self::challenged_today(player_one, millis, ignore, executor)
             .await?
-            .is_some()
-        };
+            .contains(&player_two);
 
         if challenged_today {
             return Err(Error::InvalidValue(
@@ -519,6 +1152,45 @@ impl Games<'_> {
 
         Ok(())
     }
+
+    // Players can only challenge each other once a day, so these are the opponents the player can
+    // no longer challenge on the day of `millis`
+    pub(super) async fn challenged_today<'c, 'e, E>(
+        player: types::Id,
+        millis: types::Millis,
+        ignore: Option<types::Id>,
+        executor: E,
+    ) -> Result<std::collections::HashSet<types::Id>>
+    where
+        'c: 'e,
+        E: 'e + sqlx::Executor<'c, Database = sqlx::Sqlite>,
+    {
+        let millis = i64::from(millis);
+        sqlx::query_scalar!(
+            r#"
+            SELECT
+                CASE
+                    WHEN player_one = $1 THEN player_two
+                    ELSE player_one
+                END AS "opponent!: types::Id"
+            FROM
+                games
+            WHERE
+                challenge
+                AND NOT deleted
+                AND $1 IN (player_one, player_two)
+                AND STRFTIME('%Y%m%d', $2 / 1000, 'unixepoch') = STRFTIME('%Y%m%d', millis / 1000, 'unixepoch')
+                AND id IS NOT $3
+            "#,
+            player,
+            millis,
+            ignore,
+        )
+        .fetch_all(executor)
+        .await
+        .map(|r| r.into_iter().collect())
+        .map_err(Error::from)
+    }
 }
 
 #[derive(Debug)]
@@ -529,6 +1201,122 @@ struct RatingUpdate {
     rating_delta: f64,
 }
 
+pub(super) struct Ratings {
+    last: std::collections::HashMap<types::Id, (f64, types::Millis)>,
+    resets: Vec<super::seasons::Reset>,
+    default_rating: f64,
+}
+
+impl Ratings {
+    pub(super) fn new(
+        last: std::collections::HashMap<types::Id, (f64, types::Millis)>,
+        resets: Vec<super::seasons::Reset>,
+        default_rating: f64,
+    ) -> Self {
+        Self {
+            last,
+            resets,
+            default_rating,
+        }
+    }
+
+    fn get(&self, player: types::Id, millis: types::Millis) -> f64 {
+        self.last
+            .get(&player)
+            .map_or(self.default_rating, |&(rating, last)| {
+                super::seasons::apply_resets(
+                    rating,
+                    last,
+                    millis,
+                    &self.resets,
+                    self.default_rating,
+                )
+            })
+    }
+
+    fn apply<F>(&mut self, game: &types::Game, rating_updater: F) -> RatingUpdate
+    where
+        F: Fn(f64, f64, bool, bool) -> f64,
+    {
+        let rating_one = self.get(game.player_one, game.millis);
+        let rating_two = self.get(game.player_two, game.millis);
+
+        let rating_delta = if game.deleted {
+            0.0
+        } else {
+            rating_updater(
+                rating_one,
+                rating_two,
+                game.score_one > game.score_two,
+                game.challenge,
+            )
+        };
+
+        self.last
+            .insert(game.player_one, (rating_one + rating_delta, game.millis));
+        self.last
+            .insert(game.player_two, (rating_two - rating_delta, game.millis));
+
+        RatingUpdate {
+            id: game.id,
+            rating_one,
+            rating_two,
+            rating_delta,
+        }
+    }
+
+    pub(super) fn replay<F>(&mut self, games: &[types::Game], rating_updater: F)
+    where
+        F: Copy + Fn(f64, f64, bool, bool) -> f64,
+    {
+        for game in games {
+            self.apply(game, rating_updater);
+        }
+    }
+
+    pub(super) fn at(&self, millis: types::Millis) -> impl Iterator<Item = (types::Id, f64)> + '_ {
+        self.last
+            .keys()
+            .map(move |&player| (player, self.get(player, millis)))
+    }
+}
+
+#[derive(Debug)]
+struct RatingStep {
+    millis: types::Millis,
+    before: f64,
+    after: f64,
+}
+
+fn bucket_ratings(
+    steps: Vec<RatingStep>,
+    resolution: types::Resolution,
+) -> Vec<types::RatingPoint> {
+    let mut points = Vec::<types::RatingPoint>::new();
+
+    for step in steps {
+        let millis = super::bucket_start(step.millis, resolution);
+
+        match points.last_mut() {
+            Some(point) if resolution != types::Resolution::Game && point.millis == millis => {
+                point.rating = step.after;
+                point.peak = point.peak.max(step.after);
+                point.trough = point.trough.min(step.after);
+                point.games += 1;
+            }
+            _ => points.push(types::RatingPoint {
+                millis,
+                rating: step.after,
+                peak: step.before.max(step.after),
+                trough: step.before.min(step.after),
+                games: 1,
+            }),
+        }
+    }
+
+    points
+}
+
 fn build_update_query(
     updates: &[RatingUpdate],
 ) -> Option<sqlx::QueryBuilder<'static, sqlx::Sqlite>> {
@@ -562,16 +1350,111 @@ fn build_update_query(
     for update in updates {
         separated_builder.push_bind(update.id);
     }
-    builder.push(") RETURNING id, player_one, player_two, score_one, score_two, rating_one, rating_two, rating_delta, challenge, deleted, millis, created_ms");
+    builder.push(") RETURNING id, player_one, player_two, score_one, score_two, rating_one, rating_two, rating_delta, challenge, deleted, millis, created_ms, comment, venue, first_server");
 
     Some(builder)
 }
 
+// Points are stored as one character each: `1` and `2` for the side that won the point, `a` and
+// `b` for the side that the serve changed over to
+pub(super) fn encode_points(points: &[types::Point]) -> String {
+    points
+        .iter()
+        .map(|point| match point {
+            types::Point::Won(types::Side::One) => '1',
+            types::Point::Won(types::Side::Two) => '2',
+            types::Point::Serve(types::Side::One) => 'a',
+            types::Point::Serve(types::Side::Two) => 'b',
+        })
+        .collect()
+}
+
+pub(super) fn decode_points(log: &str) -> Result<Vec<types::Point>> {
+    log.chars()
+        .map(|c| match c {
+            '1' => Ok(types::Point::Won(types::Side::One)),
+            '2' => Ok(types::Point::Won(types::Side::Two)),
+            'a' => Ok(types::Point::Serve(types::Side::One)),
+            'b' => Ok(types::Point::Serve(types::Side::Two)),
+            _ => Err(Error::Query(sqlx::Error::Decode(
+                format!("Invalid point `{c}`").into(),
+            ))),
+        })
+        .collect()
+}
+
+pub(super) fn validate_points(score_one: i64, score_two: i64, points: &[types::Point]) -> Result {
+    let mut score = (0, 0);
+    let mut last = None;
+    let mut server = None;
+
+    for &point in points {
+        match point {
+            types::Point::Won(side) => {
+                match side {
+                    types::Side::One => score.0 += 1,
+                    types::Side::Two => score.1 += 1,
+                }
+                last = Some(side);
+            }
+            types::Point::Serve(side) => {
+                if server == Some(side) {
+                    return Err(Error::InvalidValue(
+                        "The serve can only change over to the other side",
+                    ));
+                }
+                server = Some(side);
+            }
+        }
+    }
+
+    let winner = if score_one > score_two {
+        types::Side::One
+    } else {
+        types::Side::Two
+    };
+
+    if score != (score_one, score_two) {
+        Err(Error::InvalidValue("Points must add up to the game score"))
+    } else if last != Some(winner) {
+        Err(Error::InvalidValue(
+            "The last point must be won by the winner",
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+fn longest_runs(points: &[types::Point]) -> (i64, i64) {
+    let mut runs = (0, 0);
+    let mut current = (0, 0);
+
+    for point in points {
+        match point {
+            types::Point::Won(types::Side::One) => {
+                current = (current.0 + 1, 0);
+                runs.0 = runs.0.max(current.0);
+            }
+            types::Point::Won(types::Side::Two) => {
+                current = (0, current.1 + 1);
+                runs.1 = runs.1.max(current.1);
+            }
+            types::Point::Serve(_) => {}
+        }
+    }
+
+    runs
+}
+
+fn normalize(value: Option<&str>) -> Option<&str> {
+    value.map(str::trim).filter(|v| !v.is_empty())
+}
+
 fn validate_game(
-    player_one: types::Id,
-    player_two: types::Id,
-    score_one: impl Into<i64>,
-    score_two: impl Into<i64>,
+    (player_one, player_two): (types::Id, types::Id),
+    (score_one, score_two): (impl Into<i64>, impl Into<i64>),
+    (comment, venue, first_server): (Option<&str>, Option<&str>, Option<types::Id>),
+    venues: &[String],
 ) -> Result {
     let score_one = score_one.into();
     let score_two = score_two.into();
@@ -591,6 +1474,16 @@ fn validate_game(
         Err(Error::InvalidValue("Tie breaks require a 12x10 score"))
     } else if (score_one == 11 && score_two >= 11) || (score_two == 11 && score_one >= 11) {
         Err(Error::InvalidValue("There can only be one winner"))
+    } else if comment.is_some_and(|c| c.chars().count() > MAX_COMMENT) {
+        Err(Error::InvalidValue(
+            "Comments cannot be longer than 256 characters",
+        ))
+    } else if venue.is_some_and(|v| !venues.iter().any(|venue| venue == v)) {
+        Err(Error::InvalidValue("Unknown venue"))
+    } else if first_server.is_some_and(|s| s != player_one && s != player_two) {
+        Err(Error::InvalidValue(
+            "The first server must be one of the players",
+        ))
     } else {
         Ok(())
     }
@@ -124,6 +124,15 @@ impl Invites<'_> {
         .fetch_one(tx.as_mut())
         .await?;
 
+        super::audit::Audit::record(
+            Some(inviter),
+            types::AuditAction::Invite,
+            (types::Entity::Invite, invite.id),
+            (None, super::audit::Audit::snapshot(&invite)?),
+            tx.as_mut(),
+        )
+        .await?;
+
         tx.commit().await?;
 
         self.store.update_version();
@@ -133,7 +142,9 @@ impl Invites<'_> {
 
     #[tracing::instrument(skip(self))]
     pub async fn cancel(&self, inviter: types::Id, id: types::Id) -> Result<types::Invite> {
-        sqlx::query_as!(
+        let mut tx = self.store.pool.begin().await?;
+
+        let invite = sqlx::query_as!(
             types::Invite,
             r#"
             DELETE FROM
@@ -151,10 +162,23 @@ impl Invites<'_> {
             id,
             inviter
         )
-        .fetch_one(&self.store.pool)
-        .await
-        .inspect(|_| self.store.update_version())
-        .map_err(Error::from)
+        .fetch_one(tx.as_mut())
+        .await?;
+
+        super::audit::Audit::record(
+            Some(inviter),
+            types::AuditAction::CancelInvite,
+            (types::Entity::Invite, invite.id),
+            (super::audit::Audit::snapshot(&invite)?, None),
+            tx.as_mut(),
+        )
+        .await?;
+
+        tx.commit().await?;
+
+        self.store.update_version();
+
+        Ok(invite)
     }
 
     #[tracing::instrument(skip(self))]
@@ -222,6 +246,18 @@ impl Invites<'_> {
         .fetch_one(tx.as_mut())
         .await?;
 
+        super::audit::Audit::record(
+            Some(player.id),
+            types::AuditAction::AcceptInvite,
+            (types::Entity::Invite, invite.id),
+            (
+                super::audit::Audit::snapshot(&invite)?,
+                super::audit::Audit::snapshot(&player)?,
+            ),
+            tx.as_mut(),
+        )
+        .await?;
+
         tx.commit().await?;
 
         self.store.update_version();
@@ -269,6 +305,15 @@ impl Invites<'_> {
         .fetch_one(tx.as_mut())
         .await?;
 
+        super::audit::Audit::record(
+            None,
+            types::AuditAction::RejectInvite,
+            (types::Entity::Invite, invite.id),
+            (super::audit::Audit::snapshot(&invite)?, None),
+            tx.as_mut(),
+        )
+        .await?;
+
         tx.commit().await?;
 
         self.store.update_version();
@@ -0,0 +1,448 @@
+use super::super::error::Error;
+use crate::types;
+
+type Result<T = ()> = std::result::Result<T, Error>;
+
+pub struct Leaderboard<'a> {
+    store: &'a super::Store,
+}
+
+impl<'a> From<&'a super::Store> for Leaderboard<'a> {
+    fn from(store: &'a super::Store) -> Self {
+        Self { store }
+    }
+}
+
+impl Leaderboard<'_> {
+    #[tracing::instrument(skip(self))]
+    pub async fn get(
+        &self,
+        at: Option<types::Millis>,
+        min_games: i64,
+        default_rating: f64,
+    ) -> Result<Vec<types::Standing>> {
+        let now = types::Millis::now();
+        let day = i64::from(super::bucket_start(
+            at.unwrap_or(now),
+            types::Resolution::Day,
+        ));
+
+        let mut tx = self.store.pool.begin().await?;
+
+        let current = if at.is_some() {
+            Self::ranking(day, now, min_games, default_rating, &mut tx).await?
+        } else {
+            Self::compute(
+                types::Millis::from(i64::MAX),
+                now,
+                min_games,
+                default_rating,
+                &mut tx,
+            )
+            .await?
+        };
+
+        let previous = Self::ranking(
+            day - 7 * super::DAY,
+            now,
+            min_games,
+            default_rating,
+            &mut tx,
+        )
+        .await?
+        .into_iter()
+        .map(|r| (r.player, r))
+        .collect::<std::collections::HashMap<_, _>>();
+
+        tx.commit().await?;
+
+        Ok(current
+            .into_iter()
+            .map(|r| {
+                let previous = previous.get(&r.player);
+                types::Standing {
+                    player: r.player,
+                    rank: r.rank,
+                    rating: r.rating,
+                    games: r.games,
+                    previous_rank: previous.and_then(|p| p.rank),
+                    previous_rating: previous.map(|p| p.rating),
+                }
+            })
+            .collect())
+    }
+
+    // Persists the standings at the end of every day that is over since the latest snapshot, which
+    // also rebuilds the days invalidated by changes to their games
+    #[tracing::instrument(skip(self))]
+    pub async fn snapshot(&self, min_games: i64, default_rating: f64) -> Result {
+        let now = types::Millis::now();
+        let yesterday = i64::from(super::bucket_start(now, types::Resolution::Day)) - super::DAY;
+
+        let mut tx = self.store.pool.begin().await?;
+
+        let latest = sqlx::query!(
+            r#"
+            SELECT
+                MAX(day) AS "day?: i64"
+            FROM
+                snapshots
+            WHERE
+                min_games = $1
+            "#,
+            min_games,
+        )
+        .map(|r| r.day)
+        .fetch_one(tx.as_mut())
+        .await?;
+
+        let mut day = latest.map_or(yesterday, |latest| latest + super::DAY);
+        while day <= yesterday {
+            let ranking = Self::compute(
+                types::Millis::from(day + super::DAY),
+                now,
+                min_games,
+                default_rating,
+                &mut tx,
+            )
+            .await?;
+
+            if let Some(mut query) = build_snapshot_query(day, min_games, &ranking) {
+                query.build().persistent(false).execute(tx.as_mut()).await?;
+            }
+
+            day += super::DAY;
+        }
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self, rating_updater))]
+    pub async fn all_time<F>(
+        &self,
+        min_games: i64,
+        default_rating: f64,
+        rating_updater: F,
+    ) -> Result<Vec<types::Standing>>
+    where
+        F: Copy + Fn(f64, f64, bool, bool) -> f64,
+    {
+        let games = super::games::Games::list_games(&self.store.pool).await?;
+
+        // Replaying without any season resets gives the all-time ratings
+        let ranking = replay(
+            &games,
+            Vec::new(),
+            types::Millis::now(),
+            (min_games, default_rating),
+            rating_updater,
+        );
+
+        Ok(ranking
+            .into_iter()
+            .map(|r| types::Standing {
+                player: r.player,
+                rank: r.rank,
+                rating: r.rating,
+                games: r.games,
+                previous_rank: None,
+                previous_rating: None,
+            })
+            .collect())
+    }
+
+    #[tracing::instrument(skip(self, rating_updater, simulated_updater))]
+    pub async fn simulate<F, G>(
+        &self,
+        min_games: i64,
+        default_rating: f64,
+        rating_updater: F,
+        simulated_updater: G,
+    ) -> Result<Vec<types::Comparison>>
+    where
+        F: Copy + Fn(f64, f64, bool, bool) -> f64,
+        G: Copy + Fn(f64, f64, bool, bool) -> f64,
+    {
+        let now = types::Millis::now();
+
+        let mut tx = self.store.pool.begin().await?;
+        let games = super::games::Games::list_games(tx.as_mut()).await?;
+        let resets = super::seasons::Seasons::resets(tx.as_mut()).await?;
+        tx.commit().await?;
+
+        let current = replay(
+            &games,
+            resets.clone(),
+            now,
+            (min_games, default_rating),
+            rating_updater,
+        )
+        .into_iter()
+        .map(|r| (r.player, r))
+        .collect::<std::collections::HashMap<_, _>>();
+
+        Ok(replay(
+            &games,
+            resets,
+            now,
+            (min_games, default_rating),
+            simulated_updater,
+        )
+        .into_iter()
+        .map(|simulated| {
+            let current = current.get(&simulated.player);
+            types::Comparison {
+                player: simulated.player,
+                games: simulated.games,
+                rank: current.and_then(|c| c.rank),
+                rating: current.map_or(default_rating, |c| c.rating),
+                simulated_rank: simulated.rank,
+                simulated_rating: simulated.rating,
+            }
+        })
+        .collect())
+    }
+}
+
+impl Leaderboard<'_> {
+    pub(super) async fn invalidate(
+        from: Option<types::Millis>,
+        tx: &mut sqlx::Transaction<'static, sqlx::Sqlite>,
+    ) -> Result {
+        // Snapshots are taken at the end of the day, so any day ending after `from` is stale
+        let from = from.map(|from| i64::from(from) - super::DAY);
+
+        sqlx::query!(
+            r#"
+            DELETE FROM
+                snapshots
+            WHERE
+                $1 IS NULL
+                OR day > $1
+            "#,
+            from,
+        )
+        .execute(tx.as_mut())
+        .await?;
+
+        Ok(())
+    }
+
+    async fn ranking(
+        day: i64,
+        now: types::Millis,
+        min_games: i64,
+        default_rating: f64,
+        tx: &mut sqlx::Transaction<'static, sqlx::Sqlite>,
+    ) -> Result<Vec<Ranking>> {
+        let end = types::Millis::from(day + super::DAY);
+
+        // Days that are not over yet can still change, so they never have a snapshot
+        if end > now {
+            return Self::compute(end, now, min_games, default_rating, tx).await;
+        }
+
+        let snapshot = sqlx::query_as!(
+            Ranking,
+            r#"
+            SELECT
+                player,
+                rank,
+                rating,
+                games
+            FROM
+                snapshots
+            WHERE
+                day = $1
+                AND min_games = $2
+            ORDER BY
+                rank IS NULL,
+                rank ASC,
+                rating DESC
+            "#,
+            day,
+            min_games,
+        )
+        .fetch_all(tx.as_mut())
+        .await?;
+
+        if snapshot.is_empty() {
+            Self::compute(end, now, min_games, default_rating, tx).await
+        } else {
+            Ok(snapshot)
+        }
+    }
+
+    async fn compute(
+        end: types::Millis,
+        now: types::Millis,
+        min_games: i64,
+        default_rating: f64,
+        tx: &mut sqlx::Transaction<'static, sqlx::Sqlite>,
+    ) -> Result<Vec<Ranking>> {
+        let resets = super::seasons::Seasons::resets(tx.as_mut()).await?;
+
+        let mut ranking = sqlx::query!(
+            r#"
+            WITH
+                unified AS (
+                    SELECT
+                        id,
+                        player_one AS player,
+                        rating_one + rating_delta AS rating,
+                        millis
+                    FROM
+                        games
+                    WHERE
+                        millis < $1
+                        AND NOT deleted
+                    UNION ALL
+                        SELECT
+                            id,
+                            player_two AS player,
+                            rating_two - rating_delta AS rating,
+                            millis
+                        FROM
+                            games
+                        WHERE
+                            millis < $1
+                            AND NOT deleted
+                ),
+                latest AS (
+                    SELECT
+                        player,
+                        rating,
+                        millis,
+                        ROW_NUMBER() OVER (
+                            PARTITION BY player
+                            ORDER BY millis DESC, id DESC
+                        ) AS position,
+                        COUNT(*) OVER (PARTITION BY player) AS games
+                    FROM
+                        unified
+                )
+            SELECT
+                players.id AS "player!: types::Id",
+                COALESCE(latest.rating, $2) AS "rating!: f64",
+                COALESCE(latest.games, 0) AS "games!: i64",
+                latest.millis AS "millis?: types::Millis"
+            FROM
+                players
+            LEFT JOIN
+                latest
+            ON
+                latest.player = players.id
+                AND latest.position = 1
+            WHERE
+                players.created_ms < $1
+                OR latest.player IS NOT NULL
+            "#,
+            end,
+            default_rating,
+        )
+        .map(|r| Ranking {
+            player: r.player,
+            rank: None,
+            // Seasons that started since the last game still reset the rating
+            rating: r.millis.map_or(r.rating, |millis| {
+                super::seasons::apply_resets(
+                    r.rating,
+                    millis,
+                    end.min(now),
+                    &resets,
+                    default_rating,
+                )
+            }),
+            games: r.games,
+        })
+        .fetch_all(tx.as_mut())
+        .await?;
+
+        rank(&mut ranking, min_games);
+
+        Ok(ranking)
+    }
+}
+
+#[derive(Debug)]
+pub(super) struct Ranking {
+    pub(super) player: types::Id,
+    pub(super) rank: Option<i64>,
+    pub(super) rating: f64,
+    pub(super) games: i64,
+}
+
+pub(super) fn rank(ranking: &mut [Ranking], min_games: i64) {
+    ranking.sort_by(|lhs, rhs| {
+        (rhs.games >= min_games)
+            .cmp(&(lhs.games >= min_games))
+            .then_with(|| rhs.rating.total_cmp(&lhs.rating))
+    });
+
+    ranking
+        .iter_mut()
+        .filter(|r| r.games >= min_games)
+        .zip(1..)
+        .for_each(|(r, rank)| r.rank = Some(rank));
+}
+
+fn replay<F>(
+    games: &[types::Game],
+    resets: Vec<super::seasons::Reset>,
+    now: types::Millis,
+    (min_games, default_rating): (i64, f64),
+    rating_updater: F,
+) -> Vec<Ranking>
+where
+    F: Copy + Fn(f64, f64, bool, bool) -> f64,
+{
+    let mut ratings =
+        super::games::Ratings::new(std::collections::HashMap::new(), resets, default_rating);
+    ratings.replay(games, rating_updater);
+
+    let mut counts = std::collections::HashMap::<types::Id, i64>::new();
+    for game in games.iter().filter(|g| !g.deleted) {
+        *counts.entry(game.player_one).or_default() += 1;
+        *counts.entry(game.player_two).or_default() += 1;
+    }
+
+    let mut ranking = ratings
+        .at(now)
+        .map(|(player, rating)| Ranking {
+            player,
+            rank: None,
+            rating,
+            games: counts.get(&player).copied().unwrap_or_default(),
+        })
+        .collect::<Vec<_>>();
+
+    rank(&mut ranking, min_games);
+
+    ranking
+}
+
+fn build_snapshot_query(
+    day: i64,
+    min_games: i64,
+    ranking: &[Ranking],
+) -> Option<sqlx::QueryBuilder<'static, sqlx::Sqlite>> {
+    if ranking.is_empty() {
+        return None;
+    }
+
+    let mut builder = sqlx::QueryBuilder::new(
+        "INSERT OR REPLACE INTO snapshots (day, min_games, player, rank, rating, games) ",
+    );
+    builder.push_values(ranking, |mut row, ranking| {
+        row.push_bind(day)
+            .push_bind(min_games)
+            .push_bind(ranking.player)
+            .push_bind(ranking.rank)
+            .push_bind(ranking.rating)
+            .push_bind(ranking.games);
+    });
+
+    Some(builder)
+}
@@ -0,0 +1,289 @@
+use super::super::error::Error;
+use crate::types;
+
+type Result<T = ()> = std::result::Result<T, Error>;
+
+const MAX_SCORE: i64 = 12;
+
+pub struct LiveGames<'a> {
+    store: &'a super::Store,
+}
+
+impl<'a> From<&'a super::Store> for LiveGames<'a> {
+    fn from(store: &'a super::Store) -> Self {
+        Self { store }
+    }
+}
+
+impl LiveGames<'_> {
+    #[tracing::instrument(skip(self))]
+    pub async fn list(&self) -> Vec<types::LiveGame> {
+        let mut games = self
+            .store
+            .live
+            .lock()
+            .await
+            .values()
+            .cloned()
+            .collect::<Vec<_>>();
+        games.sort_unstable_by_key(|g| (g.started_ms, g.scorer));
+        games
+    }
+
+    // Replaces the in-memory state with what was persisted, so games survive a restart
+    #[tracing::instrument(skip(self))]
+    pub async fn load(&self) -> Result {
+        let mut live = self.store.live.lock().await;
+
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                scorer,
+                player_one,
+                player_two,
+                log,
+                started_ms AS "started_ms: types::Millis"
+            FROM
+                live_games
+            "#
+        )
+        .fetch_all(&self.store.pool)
+        .await?;
+
+        live.clear();
+        for row in rows {
+            let points = super::games::decode_points(&row.log)?;
+            let (score_one, score_two) = score(&points);
+            live.insert(
+                row.scorer,
+                types::LiveGame {
+                    scorer: row.scorer,
+                    player_one: row.player_one,
+                    player_two: row.player_two,
+                    score_one,
+                    score_two,
+                    points,
+                    started_ms: row.started_ms,
+                },
+            );
+        }
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn start(
+        &self,
+        scorer: types::Id,
+        (player_one, player_two): (types::Id, types::Id),
+    ) -> Result<types::LiveGame> {
+        if player_one == player_two {
+            return Err(Error::InvalidValue("Players cannot be equal"));
+        }
+
+        let mut live = self.store.live.lock().await;
+        if live.contains_key(&scorer) {
+            return Err(Error::AlreadyExists);
+        }
+
+        let started_ms = sqlx::query_scalar!(
+            r#"
+            INSERT INTO live_games (
+                scorer,
+                player_one,
+                player_two
+            ) VALUES (
+                $1,
+                $2,
+                $3
+            )
+            RETURNING
+                started_ms AS "started_ms!: types::Millis"
+            "#,
+            scorer,
+            player_one,
+            player_two,
+        )
+        .fetch_one(&self.store.pool)
+        .await?;
+
+        let game = types::LiveGame {
+            scorer,
+            player_one,
+            player_two,
+            score_one: 0,
+            score_two: 0,
+            points: Vec::new(),
+            started_ms,
+        };
+        live.insert(scorer, game.clone());
+
+        Ok(game)
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn point(&self, scorer: types::Id, side: types::Side) -> Result<types::LiveGame> {
+        let mut live = self.store.live.lock().await;
+        let game = live.get_mut(&scorer).ok_or(Error::NotFound)?;
+
+        let mut points = game.points.clone();
+        points.push(types::Point::Won(side));
+
+        let (score_one, score_two) = score(&points);
+        if score_one > MAX_SCORE || score_two > MAX_SCORE {
+            return Err(Error::InvalidValue(
+                "Games cannot have a score larger than 12",
+            ));
+        }
+
+        Self::persist(scorer, &points, &self.store.pool).await?;
+
+        game.points = points;
+        game.score_one = score_one;
+        game.score_two = score_two;
+
+        Ok(game.clone())
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn undo(&self, scorer: types::Id) -> Result<types::LiveGame> {
+        let mut live = self.store.live.lock().await;
+        let game = live.get_mut(&scorer).ok_or(Error::NotFound)?;
+
+        let mut points = game.points.clone();
+        points
+            .pop()
+            .ok_or(Error::InvalidValue("There are no points to undo"))?;
+
+        Self::persist(scorer, &points, &self.store.pool).await?;
+
+        (game.score_one, game.score_two) = score(&points);
+        game.points = points;
+
+        Ok(game.clone())
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn cancel(&self, scorer: types::Id) -> Result<types::LiveGame> {
+        let mut live = self.store.live.lock().await;
+        if !live.contains_key(&scorer) {
+            return Err(Error::NotFound);
+        }
+
+        Self::remove(scorer, &self.store.pool).await?;
+
+        live.remove(&scorer).ok_or(Error::NotFound)
+    }
+
+    // Registers the final score as a regular game, attaching the points to it
+    #[tracing::instrument(skip(self, rating_updater))]
+    pub async fn finish<F>(
+        &self,
+        scorer: types::Id,
+        default_rating: f64,
+        rating_updater: F,
+    ) -> Result<(
+        types::LiveGame,
+        types::Game,
+        Vec<types::Game>,
+        types::Derived,
+    )>
+    where
+        F: Copy + Fn(f64, f64, bool, bool) -> f64,
+    {
+        let mut live = self.store.live.lock().await;
+        let game = live.get(&scorer).ok_or(Error::NotFound)?;
+
+        let score_one = u8::try_from(game.score_one)
+            .map_err(|_| Error::InvalidValue("Games cannot have a score larger than 12"))?;
+        let score_two = u8::try_from(game.score_two)
+            .map_err(|_| Error::InvalidValue("Games cannot have a score larger than 12"))?;
+
+        let mut tx = self.store.pool.begin().await?;
+
+        // Live games only track who won each point, so the first server is not known
+        let (registered, updates, derived) = super::games::Games::execute_register(
+            scorer,
+            (game.player_one, game.player_two),
+            (score_one, score_two),
+            false,
+            types::Millis::now(),
+            (None, None, None),
+            &self.store.venues,
+            default_rating,
+            rating_updater,
+            &mut tx,
+        )
+        .await?;
+
+        // Points that do not tell how the game was won are not worth keeping
+        if super::games::validate_points(game.score_one, game.score_two, &game.points).is_ok() {
+            super::games::Games::execute_record_points(registered.id, &game.points, &mut tx)
+                .await?;
+        }
+
+        Self::remove(scorer, tx.as_mut()).await?;
+
+        tx.commit().await?;
+
+        self.store.update_version();
+
+        let game = live.remove(&scorer).ok_or(Error::NotFound)?;
+
+        Ok((game, registered, updates, derived))
+    }
+}
+
+impl LiveGames<'_> {
+    async fn persist<'c, 'e, E>(scorer: types::Id, points: &[types::Point], executor: E) -> Result
+    where
+        'c: 'e,
+        E: 'e + sqlx::Executor<'c, Database = sqlx::Sqlite>,
+    {
+        let log = super::games::encode_points(points);
+        sqlx::query!(
+            r#"
+            UPDATE
+                live_games
+            SET
+                log = $2
+            WHERE
+                scorer = $1
+            "#,
+            scorer,
+            log,
+        )
+        .execute(executor)
+        .await
+        .map(|_| ())
+        .map_err(Error::from)
+    }
+
+    async fn remove<'c, 'e, E>(scorer: types::Id, executor: E) -> Result
+    where
+        'c: 'e,
+        E: 'e + sqlx::Executor<'c, Database = sqlx::Sqlite>,
+    {
+        sqlx::query!(
+            r#"
+            DELETE FROM
+                live_games
+            WHERE
+                scorer = $1
+            "#,
+            scorer,
+        )
+        .execute(executor)
+        .await
+        .map(|_| ())
+        .map_err(Error::from)
+    }
+}
+
+fn score(points: &[types::Point]) -> (i64, i64) {
+    points.iter().fold((0, 0), |(one, two), point| match point {
+        types::Point::Won(types::Side::One) => (one + 1, two),
+        types::Point::Won(types::Side::Two) => (one, two + 1),
+        types::Point::Serve(_) => (one, two),
+    })
+}
@@ -10,10 +10,12 @@ impl Store {
             r#"
             INSERT INTO players (
                 name,
-                email
+                email,
+                admin
             ) VALUES (
                 $1,
-                $2
+                $2,
+                TRUE
             ) RETURNING
                 id,
                 name,
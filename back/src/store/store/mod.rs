@@ -1,6 +1,15 @@
+mod achievements;
+mod audit;
 mod games;
 mod invites;
+mod leaderboard;
+mod live_games;
 mod players;
+mod seasons;
+mod streaks;
+mod tournaments;
+
+use crate::types;
 
 #[cfg(feature = "local")]
 mod mock;
@@ -12,6 +21,9 @@ mod tests;
 pub struct Store {
     pool: sqlx::sqlite::SqlitePool,
     version: std::sync::Arc<std::sync::atomic::AtomicU32>,
+    venues: std::sync::Arc<[String]>,
+    live: std::sync::Arc<tokio::sync::Mutex<std::collections::HashMap<types::Id, types::LiveGame>>>,
+    gate: std::sync::Arc<tokio::sync::RwLock<()>>,
 }
 
 impl Store {
@@ -31,7 +43,19 @@ impl Store {
 
         let version = std::sync::Arc::new(std::sync::atomic::AtomicU32::new(rand::random()));
 
-        Ok(Self { pool, version })
+        Ok(Self {
+            pool,
+            version,
+            venues: std::sync::Arc::from([]),
+            live: std::sync::Arc::default(),
+            gate: std::sync::Arc::default(),
+        })
+    }
+
+    #[must_use]
+    pub fn with_venues(mut self, venues: Vec<String>) -> Self {
+        self.venues = std::sync::Arc::from(venues);
+        self
     }
 
     pub async fn migrate(&self) -> Result<(), sqlx::migrate::MigrateError> {
@@ -43,6 +67,26 @@ impl Store {
         self.version.load(std::sync::atomic::Ordering::Relaxed)
     }
 
+    // Requests run side by side while holding a shared guard, so a holder of the exclusive guard
+    // sees the store unchanged by anyone else
+    pub async fn shared(&self) -> tokio::sync::RwLockReadGuard<'_, ()> {
+        self.gate.read().await
+    }
+
+    pub async fn exclusive(&self) -> tokio::sync::RwLockWriteGuard<'_, ()> {
+        self.gate.write().await
+    }
+
+    #[must_use]
+    pub fn achievements(&self) -> achievements::Achievements<'_> {
+        achievements::Achievements::from(self)
+    }
+
+    #[must_use]
+    pub fn audit(&self) -> audit::Audit<'_> {
+        audit::Audit::from(self)
+    }
+
     #[must_use]
     pub fn invites(&self) -> invites::Invites<'_> {
         invites::Invites::from(self)
@@ -53,11 +97,36 @@ impl Store {
         games::Games::from(self)
     }
 
+    #[must_use]
+    pub fn leaderboard(&self) -> leaderboard::Leaderboard<'_> {
+        leaderboard::Leaderboard::from(self)
+    }
+
+    #[must_use]
+    pub fn live_games(&self) -> live_games::LiveGames<'_> {
+        live_games::LiveGames::from(self)
+    }
+
     #[must_use]
     pub fn players(&self) -> players::Players<'_> {
         players::Players::from(self)
     }
 
+    #[must_use]
+    pub fn seasons(&self) -> seasons::Seasons<'_> {
+        seasons::Seasons::from(self)
+    }
+
+    #[must_use]
+    pub fn streaks(&self) -> streaks::Streaks<'_> {
+        streaks::Streaks::from(self)
+    }
+
+    #[must_use]
+    pub fn tournaments(&self) -> tournaments::Tournaments<'_> {
+        tournaments::Tournaments::from(self)
+    }
+
     fn update_version(&self) {
         self.version
             .store(rand::random(), std::sync::atomic::Ordering::Relaxed);
@@ -68,11 +137,31 @@ impl Store {
 impl From<sqlx::SqlitePool> for Store {
     fn from(pool: sqlx::SqlitePool) -> Self {
         let version = std::sync::Arc::new(std::sync::atomic::AtomicU32::new(rand::random()));
-        Self { pool, version }
+        Self {
+            pool,
+            version,
+            venues: std::sync::Arc::from([]),
+            live: std::sync::Arc::default(),
+            gate: std::sync::Arc::default(),
+        }
     }
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, sqlx::FromRow)]
 pub struct Id {
-    pub id: crate::types::Id,
+    pub id: types::Id,
+}
+
+const DAY: i64 = 24 * 60 * 60 * 1000;
+
+fn bucket_start(millis: types::Millis, resolution: types::Resolution) -> types::Millis {
+    let millis = i64::from(millis);
+    let start = match resolution {
+        types::Resolution::Game => millis,
+        types::Resolution::Day => millis - millis.rem_euclid(DAY),
+        // The epoch was a Thursday, so shift it by three days for weeks to start on Mondays
+        types::Resolution::Week => millis - (millis + 3 * DAY).rem_euclid(7 * DAY),
+    };
+
+    types::Millis::from(start)
 }
@@ -40,6 +40,87 @@ impl Players<'_> {
         .map_err(Error::from)
     }
 
+    #[tracing::instrument(skip(self))]
+    pub async fn is_admin(&self, id: types::Id) -> Result<bool> {
+        sqlx::query_scalar!(
+            r#"
+            SELECT
+                admin AS "admin: bool"
+            FROM
+                players
+            WHERE
+                id = $1
+            "#,
+            id
+        )
+        .fetch_optional(&self.store.pool)
+        .await
+        .map(|admin| admin.unwrap_or_default())
+        .map_err(Error::from)
+    }
+
+    // Only granted from the command line, so the actor is not recorded
+    #[tracing::instrument(skip(self))]
+    pub async fn set_admin(&self, email: &str, admin: bool) -> Result<types::Id> {
+        let email = email.trim().to_lowercase();
+        if email.is_empty() {
+            return Err(Error::BlankValue("email"));
+        }
+
+        let mut tx = self.store.pool.begin().await?;
+
+        let before = sqlx::query!(
+            r#"
+            SELECT
+                id,
+                admin AS "admin: bool"
+            FROM
+                players
+            WHERE
+                email = $1
+            "#,
+            email
+        )
+        .fetch_optional(tx.as_mut())
+        .await?
+        .ok_or(Error::NotFound)?;
+
+        sqlx::query!(
+            r#"
+            UPDATE
+                players
+            SET
+                admin = $2
+            WHERE
+                id = $1
+            "#,
+            before.id,
+            admin
+        )
+        .execute(tx.as_mut())
+        .await?;
+
+        super::audit::Audit::record(
+            None,
+            if admin {
+                types::AuditAction::GrantAdmin
+            } else {
+                types::AuditAction::RevokeAdmin
+            },
+            (types::Entity::Player, before.id),
+            (
+                super::audit::Audit::snapshot(&before.admin)?,
+                super::audit::Audit::snapshot(&admin)?,
+            ),
+            tx.as_mut(),
+        )
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(before.id)
+    }
+
     #[tracing::instrument(skip(self))]
     pub async fn list(&self) -> Result<Vec<types::Player>> {
         sqlx::query_as!(
@@ -63,7 +144,12 @@ impl Players<'_> {
     }
 
     #[tracing::instrument(skip(self))]
-    pub async fn rename(&self, id: types::Id, name: &str) -> Result<types::Player> {
+    // Returns the player before and after being renamed
+    pub async fn rename(
+        &self,
+        id: types::Id,
+        name: &str,
+    ) -> Result<(types::Player, types::Player)> {
         let name = name.trim();
         if name.is_empty() {
             return Err(Error::BlankValue("name"));
@@ -97,6 +183,25 @@ impl Players<'_> {
             return Err(Error::AlreadyExists);
         }
 
+        let before = sqlx::query_as!(
+            types::Player,
+            r#"
+            SELECT
+                id,
+                name,
+                email,
+                inviter,
+                created_ms AS "created_ms: types::Millis"
+            FROM
+                players
+            WHERE
+                id = $1
+            "#,
+            id
+        )
+        .fetch_one(tx.as_mut())
+        .await?;
+
         let player = sqlx::query_as!(
             types::Player,
             r#"
@@ -120,10 +225,172 @@ impl Players<'_> {
         .await
         .map_err(Error::from)?;
 
+        super::audit::Audit::record(
+            Some(id),
+            types::AuditAction::Rename,
+            (types::Entity::Player, id),
+            (
+                super::audit::Audit::snapshot(&before)?,
+                super::audit::Audit::snapshot(&player)?,
+            ),
+            tx.as_mut(),
+        )
+        .await?;
+
         tx.commit().await?;
 
         self.store.update_version();
 
-        Ok(player)
+        Ok((before, player))
     }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn suggest_opponents(
+        &self,
+        id: types::Id,
+        default_rating: f64,
+    ) -> Result<Vec<types::Suggestion>> {
+        let now = types::Millis::now();
+
+        let mut tx = self.store.pool.begin().await?;
+
+        let resets = super::seasons::Seasons::resets(tx.as_mut()).await?;
+
+        let candidates = sqlx::query!(
+            r#"
+            WITH
+                unified AS (
+                    SELECT
+                        id,
+                        player_one AS player,
+                        rating_one + rating_delta AS rating,
+                        millis
+                    FROM
+                        games
+                    WHERE
+                        NOT deleted
+                    UNION ALL
+                        SELECT
+                            id,
+                            player_two AS player,
+                            rating_two - rating_delta AS rating,
+                            millis
+                        FROM
+                            games
+                        WHERE
+                            NOT deleted
+                ),
+                latest AS (
+                    SELECT
+                        player,
+                        rating,
+                        millis,
+                        ROW_NUMBER() OVER (
+                            PARTITION BY player
+                            ORDER BY millis DESC, id DESC
+                        ) AS position
+                    FROM
+                        unified
+                ),
+                pairs AS (
+                    SELECT
+                        CASE
+                            WHEN player_one = $1 THEN player_two
+                            ELSE player_one
+                        END AS opponent,
+                        MAX(millis) AS millis
+                    FROM
+                        games
+                    WHERE
+                        NOT deleted
+                        AND $1 IN (player_one, player_two)
+                    GROUP BY
+                        opponent
+                )
+            SELECT
+                players.id AS "player!: types::Id",
+                latest.rating AS "rating?: f64",
+                latest.millis AS "millis?: types::Millis",
+                pairs.millis AS "last_played?: types::Millis"
+            FROM
+                players
+            LEFT JOIN
+                latest
+            ON
+                latest.player = players.id
+                AND latest.position = 1
+            LEFT JOIN
+                pairs
+            ON
+                pairs.opponent = players.id
+            "#,
+            id,
+        )
+        .map(|r| {
+            let rating = match (r.rating, r.millis) {
+                (Some(rating), Some(millis)) => {
+                    super::seasons::apply_resets(rating, millis, now, &resets, default_rating)
+                }
+                _ => default_rating,
+            };
+            (r.player, rating, r.last_played)
+        })
+        .fetch_all(tx.as_mut())
+        .await?;
+
+        let challenged = super::games::Games::challenged_today(id, now, None, tx.as_mut()).await?;
+
+        tx.commit().await?;
+
+        let Some(&(_, rating, _)) = candidates.iter().find(|(player, ..)| *player == id) else {
+            return Err(Error::NotFound);
+        };
+
+        let mut suggestions = candidates
+            .into_iter()
+            .filter(|(player, ..)| *player != id)
+            .map(|(player, opponent_rating, last_played)| {
+                let challenge = !challenged.contains(&player);
+                types::Suggestion {
+                    player,
+                    rating: opponent_rating,
+                    last_played,
+                    challenge,
+                    score: suggestion_score(
+                        (rating - opponent_rating).abs(),
+                        last_played,
+                        now,
+                        challenge,
+                    ),
+                }
+            })
+            .collect::<Vec<_>>();
+
+        suggestions.sort_by(|lhs, rhs| rhs.score.total_cmp(&lhs.score));
+
+        Ok(suggestions)
+    }
+}
+
+// Ratings this far apart halve the closeness score
+const RATING_SCALE: f64 = 200.0;
+// Pairs that have not played for this long get the full recency score
+const RECENCY_DAYS: i64 = 14;
+
+fn suggestion_score(
+    rating_gap: f64,
+    last_played: Option<types::Millis>,
+    now: types::Millis,
+    challenge: bool,
+) -> f64 {
+    let closeness = 1.0 / (1.0 + rating_gap / RATING_SCALE);
+
+    // allow(clippy::cast_precision_loss): The elapsed time is clamped to a handful of days
+    #[allow(clippy::cast_precision_loss)]
+    let recency = last_played.map_or(1.0, |last| {
+        let elapsed = (i64::from(now) - i64::from(last)).clamp(0, RECENCY_DAYS * super::DAY);
+        elapsed as f64 / (RECENCY_DAYS * super::DAY) as f64
+    });
+
+    closeness + recency + if challenge { 0.5 } else { 0.0 }
 }
@@ -0,0 +1,554 @@
+use super::super::error::Error;
+use crate::types;
+
+type Result<T = ()> = std::result::Result<T, Error>;
+
+pub struct Seasons<'a> {
+    store: &'a super::Store,
+}
+
+impl<'a> From<&'a super::Store> for Seasons<'a> {
+    fn from(store: &'a super::Store) -> Self {
+        Self { store }
+    }
+}
+
+impl Seasons<'_> {
+    #[tracing::instrument(skip(self))]
+    pub async fn list(&self) -> Result<Vec<types::Season>> {
+        sqlx::query_as!(
+            types::Season,
+            r#"
+            SELECT
+                id,
+                name,
+                start_ms AS "start_ms: types::Millis",
+                end_ms AS "end_ms: types::Millis",
+                reset,
+                created_ms AS "created_ms: types::Millis"
+            FROM
+                seasons
+            ORDER BY
+                start_ms ASC
+            "#
+        )
+        .fetch_all(&self.store.pool)
+        .await
+        .map_err(Error::from)
+    }
+
+    #[tracing::instrument(skip(self, rating_updater))]
+    pub async fn create<F>(
+        &self,
+        actor: types::Id,
+        name: &str,
+        (start, end): (types::Millis, types::Millis),
+        reset: f64,
+        default_rating: f64,
+        rating_updater: F,
+    ) -> Result<(types::Season, Vec<types::Game>, types::Derived)>
+    where
+        F: Copy + Fn(f64, f64, bool, bool) -> f64,
+    {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(Error::BlankValue("name"));
+        }
+
+        if end <= start {
+            return Err(Error::InvalidValue("Seasons must end after they start"));
+        }
+
+        if !(0.0..=1.0).contains(&reset) {
+            return Err(Error::InvalidValue("Season resets must be between 0 and 1"));
+        }
+
+        let mut tx = self.store.pool.begin().await?;
+
+        if sqlx::query_as!(
+            super::Id,
+            r#"
+            SELECT
+                id
+            FROM
+                seasons
+            WHERE
+                start_ms < $2
+                AND end_ms > $1
+            "#,
+            start,
+            end,
+        )
+        .fetch_optional(tx.as_mut())
+        .await?
+        .is_some()
+        {
+            return Err(Error::InvalidValue("Seasons cannot overlap"));
+        }
+
+        let season = sqlx::query_as!(
+            types::Season,
+            r#"
+            INSERT INTO seasons (
+                name,
+                start_ms,
+                end_ms,
+                reset
+            ) VALUES (
+                $1,
+                $2,
+                $3,
+                $4
+            ) RETURNING
+                id,
+                name,
+                start_ms AS "start_ms: types::Millis",
+                end_ms AS "end_ms: types::Millis",
+                reset,
+                created_ms AS "created_ms: types::Millis"
+            "#,
+            name,
+            start,
+            end,
+            reset,
+        )
+        .fetch_one(tx.as_mut())
+        .await?;
+
+        let (updates, derived) = super::games::Games::execute_refresh(
+            Some(season.start_ms),
+            default_rating,
+            rating_updater,
+            &mut tx,
+        )
+        .await?;
+
+        super::audit::Audit::record(
+            Some(actor),
+            types::AuditAction::CreateSeason,
+            (types::Entity::Season, season.id),
+            (None, super::audit::Audit::snapshot(&season)?),
+            tx.as_mut(),
+        )
+        .await?;
+
+        tx.commit().await?;
+
+        self.store.update_version();
+
+        Ok((season, updates, derived))
+    }
+
+    #[tracing::instrument(skip(self, rating_updater))]
+    pub async fn delete<F>(
+        &self,
+        actor: types::Id,
+        id: types::Id,
+        default_rating: f64,
+        rating_updater: F,
+    ) -> Result<(types::Season, Vec<types::Game>, types::Derived)>
+    where
+        F: Copy + Fn(f64, f64, bool, bool) -> f64,
+    {
+        let mut tx = self.store.pool.begin().await?;
+
+        let season = sqlx::query_as!(
+            types::Season,
+            r#"
+            DELETE FROM
+                seasons
+            WHERE
+                id = $1
+            RETURNING
+                id,
+                name,
+                start_ms AS "start_ms: types::Millis",
+                end_ms AS "end_ms: types::Millis",
+                reset,
+                created_ms AS "created_ms: types::Millis"
+            "#,
+            id,
+        )
+        .fetch_one(tx.as_mut())
+        .await?;
+
+        let (updates, derived) = super::games::Games::execute_refresh(
+            Some(season.start_ms),
+            default_rating,
+            rating_updater,
+            &mut tx,
+        )
+        .await?;
+
+        super::audit::Audit::record(
+            Some(actor),
+            types::AuditAction::DeleteSeason,
+            (types::Entity::Season, season.id),
+            (super::audit::Audit::snapshot(&season)?, None),
+            tx.as_mut(),
+        )
+        .await?;
+
+        tx.commit().await?;
+
+        self.store.update_version();
+
+        Ok((season, updates, derived))
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn games(&self, id: types::Id) -> Result<Vec<types::Game>> {
+        let mut tx = self.store.pool.begin().await?;
+
+        let season = Self::get(id, tx.as_mut()).await?;
+
+        let games = sqlx::query_as!(
+            types::Game,
+            r#"
+            SELECT
+                id,
+                player_one,
+                player_two,
+                score_one,
+                score_two,
+                rating_one,
+                rating_two,
+                rating_delta,
+                challenge,
+                deleted,
+                millis AS "millis: types::Millis",
+                created_ms AS "created_ms: types::Millis",
+                comment,
+                venue,
+                first_server
+            FROM
+                games
+            WHERE
+                millis >= $1
+                AND millis < $2
+            ORDER BY
+                millis ASC
+            "#,
+            season.start_ms,
+            season.end_ms,
+        )
+        .fetch_all(tx.as_mut())
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(games)
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn standings(&self, id: types::Id, min_games: i64) -> Result<Vec<types::Standing>> {
+        let mut tx = self.store.pool.begin().await?;
+
+        let season = Self::get(id, tx.as_mut()).await?;
+
+        // Ended seasons are archived by the daily task, until then they are computed on every read
+        let archived = if season.end_ms > types::Millis::now() {
+            Vec::new()
+        } else {
+            Self::archived(&season, min_games, tx.as_mut()).await?
+        };
+
+        let ranking = if archived.is_empty() {
+            Self::compute(&season, min_games, tx.as_mut()).await?
+        } else {
+            archived
+        };
+
+        tx.commit().await?;
+
+        Ok(ranking
+            .into_iter()
+            .map(|r| types::Standing {
+                player: r.player,
+                rank: r.rank,
+                rating: r.rating,
+                games: r.games,
+                previous_rank: None,
+                previous_rating: None,
+            })
+            .collect())
+    }
+
+    // Archives the standings of the seasons that ended and are not archived yet, including the
+    // ones invalidated by changes to their games
+    #[tracing::instrument(skip(self))]
+    pub async fn archive(&self, min_games: i64) -> Result {
+        let mut tx = self.store.pool.begin().await?;
+
+        let now = types::Millis::now();
+        let seasons = sqlx::query_as!(
+            types::Season,
+            r#"
+            SELECT
+                id,
+                name,
+                start_ms AS "start_ms: types::Millis",
+                end_ms AS "end_ms: types::Millis",
+                reset,
+                created_ms AS "created_ms: types::Millis"
+            FROM
+                seasons
+            WHERE
+                end_ms <= $1
+                AND NOT EXISTS (
+                    SELECT
+                        1
+                    FROM
+                        standings
+                    WHERE
+                        standings.season = seasons.id
+                        AND standings.min_games = $2
+                )
+            "#,
+            now,
+            min_games,
+        )
+        .fetch_all(tx.as_mut())
+        .await?;
+
+        for season in seasons {
+            let ranking = Self::compute(&season, min_games, tx.as_mut()).await?;
+            if let Some(mut query) = build_archive_query(season.id, min_games, &ranking) {
+                query.build().persistent(false).execute(tx.as_mut()).await?;
+            }
+        }
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+}
+
+impl Seasons<'_> {
+    pub(super) async fn resets<'c, 'e, E>(executor: E) -> Result<Vec<Reset>>
+    where
+        'c: 'e,
+        E: 'e + sqlx::Executor<'c, Database = sqlx::Sqlite>,
+    {
+        sqlx::query_as!(
+            Reset,
+            r#"
+            SELECT
+                start_ms AS "start: types::Millis",
+                reset
+            FROM
+                seasons
+            ORDER BY
+                start_ms ASC
+            "#
+        )
+        .fetch_all(executor)
+        .await
+        .map_err(Error::from)
+    }
+
+    pub(super) async fn invalidate(
+        from: Option<types::Millis>,
+        tx: &mut sqlx::Transaction<'static, sqlx::Sqlite>,
+    ) -> Result {
+        sqlx::query!(
+            r#"
+            DELETE FROM
+                standings
+            WHERE
+                $1 IS NULL
+                OR season IN (
+                    SELECT
+                        id
+                    FROM
+                        seasons
+                    WHERE
+                        end_ms > $1
+                )
+            "#,
+            from,
+        )
+        .execute(tx.as_mut())
+        .await?;
+
+        Ok(())
+    }
+
+    pub(super) async fn get<'c, 'e, E>(id: types::Id, executor: E) -> Result<types::Season>
+    where
+        'c: 'e,
+        E: 'e + sqlx::Executor<'c, Database = sqlx::Sqlite>,
+    {
+        sqlx::query_as!(
+            types::Season,
+            r#"
+            SELECT
+                id,
+                name,
+                start_ms AS "start_ms: types::Millis",
+                end_ms AS "end_ms: types::Millis",
+                reset,
+                created_ms AS "created_ms: types::Millis"
+            FROM
+                seasons
+            WHERE
+                id = $1
+            "#,
+            id,
+        )
+        .fetch_one(executor)
+        .await
+        .map_err(Error::from)
+    }
+
+    async fn archived<'c, 'e, E>(
+        season: &types::Season,
+        min_games: i64,
+        executor: E,
+    ) -> Result<Vec<super::leaderboard::Ranking>>
+    where
+        'c: 'e,
+        E: 'e + sqlx::Executor<'c, Database = sqlx::Sqlite>,
+    {
+        sqlx::query_as!(
+            super::leaderboard::Ranking,
+            r#"
+            SELECT
+                player,
+                rank,
+                rating,
+                games
+            FROM
+                standings
+            WHERE
+                season = $1
+                AND min_games = $2
+            ORDER BY
+                rank IS NULL,
+                rank ASC,
+                rating DESC
+            "#,
+            season.id,
+            min_games,
+        )
+        .fetch_all(executor)
+        .await
+        .map_err(Error::from)
+    }
+
+    async fn compute<'c, 'e, E>(
+        season: &types::Season,
+        min_games: i64,
+        executor: E,
+    ) -> Result<Vec<super::leaderboard::Ranking>>
+    where
+        'c: 'e,
+        E: 'e + sqlx::Executor<'c, Database = sqlx::Sqlite>,
+    {
+        let mut ranking = sqlx::query_as!(
+            super::leaderboard::Ranking,
+            r#"
+            WITH
+                unified AS (
+                    SELECT
+                        id,
+                        player_one AS player,
+                        rating_one + rating_delta AS rating,
+                        millis
+                    FROM
+                        games
+                    WHERE
+                        millis >= $1
+                        AND millis < $2
+                        AND NOT deleted
+                    UNION ALL
+                        SELECT
+                            id,
+                            player_two AS player,
+                            rating_two - rating_delta AS rating,
+                            millis
+                        FROM
+                            games
+                        WHERE
+                            millis >= $1
+                            AND millis < $2
+                            AND NOT deleted
+                ),
+                latest AS (
+                    SELECT
+                        player,
+                        rating,
+                        ROW_NUMBER() OVER (
+                            PARTITION BY player
+                            ORDER BY millis DESC, id DESC
+                        ) AS position,
+                        COUNT(*) OVER (PARTITION BY player) AS games
+                    FROM
+                        unified
+                )
+            SELECT
+                player AS "player!: types::Id",
+                NULL AS "rank?: i64",
+                rating AS "rating!: f64",
+                games AS "games!: i64"
+            FROM
+                latest
+            WHERE
+                position = 1
+            "#,
+            season.start_ms,
+            season.end_ms,
+        )
+        .fetch_all(executor)
+        .await?;
+
+        super::leaderboard::rank(&mut ranking, min_games);
+
+        Ok(ranking)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct Reset {
+    start: types::Millis,
+    reset: f64,
+}
+
+pub(super) fn apply_resets(
+    rating: f64,
+    since: types::Millis,
+    until: types::Millis,
+    resets: &[Reset],
+    default_rating: f64,
+) -> f64 {
+    resets
+        .iter()
+        .filter(|r| r.start > since && r.start <= until)
+        .fold(rating, |rating, r| {
+            rating + (default_rating - rating) * r.reset
+        })
+}
+
+fn build_archive_query(
+    season: types::Id,
+    min_games: i64,
+    ranking: &[super::leaderboard::Ranking],
+) -> Option<sqlx::QueryBuilder<'static, sqlx::Sqlite>> {
+    if ranking.is_empty() {
+        return None;
+    }
+
+    let mut builder = sqlx::QueryBuilder::new(
+        "INSERT OR REPLACE INTO standings (season, min_games, player, rank, rating, games) ",
+    );
+    builder.push_values(ranking, |mut row, ranking| {
+        row.push_bind(season)
+            .push_bind(min_games)
+            .push_bind(ranking.player)
+            .push_bind(ranking.rank)
+            .push_bind(ranking.rating)
+            .push_bind(ranking.games);
+    });
+
+    Some(builder)
+}
@@ -0,0 +1,276 @@
+use super::super::error::Error;
+use crate::types;
+
+type Result<T = ()> = std::result::Result<T, Error>;
+
+pub struct Streaks<'a> {
+    store: &'a super::Store,
+}
+
+impl<'a> From<&'a super::Store> for Streaks<'a> {
+    fn from(store: &'a super::Store) -> Self {
+        Self { store }
+    }
+}
+
+impl Streaks<'_> {
+    // Streaks within a season are only over its games, so they are replayed on every read
+    #[tracing::instrument(skip(self))]
+    pub async fn list(&self, season: Option<types::Id>) -> Result<Vec<types::Streak>> {
+        if let Some(season) = season {
+            let mut games = self.store.seasons().games(season).await?;
+            games.retain(|g| !g.deleted);
+            games.sort_unstable_by_key(|g| (g.millis, g.id));
+
+            let mut streaks = std::collections::BTreeMap::new();
+            for game in &games {
+                play(&mut streaks, game);
+            }
+
+            return Ok(streaks.into_values().collect());
+        }
+
+        sqlx::query_as!(
+            types::Streak,
+            r#"
+            SELECT
+                player,
+                current,
+                longest_win,
+                longest_loss
+            FROM
+                streaks
+            ORDER BY
+                player ASC
+            "#
+        )
+        .fetch_all(&self.store.pool)
+        .await
+        .map_err(Error::from)
+    }
+
+    // Game writes refresh the streaks along with them, so rebuilding them is only needed to check
+    // that they hold
+    #[cfg(test)]
+    #[tracing::instrument(skip(self))]
+    pub async fn refresh(
+        &self,
+        from: Option<types::Millis>,
+    ) -> Result<Vec<(types::Streak, types::Streak)>> {
+        let mut tx = self.store.pool.begin().await?;
+        let changes = Self::execute_refresh(from, &mut tx).await?;
+        tx.commit().await?;
+
+        if !changes.is_empty() {
+            self.store.update_version();
+        }
+
+        Ok(changes)
+    }
+}
+
+impl Streaks<'_> {
+    // Streaks are replayed from `from`, which is the earliest point in the history that changed,
+    // on top of the streaks before it. Returns the previous and current streaks of the players
+    // that changed
+    pub(super) async fn execute_refresh(
+        from: Option<types::Millis>,
+        tx: &mut sqlx::Transaction<'static, sqlx::Sqlite>,
+    ) -> Result<Vec<(types::Streak, types::Streak)>> {
+        let mut previous = sqlx::query_as!(
+            types::Streak,
+            r#"
+            SELECT
+                player,
+                current,
+                longest_win,
+                longest_loss
+            FROM
+                streaks
+            "#
+        )
+        .fetch_all(tx.as_mut())
+        .await?
+        .into_iter()
+        .map(|s| (s.player, s))
+        .collect::<std::collections::HashMap<_, _>>();
+
+        let mut streaks = match from {
+            Some(from) => Self::before(from, tx.as_mut()).await?,
+            None => std::collections::BTreeMap::new(),
+        };
+
+        // Players whose games are all gone are back to no streak at all
+        for &player in previous.keys() {
+            streaks.entry(player).or_insert_with(|| empty(player));
+        }
+
+        for game in &super::games::Games::list_since(from, tx.as_mut()).await? {
+            play(&mut streaks, game);
+        }
+
+        let mut changes = Vec::new();
+        for (player, streak) in streaks {
+            let old = previous.remove(&player).unwrap_or_else(|| empty(player));
+            if old == streak {
+                continue;
+            }
+
+            sqlx::query!(
+                r#"
+                INSERT INTO streaks (
+                    player,
+                    current,
+                    longest_win,
+                    longest_loss
+                ) VALUES (
+                    $1,
+                    $2,
+                    $3,
+                    $4
+                ) ON CONFLICT(player) DO UPDATE SET
+                    current = excluded.current,
+                    longest_win = excluded.longest_win,
+                    longest_loss = excluded.longest_loss
+                "#,
+                streak.player,
+                streak.current,
+                streak.longest_win,
+                streak.longest_loss,
+            )
+            .execute(tx.as_mut())
+            .await?;
+
+            changes.push((old, streak));
+        }
+
+        Ok(changes)
+    }
+
+    // Each run of equal results is an island, so the streaks are read from the lengths of the
+    // islands of each player
+    pub(super) async fn before<'c, 'e, E>(
+        before: types::Millis,
+        executor: E,
+    ) -> Result<std::collections::BTreeMap<types::Id, types::Streak>>
+    where
+        'c: 'e,
+        E: 'e + sqlx::Executor<'c, Database = sqlx::Sqlite>,
+    {
+        sqlx::query_as!(
+            types::Streak,
+            r#"
+            WITH
+                unified AS (
+                    SELECT
+                        id,
+                        millis,
+                        player_one AS player,
+                        score_one > score_two AS won
+                    FROM
+                        games
+                    WHERE
+                        millis < $1
+                        AND NOT deleted
+                    UNION ALL
+                        SELECT
+                            id,
+                            millis,
+                            player_two AS player,
+                            score_two > score_one AS won
+                        FROM
+                            games
+                        WHERE
+                            millis < $1
+                            AND NOT deleted
+                ),
+                islands AS (
+                    SELECT
+                        player,
+                        won,
+                        ROW_NUMBER() OVER (
+                            PARTITION BY player
+                            ORDER BY millis DESC, id DESC
+                        ) AS position,
+                        ROW_NUMBER() OVER (
+                            PARTITION BY player
+                            ORDER BY millis ASC, id ASC
+                        ) - ROW_NUMBER() OVER (
+                            PARTITION BY player, won
+                            ORDER BY millis ASC, id ASC
+                        ) AS island
+                    FROM
+                        unified
+                ),
+                runs AS (
+                    SELECT
+                        player,
+                        won,
+                        COUNT(*) AS length,
+                        MIN(position) AS position
+                    FROM
+                        islands
+                    GROUP BY
+                        player,
+                        won,
+                        island
+                )
+            SELECT
+                player AS "player!: types::Id",
+                SUM(
+                    CASE
+                        WHEN position > 1 THEN 0
+                        WHEN won THEN length
+                        ELSE -length
+                    END
+                ) AS "current!: i64",
+                MAX(CASE WHEN won THEN length ELSE 0 END) AS "longest_win!: i64",
+                MAX(CASE WHEN won THEN 0 ELSE length END) AS "longest_loss!: i64"
+            FROM
+                runs
+            GROUP BY
+                player
+            "#,
+            before,
+        )
+        .fetch_all(executor)
+        .await
+        .map(|streaks| streaks.into_iter().map(|s| (s.player, s)).collect())
+        .map_err(Error::from)
+    }
+}
+
+fn empty(player: types::Id) -> types::Streak {
+    types::Streak {
+        player,
+        current: 0,
+        longest_win: 0,
+        longest_loss: 0,
+    }
+}
+
+fn play(streaks: &mut std::collections::BTreeMap<types::Id, types::Streak>, game: &types::Game) {
+    let won = game.score_one > game.score_two;
+    extend(
+        streaks
+            .entry(game.player_one)
+            .or_insert_with(|| empty(game.player_one)),
+        won,
+    );
+    extend(
+        streaks
+            .entry(game.player_two)
+            .or_insert_with(|| empty(game.player_two)),
+        !won,
+    );
+}
+
+fn extend(streak: &mut types::Streak, won: bool) {
+    if won {
+        streak.current = streak.current.max(0) + 1;
+        streak.longest_win = streak.longest_win.max(streak.current);
+    } else {
+        streak.current = streak.current.min(0) - 1;
+        streak.longest_loss = streak.longest_loss.max(-streak.current);
+    }
+}
@@ -172,7 +172,10 @@ mod constraints {
                 challenge,
                 deleted,
                 millis AS "millis: types::Millis",
-                created_ms AS "created_ms: types::Millis"
+                created_ms AS "created_ms: types::Millis",
+                comment,
+                venue,
+                first_server
             "#,
             player.id
         )
@@ -233,7 +236,10 @@ mod constraints {
                         challenge,
                         deleted,
                         millis AS "millis: types::Millis",
-                        created_ms AS "created_ms: types::Millis"
+                        created_ms AS "created_ms: types::Millis",
+                        comment,
+                        venue,
+                        first_server
                     "#,
                     player_one.id,
                     player_two.id,
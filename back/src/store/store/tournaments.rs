@@ -0,0 +1,796 @@
+use super::super::error::Error;
+use crate::types;
+
+type Result<T = ()> = std::result::Result<T, Error>;
+
+pub struct Tournaments<'a> {
+    store: &'a super::Store,
+}
+
+impl<'a> From<&'a super::Store> for Tournaments<'a> {
+    fn from(store: &'a super::Store) -> Self {
+        Self { store }
+    }
+}
+
+impl Tournaments<'_> {
+    #[tracing::instrument(skip(self))]
+    pub async fn list(&self) -> Result<Vec<types::Tournament>> {
+        sqlx::query_as!(
+            types::Tournament,
+            r#"
+            SELECT
+                id,
+                name,
+                format AS "format: types::Format",
+                groups,
+                advancing,
+                created_ms AS "created_ms: types::Millis"
+            FROM
+                tournaments
+            ORDER BY
+                created_ms ASC
+            "#
+        )
+        .fetch_all(&self.store.pool)
+        .await
+        .map_err(Error::from)
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn create(
+        &self,
+        actor: types::Id,
+        name: &str,
+        format: types::Format,
+        players: &[types::Id],
+        (groups, advancing): (i64, i64),
+    ) -> Result<(types::Tournament, Vec<types::Fixture>)> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(Error::BlankValue("name"));
+        }
+
+        let (groups, advancing) = match format {
+            types::Format::RoundRobin | types::Format::SingleElimination => (1, 1),
+            types::Format::GroupKnockout => (groups, advancing),
+        };
+
+        let drafts = draft(format, players, groups, advancing)?;
+
+        let mut tx = self.store.pool.begin().await?;
+
+        let tournament = sqlx::query_as!(
+            types::Tournament,
+            r#"
+            INSERT INTO tournaments (
+                name,
+                format,
+                groups,
+                advancing
+            ) VALUES (
+                $1,
+                $2,
+                $3,
+                $4
+            ) RETURNING
+                id,
+                name,
+                format AS "format: types::Format",
+                groups,
+                advancing,
+                created_ms AS "created_ms: types::Millis"
+            "#,
+            name,
+            format,
+            groups,
+            advancing,
+        )
+        .fetch_one(tx.as_mut())
+        .await?;
+
+        let mut builder =
+            sqlx::QueryBuilder::new("INSERT INTO participants (tournament, player, seed) ");
+        builder.push_values((0_i64..).zip(players), |mut row, (seed, player)| {
+            row.push_bind(tournament.id)
+                .push_bind(*player)
+                .push_bind(seed);
+        });
+        builder
+            .build()
+            .persistent(false)
+            .execute(tx.as_mut())
+            .await?;
+
+        let mut builder = sqlx::QueryBuilder::new(
+            "INSERT INTO fixtures (tournament, group_index, round, position, player_one, player_two) ",
+        );
+        builder.push_values(drafts, |mut row, draft| {
+            row.push_bind(tournament.id)
+                .push_bind(draft.group_index)
+                .push_bind(draft.round)
+                .push_bind(draft.position)
+                .push_bind(draft.player_one)
+                .push_bind(draft.player_two);
+        });
+        builder.push(" RETURNING id, tournament, group_index, round, position, player_one, player_two, game, winner");
+        let mut fixtures = builder
+            .build_query_as::<types::Fixture>()
+            .persistent(false)
+            .fetch_all(tx.as_mut())
+            .await?;
+
+        let changed = resolve(&tournament, players, &mut fixtures);
+        Self::persist(&fixtures, &changed, &mut tx).await?;
+
+        super::audit::Audit::record(
+            Some(actor),
+            types::AuditAction::CreateTournament,
+            (types::Entity::Tournament, tournament.id),
+            (None, super::audit::Audit::snapshot(&tournament)?),
+            tx.as_mut(),
+        )
+        .await?;
+
+        tx.commit().await?;
+
+        self.store.update_version();
+
+        Ok((tournament, fixtures))
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn delete(&self, actor: types::Id, id: types::Id) -> Result<types::Tournament> {
+        let mut tx = self.store.pool.begin().await?;
+
+        let tournament = sqlx::query_as!(
+            types::Tournament,
+            r#"
+            DELETE FROM
+                tournaments
+            WHERE
+                id = $1
+            RETURNING
+                id,
+                name,
+                format AS "format: types::Format",
+                groups,
+                advancing,
+                created_ms AS "created_ms: types::Millis"
+            "#,
+            id,
+        )
+        .fetch_one(tx.as_mut())
+        .await?;
+
+        super::audit::Audit::record(
+            Some(actor),
+            types::AuditAction::DeleteTournament,
+            (types::Entity::Tournament, tournament.id),
+            (super::audit::Audit::snapshot(&tournament)?, None),
+            tx.as_mut(),
+        )
+        .await?;
+
+        tx.commit().await?;
+
+        self.store.update_version();
+
+        Ok(tournament)
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn fixtures(&self, id: types::Id) -> Result<Vec<types::Fixture>> {
+        let mut tx = self.store.pool.begin().await?;
+
+        Self::get(id, tx.as_mut()).await?;
+        let fixtures = Self::list_fixtures(id, tx.as_mut()).await?;
+
+        tx.commit().await?;
+
+        Ok(fixtures)
+    }
+
+    /// Tournament games are regular games, so they are rated like any other and linking one to a
+    /// fixture only decides the fixture, never the ratings
+    #[tracing::instrument(skip(self))]
+    pub async fn link(
+        &self,
+        actor: types::Id,
+        fixture: types::Id,
+        game: types::Id,
+    ) -> Result<(types::Tournament, Vec<types::Fixture>)> {
+        let mut tx = self.store.pool.begin().await?;
+
+        let linked = sqlx::query_as!(
+            types::Fixture,
+            r#"
+            SELECT
+                id,
+                tournament,
+                group_index,
+                round,
+                position,
+                player_one,
+                player_two,
+                game,
+                winner
+            FROM
+                fixtures
+            WHERE
+                id = $1
+            "#,
+            fixture,
+        )
+        .fetch_one(tx.as_mut())
+        .await?;
+
+        let (Some(player_one), Some(player_two)) = (linked.player_one, linked.player_two) else {
+            return Err(Error::InvalidValue(
+                "Fixture is still waiting for its players",
+            ));
+        };
+
+        if linked.winner.is_some() {
+            return Err(Error::InvalidValue("Fixture has already been decided"));
+        }
+
+        let tournament = Self::get(linked.tournament, tx.as_mut()).await?;
+
+        let outcome = sqlx::query_as!(
+            Outcome,
+            r#"
+            SELECT
+                player_one,
+                player_two,
+                score_one,
+                score_two,
+                millis AS "millis: types::Millis",
+                deleted
+            FROM
+                games
+            WHERE
+                id = $1
+            "#,
+            game,
+        )
+        .fetch_one(tx.as_mut())
+        .await?;
+
+        if outcome.deleted {
+            return Err(Error::InvalidValue("Deleted games cannot be linked"));
+        }
+
+        if !outcome.is_between(player_one, player_two) {
+            return Err(Error::InvalidValue("Game players do not match the fixture"));
+        }
+
+        if outcome.millis < tournament.created_ms {
+            return Err(Error::InvalidValue(
+                "Games played before the tournament cannot be linked",
+            ));
+        }
+
+        let winner = outcome.winner();
+        sqlx::query!(
+            r#"
+            UPDATE
+                fixtures
+            SET
+                game = $2,
+                winner = $3
+            WHERE
+                id = $1
+            "#,
+            fixture,
+            game,
+            winner,
+        )
+        .execute(tx.as_mut())
+        .await?;
+
+        let seeds = sqlx::query_scalar!(
+            r#"
+            SELECT
+                player
+            FROM
+                participants
+            WHERE
+                tournament = $1
+            ORDER BY
+                seed ASC
+            "#,
+            tournament.id,
+        )
+        .fetch_all(tx.as_mut())
+        .await?;
+
+        let mut fixtures = Self::list_fixtures(tournament.id, tx.as_mut()).await?;
+        let mut changed = resolve(&tournament, &seeds, &mut fixtures);
+        Self::persist(&fixtures, &changed, &mut tx).await?;
+
+        super::audit::Audit::record(
+            Some(actor),
+            types::AuditAction::LinkFixture,
+            (types::Entity::Fixture, linked.id),
+            (
+                super::audit::Audit::snapshot(&linked)?,
+                super::audit::Audit::snapshot(&types::Fixture {
+                    game: Some(game),
+                    winner: Some(winner),
+                    ..linked
+                })?,
+            ),
+            tx.as_mut(),
+        )
+        .await?;
+
+        tx.commit().await?;
+
+        self.store.update_version();
+
+        changed.insert(fixture);
+        fixtures.retain(|f| changed.contains(&f.id));
+
+        Ok((tournament, fixtures))
+    }
+}
+
+impl Tournaments<'_> {
+    pub(super) async fn validate_update<'c, 'e, E>(game: &types::Game, executor: E) -> Result
+    where
+        'c: 'e,
+        E: 'e + sqlx::Executor<'c, Database = sqlx::Sqlite>,
+    {
+        let fixture = sqlx::query!(
+            r#"
+            SELECT
+                fixtures.player_one,
+                fixtures.player_two,
+                fixtures.winner,
+                tournaments.created_ms AS "created_ms: types::Millis"
+            FROM
+                fixtures
+            INNER JOIN
+                tournaments
+            ON
+                tournaments.id = fixtures.tournament
+            WHERE
+                fixtures.game = $1
+            "#,
+            game.id,
+        )
+        .fetch_optional(executor)
+        .await?;
+
+        let Some(fixture) = fixture else {
+            return Ok(());
+        };
+
+        let outcome = Outcome {
+            player_one: game.player_one,
+            player_two: game.player_two,
+            score_one: game.score_one,
+            score_two: game.score_two,
+            millis: game.millis,
+            deleted: game.deleted,
+        };
+
+        if outcome.millis < fixture.created_ms {
+            return Err(Error::InvalidValue(
+                "Games linked to a tournament cannot be moved before it",
+            ));
+        }
+
+        match (fixture.player_one, fixture.player_two) {
+            (Some(player_one), Some(player_two))
+                if !outcome.deleted
+                    && outcome.is_between(player_one, player_two)
+                    && Some(outcome.winner()) == fixture.winner =>
+            {
+                Ok(())
+            }
+            _ => Err(Error::InvalidValue(
+                "Games linked to a tournament cannot change their outcome",
+            )),
+        }
+    }
+
+    async fn get<'c, 'e, E>(id: types::Id, executor: E) -> Result<types::Tournament>
+    where
+        'c: 'e,
+        E: 'e + sqlx::Executor<'c, Database = sqlx::Sqlite>,
+    {
+        sqlx::query_as!(
+            types::Tournament,
+            r#"
+            SELECT
+                id,
+                name,
+                format AS "format: types::Format",
+                groups,
+                advancing,
+                created_ms AS "created_ms: types::Millis"
+            FROM
+                tournaments
+            WHERE
+                id = $1
+            "#,
+            id,
+        )
+        .fetch_one(executor)
+        .await
+        .map_err(Error::from)
+    }
+
+    async fn list_fixtures<'c, 'e, E>(id: types::Id, executor: E) -> Result<Vec<types::Fixture>>
+    where
+        'c: 'e,
+        E: 'e + sqlx::Executor<'c, Database = sqlx::Sqlite>,
+    {
+        sqlx::query_as!(
+            types::Fixture,
+            r#"
+            SELECT
+                id,
+                tournament,
+                group_index,
+                round,
+                position,
+                player_one,
+                player_two,
+                game,
+                winner
+            FROM
+                fixtures
+            WHERE
+                tournament = $1
+            ORDER BY
+                id ASC
+            "#,
+            id,
+        )
+        .fetch_all(executor)
+        .await
+        .map_err(Error::from)
+    }
+
+    async fn persist(
+        fixtures: &[types::Fixture],
+        changed: &std::collections::BTreeSet<types::Id>,
+        tx: &mut sqlx::Transaction<'static, sqlx::Sqlite>,
+    ) -> Result {
+        for fixture in fixtures.iter().filter(|f| changed.contains(&f.id)) {
+            sqlx::query!(
+                r#"
+                UPDATE
+                    fixtures
+                SET
+                    player_one = $2,
+                    player_two = $3,
+                    winner = $4
+                WHERE
+                    id = $1
+                "#,
+                fixture.id,
+                fixture.player_one,
+                fixture.player_two,
+                fixture.winner,
+            )
+            .execute(tx.as_mut())
+            .await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+struct Outcome {
+    player_one: types::Id,
+    player_two: types::Id,
+    score_one: i64,
+    score_two: i64,
+    millis: types::Millis,
+    deleted: bool,
+}
+
+impl Outcome {
+    fn is_between(&self, player_one: types::Id, player_two: types::Id) -> bool {
+        (self.player_one == player_one && self.player_two == player_two)
+            || (self.player_one == player_two && self.player_two == player_one)
+    }
+
+    fn winner(&self) -> types::Id {
+        if self.score_one > self.score_two {
+            self.player_one
+        } else {
+            self.player_two
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Draft {
+    group_index: Option<i64>,
+    round: i64,
+    position: i64,
+    player_one: Option<types::Id>,
+    player_two: Option<types::Id>,
+}
+
+fn draft(
+    format: types::Format,
+    players: &[types::Id],
+    groups: i64,
+    advancing: i64,
+) -> Result<Vec<Draft>> {
+    if players.len() < 2 {
+        return Err(Error::InvalidValue("Tournaments need at least two players"));
+    }
+
+    if players
+        .iter()
+        .collect::<std::collections::HashSet<_>>()
+        .len()
+        != players.len()
+    {
+        return Err(Error::InvalidValue(
+            "Players cannot join a tournament more than once",
+        ));
+    }
+
+    match format {
+        types::Format::RoundRobin => Ok(draft_round_robin(players, 0)),
+        types::Format::SingleElimination => Ok(draft_knockout(
+            &players.iter().copied().map(Some).collect::<Vec<_>>(),
+        )),
+        types::Format::GroupKnockout => {
+            let (Ok(group_count), Ok(advancing_count)) =
+                (usize::try_from(groups), usize::try_from(advancing))
+            else {
+                return Err(Error::InvalidValue(
+                    "Groups and advancing players must be positive",
+                ));
+            };
+
+            if group_count < 2 {
+                return Err(Error::InvalidValue("Group stages need at least two groups"));
+            }
+
+            if advancing_count < 1 {
+                return Err(Error::InvalidValue(
+                    "At least one player must advance from each group",
+                ));
+            }
+
+            if players.len() < group_count.saturating_mul(advancing_count + 1) {
+                return Err(Error::InvalidValue(
+                    "Groups need more players than advance from them",
+                ));
+            }
+
+            let mut drafts = (0..groups)
+                .flat_map(|group| {
+                    let members = (0..)
+                        .zip(players)
+                        .filter(|(seed, _)| group_of(*seed, groups) == group)
+                        .map(|(_, player)| *player)
+                        .collect::<Vec<_>>();
+                    draft_round_robin(&members, group)
+                })
+                .collect::<Vec<_>>();
+            drafts.extend(draft_knockout(&vec![None; group_count * advancing_count]));
+
+            Ok(drafts)
+        }
+    }
+}
+
+fn draft_round_robin(players: &[types::Id], group: i64) -> Vec<Draft> {
+    let mut slots = players.iter().copied().map(Some).collect::<Vec<_>>();
+    if slots.len() % 2 == 1 {
+        slots.push(None);
+    }
+
+    // Circle method: the first slot stays in place while the others rotate around it
+    let mut drafts = Vec::new();
+    for round in (0..).take(slots.len() - 1) {
+        let mut position = 0;
+        for (one, two) in slots.iter().zip(slots.iter().rev()).take(slots.len() / 2) {
+            if let (Some(one), Some(two)) = (one, two) {
+                drafts.push(Draft {
+                    group_index: Some(group),
+                    round,
+                    position,
+                    player_one: Some(*one),
+                    player_two: Some(*two),
+                });
+                position += 1;
+            }
+        }
+        slots[1..].rotate_right(1);
+    }
+
+    drafts
+}
+
+fn draft_knockout(seeds: &[Option<types::Id>]) -> Vec<Draft> {
+    let size = seeds.len().next_power_of_two().max(2);
+    let order = bracket_order(size);
+
+    let mut drafts = (0..)
+        .zip(order.chunks(2))
+        .map(|(position, pair)| Draft {
+            group_index: None,
+            round: 0,
+            position,
+            player_one: seeds.get(pair[0]).copied().flatten(),
+            player_two: seeds.get(pair[1]).copied().flatten(),
+        })
+        .collect::<Vec<_>>();
+
+    let mut count = size / 2;
+    let mut round = 0;
+    while count > 1 {
+        count /= 2;
+        round += 1;
+        drafts.extend((0..).take(count).map(|position| Draft {
+            group_index: None,
+            round,
+            position,
+            player_one: None,
+            player_two: None,
+        }));
+    }
+
+    drafts
+}
+
+// Seeds are spread so that the best ones can only meet in the latest rounds
+fn bracket_order(size: usize) -> Vec<usize> {
+    let mut order = vec![0];
+    while order.len() < size {
+        let len = order.len() * 2;
+        order = order.into_iter().flat_map(|s| [s, len - 1 - s]).collect();
+    }
+    order
+}
+
+// Snake seeding keeps the groups balanced
+fn group_of(seed: i64, groups: i64) -> i64 {
+    let (row, column) = (seed / groups, seed % groups);
+    if row % 2 == 0 {
+        column
+    } else {
+        groups - 1 - column
+    }
+}
+
+fn resolve(
+    tournament: &types::Tournament,
+    seeds: &[types::Id],
+    fixtures: &mut [types::Fixture],
+) -> std::collections::BTreeSet<types::Id> {
+    let mut changed = std::collections::BTreeSet::new();
+
+    if tournament.format == types::Format::GroupKnockout {
+        seed_knockout(tournament.advancing, seeds, fixtures, &mut changed);
+    }
+
+    // Byes only happen in the first round, where a missing seed means a walkover
+    for fixture in fixtures
+        .iter_mut()
+        .filter(|f| f.group_index.is_none() && f.round == 0 && f.winner.is_none())
+    {
+        if let (Some(player), None) | (None, Some(player)) =
+            (fixture.player_one, fixture.player_two)
+        {
+            fixture.winner = Some(player);
+            changed.insert(fixture.id);
+        }
+    }
+
+    let last_round = fixtures
+        .iter()
+        .filter(|f| f.group_index.is_none())
+        .map(|f| f.round)
+        .max()
+        .unwrap_or_default();
+
+    for round in 0..last_round {
+        let winners = fixtures
+            .iter()
+            .filter(|f| f.group_index.is_none() && f.round == round)
+            .filter_map(|f| f.winner.map(|winner| (f.position, winner)))
+            .collect::<Vec<_>>();
+
+        for (position, winner) in winners {
+            let Some(next) = fixtures.iter_mut().find(|f| {
+                f.group_index.is_none() && f.round == round + 1 && f.position == position / 2
+            }) else {
+                continue;
+            };
+
+            let slot = if position % 2 == 0 {
+                &mut next.player_one
+            } else {
+                &mut next.player_two
+            };
+
+            if slot.is_none() {
+                *slot = Some(winner);
+                changed.insert(next.id);
+            }
+        }
+    }
+
+    changed
+}
+
+fn seed_knockout(
+    advancing: i64,
+    seeds: &[types::Id],
+    fixtures: &mut [types::Fixture],
+    changed: &mut std::collections::BTreeSet<types::Id>,
+) {
+    let group_stage_over = fixtures
+        .iter()
+        .filter(|f| f.group_index.is_some())
+        .all(|f| f.winner.is_some());
+    let already_seeded = fixtures
+        .iter()
+        .filter(|f| f.group_index.is_none())
+        .any(|f| f.player_one.is_some() || f.player_two.is_some());
+
+    if !group_stage_over || already_seeded {
+        return;
+    }
+
+    let mut groups = std::collections::BTreeMap::<i64, Vec<(usize, usize, types::Id)>>::new();
+    for (seed, player) in seeds.iter().enumerate() {
+        let Some(group) = fixtures
+            .iter()
+            .filter(|f| f.player_one == Some(*player) || f.player_two == Some(*player))
+            .find_map(|f| f.group_index)
+        else {
+            continue;
+        };
+
+        let wins = fixtures
+            .iter()
+            .filter(|f| f.winner == Some(*player))
+            .count();
+
+        groups.entry(group).or_default().push((wins, seed, *player));
+    }
+
+    for table in groups.values_mut() {
+        table.sort_by(|lhs, rhs| rhs.0.cmp(&lhs.0).then_with(|| lhs.1.cmp(&rhs.1)));
+    }
+
+    // Group winners are seeded first, then the runners-up, and so forth
+    let qualifiers = (0..usize::try_from(advancing).unwrap_or_default())
+        .flat_map(|rank| groups.values().filter_map(move |t| t.get(rank)))
+        .map(|(_, _, player)| Some(*player))
+        .collect::<Vec<_>>();
+
+    let size = qualifiers.len().next_power_of_two().max(2);
+    let order = bracket_order(size);
+
+    for fixture in fixtures
+        .iter_mut()
+        .filter(|f| f.group_index.is_none() && f.round == 0)
+    {
+        let Some(pair) = usize::try_from(fixture.position)
+            .ok()
+            .and_then(|position| order.chunks(2).nth(position))
+        else {
+            continue;
+        };
+
+        fixture.player_one = qualifiers.get(pair[0]).copied().flatten();
+        fixture.player_two = qualifiers.get(pair[1]).copied().flatten();
+        changed.insert(fixture.id);
+    }
+}
@@ -97,6 +97,9 @@ pub(crate) struct Game {
     pub deleted: bool,
     pub millis: Millis,
     pub created_ms: Millis,
+    pub comment: Option<String>,
+    pub venue: Option<String>,
+    pub first_server: Option<Id>,
 }
 
 #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
@@ -113,6 +116,9 @@ pub(crate) struct GameTuple(
     pub bool,
     pub Millis,
     pub Millis,
+    pub Option<String>,
+    pub Option<String>,
+    pub Option<Id>,
 );
 
 impl From<Game> for GameTuple {
@@ -130,6 +136,9 @@ impl From<Game> for GameTuple {
             value.deleted,
             value.millis,
             value.created_ms,
+            value.comment,
+            value.venue,
+            value.first_server,
         )
     }
 }
@@ -149,6 +158,9 @@ impl From<GameTuple> for Game {
             deleted: value.9,
             millis: value.10,
             created_ms: value.11,
+            comment: value.12,
+            venue: value.13,
+            first_server: value.14,
         }
     }
 }
@@ -166,6 +178,8 @@ pub(crate) struct History {
     pub deleted: bool,
     pub millis: Millis,
     pub created_ms: Millis,
+    pub editor: Option<Id>,
+    pub reason: Option<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
@@ -180,6 +194,8 @@ pub(crate) struct HistoryTuple(
     pub bool,
     pub Millis,
     pub Millis,
+    pub Option<Id>,
+    pub Option<String>,
 );
 
 impl From<History> for HistoryTuple {
@@ -195,6 +211,8 @@ impl From<History> for HistoryTuple {
             value.deleted,
             value.millis,
             value.created_ms,
+            value.editor,
+            value.reason,
         )
     }
 }
@@ -212,6 +230,398 @@ impl From<HistoryTuple> for History {
             deleted: value.7,
             millis: value.8,
             created_ms: value.9,
+            editor: value.10,
+            reason: value.11,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum Side {
+    One,
+    Two,
+}
+
+// A point won by a side or the serve changing over to a side
+#[derive(Debug, Copy, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum Point {
+    Won(Side),
+    Serve(Side),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Points {
+    pub game: Id,
+    pub points: Vec<Point>,
+    pub longest_run_one: i64,
+    pub longest_run_two: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct LiveGame {
+    pub scorer: Id,
+    pub player_one: Id,
+    pub player_two: Id,
+    pub score_one: i64,
+    pub score_two: i64,
+    pub points: Vec<Point>,
+    pub started_ms: Millis,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Season {
+    pub id: Id,
+    pub name: String,
+    pub start_ms: Millis,
+    pub end_ms: Millis,
+    pub reset: f64,
+    pub created_ms: Millis,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub(crate) struct SeasonTuple(
+    pub Id,
+    pub String,
+    pub Millis,
+    pub Millis,
+    pub f64,
+    pub Millis,
+);
+
+impl From<Season> for SeasonTuple {
+    fn from(value: Season) -> Self {
+        Self(
+            value.id,
+            value.name,
+            value.start_ms,
+            value.end_ms,
+            value.reset,
+            value.created_ms,
+        )
+    }
+}
+
+impl From<SeasonTuple> for Season {
+    fn from(value: SeasonTuple) -> Self {
+        Self {
+            id: value.0,
+            name: value.1,
+            start_ms: value.2,
+            end_ms: value.3,
+            reset: value.4,
+            created_ms: value.5,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize, sqlx::Type)]
+#[serde(rename_all = "camelCase")]
+#[sqlx(rename_all = "camelCase")]
+pub(crate) enum AuditAction {
+    Rename,
+    Invite,
+    CancelInvite,
+    AcceptInvite,
+    RejectInvite,
+    CreateSeason,
+    DeleteSeason,
+    CreateTournament,
+    DeleteTournament,
+    LinkFixture,
+    RevertGame,
+    UndoGame,
+    GrantAdmin,
+    RevokeAdmin,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize, sqlx::Type)]
+#[serde(rename_all = "camelCase")]
+#[sqlx(rename_all = "camelCase")]
+pub(crate) enum Entity {
+    Player,
+    Invite,
+    Season,
+    Tournament,
+    Fixture,
+    Game,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Audit {
+    pub id: Id,
+    pub actor: Option<Id>,
+    pub action: AuditAction,
+    pub entity: Entity,
+    pub entity_id: Id,
+    // JSON snapshots of the entity
+    pub before: Option<String>,
+    pub after: Option<String>,
+    pub created_ms: Millis,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub(crate) struct AuditTuple(
+    pub Id,
+    pub Option<Id>,
+    pub AuditAction,
+    pub Entity,
+    pub Id,
+    pub Option<String>,
+    pub Option<String>,
+    pub Millis,
+);
+
+impl From<Audit> for AuditTuple {
+    fn from(value: Audit) -> Self {
+        Self(
+            value.id,
+            value.actor,
+            value.action,
+            value.entity,
+            value.entity_id,
+            value.before,
+            value.after,
+            value.created_ms,
+        )
+    }
+}
+
+impl From<AuditTuple> for Audit {
+    fn from(value: AuditTuple) -> Self {
+        Self {
+            id: value.0,
+            actor: value.1,
+            action: value.2,
+            entity: value.3,
+            entity_id: value.4,
+            before: value.5,
+            after: value.6,
+            created_ms: value.7,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Streak {
+    pub player: Id,
+    // Positive for wins and negative for losses
+    pub current: i64,
+    pub longest_win: i64,
+    pub longest_loss: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub(crate) struct StreakTuple(pub Id, pub i64, pub i64, pub i64);
+
+impl From<Streak> for StreakTuple {
+    fn from(value: Streak) -> Self {
+        Self(
+            value.player,
+            value.current,
+            value.longest_win,
+            value.longest_loss,
+        )
+    }
+}
+
+impl From<StreakTuple> for Streak {
+    fn from(value: StreakTuple) -> Self {
+        Self {
+            player: value.0,
+            current: value.1,
+            longest_win: value.2,
+            longest_loss: value.3,
+        }
+    }
+}
+
+// What changed along with the games it is derived from
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct Derived {
+    // Previous and current streaks of the players whose streaks changed
+    pub streaks: Vec<(Streak, Streak)>,
+    pub unlocked: Vec<Achievement>,
+    pub revoked: Vec<Achievement>,
+}
+
+impl Derived {
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.streaks.is_empty() && self.unlocked.is_empty() && self.revoked.is_empty()
+    }
+}
+
+#[derive(
+    Debug,
+    Copy,
+    Clone,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    serde::Serialize,
+    serde::Deserialize,
+    sqlx::Type,
+)]
+#[serde(rename_all = "camelCase")]
+#[sqlx(rename_all = "camelCase")]
+pub(crate) enum AchievementKind {
+    FirstWin,
+    WinStreak,
+    BeatTheBest,
+    Shutout,
+    Centurion,
+    TieBreak,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Achievement {
+    pub id: Id,
+    pub player: Id,
+    pub kind: AchievementKind,
+    pub game: Id,
+    pub created_ms: Millis,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub(crate) struct AchievementTuple(pub Id, pub Id, pub AchievementKind, pub Id, pub Millis);
+
+impl From<Achievement> for AchievementTuple {
+    fn from(value: Achievement) -> Self {
+        Self(
+            value.id,
+            value.player,
+            value.kind,
+            value.game,
+            value.created_ms,
+        )
+    }
+}
+
+impl From<AchievementTuple> for Achievement {
+    fn from(value: AchievementTuple) -> Self {
+        Self {
+            id: value.0,
+            player: value.1,
+            kind: value.2,
+            game: value.3,
+            created_ms: value.4,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize, sqlx::Type)]
+#[serde(rename_all = "camelCase")]
+#[sqlx(rename_all = "camelCase")]
+pub(crate) enum Format {
+    RoundRobin,
+    SingleElimination,
+    GroupKnockout,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Tournament {
+    pub id: Id,
+    pub name: String,
+    pub format: Format,
+    pub groups: i64,
+    pub advancing: i64,
+    pub created_ms: Millis,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub(crate) struct TournamentTuple(pub Id, pub String, pub Format, pub i64, pub i64, pub Millis);
+
+impl From<Tournament> for TournamentTuple {
+    fn from(value: Tournament) -> Self {
+        Self(
+            value.id,
+            value.name,
+            value.format,
+            value.groups,
+            value.advancing,
+            value.created_ms,
+        )
+    }
+}
+
+impl From<TournamentTuple> for Tournament {
+    fn from(value: TournamentTuple) -> Self {
+        Self {
+            id: value.0,
+            name: value.1,
+            format: value.2,
+            groups: value.3,
+            advancing: value.4,
+            created_ms: value.5,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Fixture {
+    pub id: Id,
+    pub tournament: Id,
+    pub group_index: Option<i64>,
+    pub round: i64,
+    pub position: i64,
+    pub player_one: Option<Id>,
+    pub player_two: Option<Id>,
+    pub game: Option<Id>,
+    pub winner: Option<Id>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub(crate) struct FixtureTuple(
+    pub Id,
+    pub Id,
+    pub Option<i64>,
+    pub i64,
+    pub i64,
+    pub Option<Id>,
+    pub Option<Id>,
+    pub Option<Id>,
+    pub Option<Id>,
+);
+
+impl From<Fixture> for FixtureTuple {
+    fn from(value: Fixture) -> Self {
+        Self(
+            value.id,
+            value.tournament,
+            value.group_index,
+            value.round,
+            value.position,
+            value.player_one,
+            value.player_two,
+            value.game,
+            value.winner,
+        )
+    }
+}
+
+impl From<FixtureTuple> for Fixture {
+    fn from(value: FixtureTuple) -> Self {
+        Self {
+            id: value.0,
+            tournament: value.1,
+            group_index: value.2,
+            round: value.3,
+            position: value.4,
+            player_one: value.5,
+            player_two: value.6,
+            game: value.7,
+            winner: value.8,
         }
     }
 }
@@ -244,3 +654,197 @@ impl From<Millis> for i64 {
         value.0
     }
 }
+
+impl Millis {
+    pub fn now() -> Self {
+        // allow(clippy::cast_possible_truncation): Milliseconds since the epoch fit in an i64
+        #[allow(clippy::cast_possible_truncation)]
+        let millis = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.as_millis() as i64);
+        Self(millis)
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum Resolution {
+    Game,
+    Day,
+    Week,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RatingPoint {
+    pub millis: Millis,
+    pub rating: f64,
+    pub peak: f64,
+    pub trough: f64,
+    pub games: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub(crate) struct RatingPointTuple(pub Millis, pub f64, pub f64, pub f64, pub u32);
+
+impl From<RatingPoint> for RatingPointTuple {
+    fn from(value: RatingPoint) -> Self {
+        Self(
+            value.millis,
+            value.rating,
+            value.peak,
+            value.trough,
+            value.games,
+        )
+    }
+}
+
+impl From<RatingPointTuple> for RatingPoint {
+    fn from(value: RatingPointTuple) -> Self {
+        Self {
+            millis: value.0,
+            rating: value.1,
+            peak: value.2,
+            trough: value.3,
+            games: value.4,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RatingParameters {
+    pub k_factor: f64,
+    pub challenge_multiplier: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Comparison {
+    pub player: Id,
+    pub games: i64,
+    pub rank: Option<i64>,
+    pub rating: f64,
+    pub simulated_rank: Option<i64>,
+    pub simulated_rating: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub(crate) struct ComparisonTuple(
+    pub Id,
+    pub i64,
+    pub Option<i64>,
+    pub f64,
+    pub Option<i64>,
+    pub f64,
+);
+
+impl From<Comparison> for ComparisonTuple {
+    fn from(value: Comparison) -> Self {
+        Self(
+            value.player,
+            value.games,
+            value.rank,
+            value.rating,
+            value.simulated_rank,
+            value.simulated_rating,
+        )
+    }
+}
+
+impl From<ComparisonTuple> for Comparison {
+    fn from(value: ComparisonTuple) -> Self {
+        Self {
+            player: value.0,
+            games: value.1,
+            rank: value.2,
+            rating: value.3,
+            simulated_rank: value.4,
+            simulated_rating: value.5,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Suggestion {
+    pub player: Id,
+    pub rating: f64,
+    pub last_played: Option<Millis>,
+    pub challenge: bool,
+    pub score: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub(crate) struct SuggestionTuple(pub Id, pub f64, pub Option<Millis>, pub bool, pub f64);
+
+impl From<Suggestion> for SuggestionTuple {
+    fn from(value: Suggestion) -> Self {
+        Self(
+            value.player,
+            value.rating,
+            value.last_played,
+            value.challenge,
+            value.score,
+        )
+    }
+}
+
+impl From<SuggestionTuple> for Suggestion {
+    fn from(value: SuggestionTuple) -> Self {
+        Self {
+            player: value.0,
+            rating: value.1,
+            last_played: value.2,
+            challenge: value.3,
+            score: value.4,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Standing {
+    pub player: Id,
+    pub rank: Option<i64>,
+    pub rating: f64,
+    pub games: i64,
+    pub previous_rank: Option<i64>,
+    pub previous_rating: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub(crate) struct StandingTuple(
+    pub Id,
+    pub Option<i64>,
+    pub f64,
+    pub i64,
+    pub Option<i64>,
+    pub Option<f64>,
+);
+
+impl From<Standing> for StandingTuple {
+    fn from(value: Standing) -> Self {
+        Self(
+            value.player,
+            value.rank,
+            value.rating,
+            value.games,
+            value.previous_rank,
+            value.previous_rating,
+        )
+    }
+}
+
+impl From<StandingTuple> for Standing {
+    fn from(value: StandingTuple) -> Self {
+        Self {
+            player: value.0,
+            rank: value.1,
+            rating: value.2,
+            games: value.3,
+            previous_rank: value.4,
+            previous_rating: value.5,
+        }
+    }
+}
@@ -4,25 +4,76 @@ pub trait Message: serde::Serialize {}
 impl<T> Message for Response<T> where T: serde::Serialize {}
 impl Message for Error {}
 impl<T> Message for Push<T> where T: serde::Serialize {}
+impl Message for Resync {}
+impl Message for Session {}
+impl<T> Message for BatchResponse<T> where T: serde::Serialize {}
 
-#[derive(Debug, serde::Serialize)]
+// Written as maps even by modes that write structs as arrays, so messages are told apart by keys
+macro_rules! envelope {
+    ($name: ident $(<$generic: ident>)?, $($field: ident),+) => {
+        impl$(<$generic>)? serde::Serialize for $name$(<$generic>)?
+        $(where $generic: serde::Serialize)?
+        {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: serde::Serializer,
+            {
+                use serde::ser::SerializeMap;
+
+                let mut map = serializer.serialize_map(Some([$(stringify!($field)),+].len()))?;
+                $(map.serialize_entry(stringify!($field), &self.$field)?;)+
+                map.end()
+            }
+        }
+    };
+}
+
+envelope!(Response<T>, id, ok);
+envelope!(Push<T>, seq, push);
+envelope!(Resync, resync);
+envelope!(Session, session, seq);
+envelope!(BatchResponse<T>, id, batch);
+
+impl serde::Serialize for Error {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeMap;
+
+        let mut map = serializer.serialize_map(Some(1 + usize::from(self.id.is_some())))?;
+        if let Some(id) = self.id {
+            map.serialize_entry("id", &id)?;
+        }
+        map.serialize_entry("error", &self.error)?;
+        map.end()
+    }
+}
+
+#[derive(Debug)]
 pub struct Response<T> {
     pub id: Id,
     pub ok: T,
 }
 
-#[derive(Debug, serde::Serialize)]
+#[derive(Debug)]
 pub struct Error {
-    #[serde(skip_serializing_if = "Option::is_none")]
     pub id: Option<Id>,
     pub error: super::service::Error,
 }
 
-#[derive(Debug, serde::Serialize)]
+#[derive(Debug)]
 pub struct Push<T> {
+    pub seq: super::service::Seq,
     pub push: T,
 }
 
+// Pushes were missed and can no longer be replayed, so clients must fetch everything again
+#[derive(Debug)]
+pub struct Resync {
+    pub resync: super::service::Seq,
+}
+
 #[derive(Debug, Clone, serde::Deserialize)]
 #[cfg_attr(test, derive(PartialEq, serde::Serialize))]
 pub struct Request<T> {
@@ -31,6 +82,51 @@ pub struct Request<T> {
     pub payload: T,
 }
 
+// Executes the requests in order, skipping the ones after the first failure if requested
+#[derive(Debug, Clone, serde::Deserialize)]
+#[cfg_attr(test, derive(PartialEq, serde::Serialize))]
+pub struct Batch<T> {
+    pub id: Id,
+    pub batch: Vec<Request<T>>,
+    #[serde(default, rename = "stopOnError")]
+    pub stop_on_error: bool,
+}
+
+// Carries the outcome of every request in the batch, keyed by their own ids
+#[derive(Debug)]
+pub struct BatchResponse<T> {
+    pub id: Id,
+    pub batch: Vec<Outcome<T>>,
+}
+
+#[derive(Debug, serde::Serialize)]
+#[serde(untagged)]
+pub enum Outcome<T> {
+    Ok(Response<T>),
+    Error(Error),
+}
+
+// Sent when connecting, so the client can resume from it after reconnecting
+#[derive(Debug)]
+pub struct Session {
+    pub session: String,
+    pub seq: super::service::Seq,
+}
+
+#[derive(Debug, Clone, serde::Deserialize)]
+#[cfg_attr(test, derive(PartialEq, serde::Serialize))]
+pub struct Resume {
+    pub resume: String,
+    pub seq: super::service::Seq,
+}
+
+// Drops the request still in flight with the given id, which will then get no response
+#[derive(Debug, Clone, serde::Deserialize)]
+#[cfg_attr(test, derive(PartialEq, serde::Serialize))]
+pub struct Cancel {
+    pub cancel: Id,
+}
+
 #[derive(Debug, Clone, serde::Deserialize)]
 #[cfg_attr(test, derive(PartialEq, serde::Serialize))]
 pub struct OnlyId {
@@ -1,16 +1,105 @@
 mod message;
 mod mode;
+mod session;
 
-pub use mode::Mode;
+pub use mode::{Cbor, Compact, Mode};
+pub use session::Sessions;
 
 use super::service;
 
+const MAX_BATCH: usize = 32;
+const MAX_IN_FLIGHT: usize = 8;
+const COMPRESSION_THRESHOLD: usize = 1024;
+
+// Subprotocol offered by clients that can inflate the large messages
+pub const DEFLATE: &str = "deflate";
+
 enum FlowControl<T> {
     Break,
     Continue,
     Pass(T),
 }
 
+enum Incoming<T> {
+    Request(message::Request<T>),
+    Batch(message::Batch<T>),
+    Resume(message::Resume),
+    Cancel(message::Cancel),
+}
+
+enum Completed<R, E> {
+    Call(Result<R, E>),
+    Batch(Vec<(message::Id, Result<R, E>)>),
+}
+
+struct Completion<R, E> {
+    id: message::Id,
+    task: u64,
+    completed: Option<Completed<R, E>>,
+}
+
+// Reports back when dropped, so the request is accounted for even if it panicked or was cancelled
+struct Slot<R, E> {
+    completion: Option<Completion<R, E>>,
+    done: tokio::sync::mpsc::UnboundedSender<Completion<R, E>>,
+}
+
+impl<R, E> Drop for Slot<R, E> {
+    fn drop(&mut self) {
+        if let Some(completion) = self.completion.take() {
+            // The connection is gone if nobody is listening
+            let _ = self.done.send(completion);
+        }
+    }
+}
+
+// Ids can be reused once cancelled, so tasks are told apart by their own counter. Tasks that
+// cannot be aborted have no handle
+#[derive(Default)]
+struct InFlight {
+    next: u64,
+    tasks: std::collections::HashMap<message::Id, (u64, Option<tokio::task::AbortHandle>)>,
+}
+
+impl InFlight {
+    fn insert(&mut self, id: message::Id, handle: Option<tokio::task::AbortHandle>) {
+        self.tasks.insert(id, (self.next, handle));
+        self.next += 1;
+    }
+
+    fn cancel(&mut self, id: message::Id) -> bool {
+        let Some((_, handle)) = self.tasks.remove(&id) else {
+            return false;
+        };
+
+        if let Some(handle) = handle {
+            handle.abort();
+        }
+        true
+    }
+
+    fn cancel_all(&mut self) {
+        for (_, (_, handle)) in self.tasks.drain() {
+            if let Some(handle) = handle {
+                handle.abort();
+            }
+        }
+    }
+
+    fn complete(&mut self, id: message::Id, task: u64) -> bool {
+        if self
+            .tasks
+            .get(&id)
+            .is_some_and(|(current, _)| *current == task)
+        {
+            self.tasks.remove(&id);
+            true
+        } else {
+            false
+        }
+    }
+}
+
 pub struct Layer<M, S>
 where
     M: Mode,
@@ -19,6 +108,8 @@ where
     id: String,
     service: S,
     socket: axum::extract::ws::WebSocket,
+    sessions: Sessions,
+    deflate: bool,
     _mode: std::marker::PhantomData<M>,
 }
 
@@ -27,14 +118,19 @@ where
     M: Mode,
     S: service::Service,
 {
-    pub fn new(socket: axum::extract::ws::WebSocket, service: S) -> Self {
+    pub fn new(socket: axum::extract::ws::WebSocket, service: S, sessions: Sessions) -> Self {
         let id = format!("{id:04x}", id = rand::random::<u16>());
-        tracing::debug!(ws = %id, mode = %M::mode(), "Opening websocket");
+        let deflate = socket
+            .protocol()
+            .is_some_and(|protocol| protocol.as_bytes() == DEFLATE.as_bytes());
+        tracing::debug!(ws = %id, mode = %M::mode(), %deflate, "Opening websocket");
 
         Self {
             id,
             socket,
             service,
+            sessions,
+            deflate,
             _mode: std::marker::PhantomData,
         }
     }
@@ -51,43 +147,318 @@ where
             };
         }
 
-        let mut broadcast = self.service.subscribe();
+        if let Some(close) = self.service.rejects() {
+            self.close(close).await;
+            return;
+        }
+
+        let (mut last, mut broadcast) = self.service.subscribe();
+        let start = last;
+
+        let (done, mut completions) = tokio::sync::mpsc::unbounded_channel();
+        let mut in_flight = InFlight::default();
+
+        let session = message::Session {
+            session: self.sessions.issue(self.service.owner()),
+            seq: start,
+        };
+        if let FlowControl::Break = self.send::<_, ()>(session).await {
+            return;
+        }
 
         loop {
             tokio::select! {
                 () = tokio::time::sleep(std::time::Duration::from_secs(30)) => self.heartbeat().await,
                 message = broadcast.recv() => {
-                    let push = match message {
-                        Ok(push) => push,
+                    let (seq, push) = match message {
+                        Ok(message) => message,
+                        Err(tokio::sync::broadcast::error::RecvError::Lagged(skipped)) => {
+                            tracing::warn!(%skipped, "Lagged behind the broadcaster");
+                            flow!(self.catch_up(&mut last).await);
+                        }
                         Err(error) => {
                             tracing::warn!(%error, "Failed to read from broadcaster");
                             continue;
                         }
                     };
 
-                    let message = message::Push { push };
+                    // Already replayed while catching up
+                    if seq <= last {
+                        continue;
+                    }
+                    last = seq;
+
+                    if let Some(close) = self.service.closes(&push) {
+                        self.close(close).await;
+                        break;
+                    }
+
+                    if !self.service.accepts(&push) {
+                        continue;
+                    }
+
+                    let Some(push) = self.service.present_push(push) else {
+                        continue;
+                    };
+                    let message = message::Push { seq, push };
                     tracing::debug!("Pushing message");
                     flow!(self.send(message).await);
                 }
+                // Never closes, since `done` is held by this loop
+                Some(Completion { id, task, completed }) = completions.recv() => {
+                    // Cancelled requests are no longer in flight and get no response
+                    if !in_flight.complete(id, task) {
+                        continue;
+                    }
+
+                    flow!(self.respond(id, completed).await);
+                }
                 request = self.recv() => {
-                    let message::Request{ id, payload } = flow!(request);
+                    match flow!(request) {
+                        Incoming::Request(message::Request { id, payload }) => {
+                            let cancellable = self.service.cancellable(&payload);
+                            let mut service = self.service.clone();
+                            let work = async move { Completed::Call(service.call(payload).await) };
+                            flow!(self.spawn(id, work, cancellable, &mut in_flight, &done).await);
+                        }
+                        Incoming::Batch(message::Batch {
+                            id,
+                            batch,
+                            stop_on_error,
+                        }) => {
+                            if batch.len() > MAX_BATCH {
+                                tracing::warn!(count = %batch.len(), "Batch is too large");
+                                let message = message::Error {
+                                    id: Some(id),
+                                    error: service::Error::new(
+                                        hyper::StatusCode::BAD_REQUEST,
+                                        &format!("Batches cannot have more than {MAX_BATCH} requests"),
+                                    ),
+                                };
+                                flow!(self.send(message).await);
+                            }
+
+                            let (ids, payloads): (Vec<_>, Vec<_>) = batch
+                                .into_iter()
+                                .map(|message::Request { id, payload }| (id, payload))
+                                .unzip();
+                            let cancellable = payloads.iter().all(|p| self.service.cancellable(p));
 
-                    match self.service.call(payload).await {
-                        Ok(ok) =>{
-                            let message = message::Response { id, ok };
-                            tracing::debug!("Responding OK");
-                            flow!(self.send(message).await);
+                            let mut service = self.service.clone();
+                            let work = async move {
+                                let results = service.batch(payloads, stop_on_error).await;
+                                Completed::Batch(ids.into_iter().zip(results).collect())
+                            };
+                            flow!(self.spawn(id, work, cancellable, &mut in_flight, &done).await);
+                        }
+                        Incoming::Resume(resume) => flow!(self.resume(resume, start, last).await),
+                        Incoming::Cancel(message::Cancel { cancel }) => {
+                            if in_flight.cancel(cancel) {
+                                tracing::debug!(id = %cancel, "Cancelled request");
+                            } else {
+                                tracing::debug!(id = %cancel, "Request to cancel is not in flight");
+                            }
                         }
-                        Err(error) => {
-                            let error = error.into();
-                            let message = message::Error { id: Some(id), error };
-                            tracing::debug!("Responding ERROR");
-                            flow!(self.send(message).await);
-                        },
                     }
                 }
             }
         }
+
+        // Nobody is left to respond to
+        in_flight.cancel_all();
+    }
+
+    async fn spawn<F, R>(
+        &mut self,
+        id: message::Id,
+        work: F,
+        cancellable: bool,
+        in_flight: &mut InFlight,
+        done: &tokio::sync::mpsc::UnboundedSender<Completion<S::Response, S::Error>>,
+    ) -> FlowControl<R>
+    where
+        F: std::future::Future<Output = Completed<S::Response, S::Error>> + Send + 'static,
+    {
+        if in_flight.tasks.contains_key(&id) {
+            tracing::warn!(%id, "Request id is already in flight");
+            let message = message::Error {
+                id: Some(id),
+                error: service::Error::new(
+                    hyper::StatusCode::CONFLICT,
+                    "A request with this id is already in flight",
+                ),
+            };
+            return self.send(message).await;
+        }
+
+        if in_flight.tasks.len() >= MAX_IN_FLIGHT {
+            tracing::warn!(%id, "Too many requests in flight");
+            let message = message::Error {
+                id: Some(id),
+                error: service::Error::new(
+                    hyper::StatusCode::TOO_MANY_REQUESTS,
+                    &format!("Cannot have more than {MAX_IN_FLIGHT} requests in flight"),
+                ),
+            };
+            return self.send(message).await;
+        }
+
+        let mut slot = Slot {
+            completion: Some(Completion {
+                id,
+                task: in_flight.next,
+                completed: None,
+            }),
+            done: done.clone(),
+        };
+
+        let task = tokio::spawn(async move {
+            let completed = work.await;
+            if let Some(completion) = slot.completion.as_mut() {
+                completion.completed = Some(completed);
+            }
+        });
+
+        in_flight.insert(id, cancellable.then(|| task.abort_handle()));
+        FlowControl::Continue
+    }
+
+    async fn respond<R>(
+        &mut self,
+        id: message::Id,
+        completed: Option<Completed<S::Response, S::Error>>,
+    ) -> FlowControl<R> {
+        match completed {
+            Some(Completed::Call(Ok(ok))) => {
+                let ok = self.service.present_response(ok);
+                let message = message::Response { id, ok };
+                tracing::debug!("Responding OK");
+                self.send(message).await
+            }
+            Some(Completed::Call(Err(error))) => {
+                let error = error.into();
+                let message = message::Error {
+                    id: Some(id),
+                    error,
+                };
+                tracing::debug!("Responding ERROR");
+                self.send(message).await
+            }
+            Some(Completed::Batch(results)) => {
+                let batch = results
+                    .into_iter()
+                    .map(|(id, result)| match result {
+                        Ok(ok) => message::Outcome::Ok(message::Response {
+                            id,
+                            ok: self.service.present_response(ok),
+                        }),
+                        Err(error) => message::Outcome::Error(message::Error {
+                            id: Some(id),
+                            error: error.into(),
+                        }),
+                    })
+                    .collect();
+
+                tracing::debug!("Responding BATCH");
+                self.send(message::BatchResponse { id, batch }).await
+            }
+            None => {
+                tracing::error!(%id, "Request did not complete");
+                let message = message::Error {
+                    id: Some(id),
+                    error: service::Error::new(
+                        hyper::StatusCode::INTERNAL_SERVER_ERROR,
+                        "Internal server error",
+                    ),
+                };
+                self.send(message).await
+            }
+        }
+    }
+
+    async fn catch_up<R>(&mut self, last: &mut service::Seq) -> FlowControl<R> {
+        match self.service.replay(*last) {
+            service::Replay::Missed(pushes) => {
+                if let Some(close) = pushes
+                    .iter()
+                    .find_map(|(_, push)| self.service.closes(push))
+                {
+                    self.close(close).await;
+                    return FlowControl::Break;
+                }
+
+                if let Some((seq, _)) = pushes.last() {
+                    *last = *seq;
+                }
+                self.replay(pushes).await
+            }
+            service::Replay::Resync(seq) => {
+                tracing::warn!(%seq, "Missed pushes are no longer buffered, requesting a resync");
+                *last = seq;
+                self.send(message::Resync { resync: seq }).await
+            }
+        }
+    }
+
+    // Pushes after `start` are already sent by this connection, so only the ones in between are
+    // replayed, even if out of order with what was pushed meanwhile
+    async fn resume<R>(
+        &mut self,
+        message::Resume { resume, seq }: message::Resume,
+        start: service::Seq,
+        last: service::Seq,
+    ) -> FlowControl<R> {
+        if !self.sessions.owns(&resume, &self.service.owner()) {
+            tracing::debug!("Unknown session, requesting a resync");
+            return self.send(message::Resync { resync: last }).await;
+        }
+
+        match self.service.replay(seq) {
+            service::Replay::Missed(pushes) => {
+                let pushes = pushes
+                    .into_iter()
+                    .filter(|(seq, _)| *seq <= start)
+                    .collect();
+                self.replay(pushes).await
+            }
+            service::Replay::Resync(seq) => {
+                tracing::debug!(%seq, "Session is too far behind, requesting a resync");
+                self.send(message::Resync { resync: seq }).await
+            }
+        }
+    }
+
+    async fn replay<R>(&mut self, pushes: Vec<(service::Seq, S::Push)>) -> FlowControl<R> {
+        tracing::debug!(count = %pushes.len(), "Replaying missed pushes");
+        for (seq, push) in pushes {
+            if !self.service.accepts(&push) {
+                continue;
+            }
+
+            let Some(push) = self.service.present_push(push) else {
+                continue;
+            };
+            if let FlowControl::Break = self.send::<_, ()>(message::Push { seq, push }).await {
+                return FlowControl::Break;
+            }
+        }
+        FlowControl::Continue
+    }
+
+    async fn close(&mut self, close: service::Close) {
+        tracing::info!(code = %close.code, reason = %close.reason, "Closing websocket");
+        let frame = axum::extract::ws::CloseFrame {
+            code: close.code,
+            reason: std::borrow::Cow::Borrowed(close.reason),
+        };
+
+        if let Err(error) = self
+            .socket
+            .send(axum::extract::ws::Message::Close(Some(frame)))
+            .await
+        {
+            tracing::warn!(%error, "Failed to send close frame");
+        }
     }
 
     async fn heartbeat(&mut self) {
@@ -101,7 +472,7 @@ where
         }
     }
 
-    async fn recv(&mut self) -> FlowControl<message::Request<S::Request>> {
+    async fn recv(&mut self) -> FlowControl<Incoming<S::Request>> {
         // Closed socket
         let Some(message) = self.socket.recv().await else {
             tracing::debug!("Closing websocket");
@@ -133,8 +504,20 @@ where
             axum::extract::ws::Message::Binary(binary) => binary,
         };
 
+        if let Ok(resume) = M::deserialize(&bytes) {
+            return FlowControl::Pass(Incoming::Resume(resume));
+        }
+
+        if let Ok(cancel) = M::deserialize(&bytes) {
+            return FlowControl::Pass(Incoming::Cancel(cancel));
+        }
+
+        if let Ok(batch) = M::deserialize(&bytes) {
+            return FlowControl::Pass(Incoming::Batch(batch));
+        }
+
         match M::deserialize(&bytes) {
-            Ok(message) => FlowControl::Pass(message),
+            Ok(message) => FlowControl::Pass(Incoming::Request(message)),
             Err(error) => {
                 tracing::warn!(%error, "Failed to deserialize request");
                 let message = message::Error {
@@ -152,11 +535,21 @@ where
     {
         match M::serialize(message) {
             Ok((message, len)) => {
+                let (message, compressed) = if self.deflate {
+                    compress(message, len)
+                } else {
+                    (message, None)
+                };
+
                 if let Err(error) = self.socket.send(message).await {
                     tracing::error!(%error, "Failed to send message");
                     FlowControl::Break
                 } else {
-                    tracing::debug!(bytes = %len, "Message sent");
+                    if let Some(compressed) = compressed {
+                        tracing::debug!(bytes = %len, %compressed, "Message sent");
+                    } else {
+                        tracing::debug!(bytes = %len, "Message sent");
+                    }
                     FlowControl::Continue
                 }
             }
@@ -168,6 +561,37 @@ where
     }
 }
 
+// Compressed messages are always binary and start with the zlib header, which is never the first
+// byte of an uncompressed message
+fn compress(
+    message: axum::extract::ws::Message,
+    len: usize,
+) -> (axum::extract::ws::Message, Option<usize>) {
+    if len < COMPRESSION_THRESHOLD {
+        return (message, None);
+    }
+
+    let bytes = match &message {
+        axum::extract::ws::Message::Text(text) => text.as_bytes(),
+        axum::extract::ws::Message::Binary(binary) => binary.as_slice(),
+        _ => return (message, None),
+    };
+
+    let mut encoder =
+        flate2::write::ZlibEncoder::new(Vec::with_capacity(len / 4), flate2::Compression::fast());
+
+    match std::io::Write::write_all(&mut encoder, bytes).and_then(|()| encoder.finish()) {
+        Ok(compressed) => {
+            let len = compressed.len();
+            (axum::extract::ws::Message::Binary(compressed), Some(len))
+        }
+        Err(error) => {
+            tracing::warn!(%error, "Failed to compress message");
+            (message, None)
+        }
+    }
+}
+
 fn try_extract_id<M>(bytes: &[u8]) -> Option<message::Id>
 where
     M: Mode,
@@ -179,12 +603,12 @@ where
 mod tests {
     #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
     struct Payload<'a> {
-        name: &'a str,
+        name: std::borrow::Cow<'a, str>,
         count: u32,
     }
 
     const OBJ: Payload<'_> = Payload {
-        name: "name_value",
+        name: std::borrow::Cow::Borrowed("name_value"),
         count: 8855,
     };
     const STR: &str = r#"{"name":"name_value","count":8855}"#;
@@ -239,22 +663,365 @@ mod tests {
         }
     }
 
+    mod mode {
+        use super::{
+            super::{
+                message::{Request, Response},
+                mode::sealed::Mode,
+                Cbor, Compact,
+            },
+            Payload, OBJ,
+        };
+
+        fn bytes(message: axum::extract::ws::Message) -> Vec<u8> {
+            match message {
+                axum::extract::ws::Message::Text(text) => text.into_bytes(),
+                axum::extract::ws::Message::Binary(binary) => binary,
+                message => panic!("Unexpected message: {message:?}"),
+            }
+        }
+
+        fn round_trip<M: Mode>() {
+            let request = Request {
+                id: 27,
+                payload: OBJ,
+            };
+
+            let output = bytes(M::serialize(request.clone()).unwrap().0);
+            let message = M::deserialize::<Request<Payload<'_>>>(&output).unwrap();
+
+            assert_eq!(message, request);
+        }
+
+        #[test]
+        fn text() {
+            round_trip::<String>();
+        }
+
+        #[test]
+        fn binary() {
+            round_trip::<Vec<u8>>();
+        }
+
+        #[test]
+        fn cbor() {
+            round_trip::<Cbor>();
+        }
+
+        #[test]
+        fn compact() {
+            round_trip::<Compact>();
+        }
+
+        #[test]
+        fn cbor_envelope() {
+            let output = bytes(Cbor::serialize(Response { id: 27, ok: OBJ }).unwrap().0);
+            let value = ciborium::from_reader::<serde_json::Value, _>(output.as_slice()).unwrap();
+
+            let expected =
+                serde_json::json!({ "id": 27, "ok": { "name": "name_value", "count": 8855 } });
+
+            assert_eq!(value, expected);
+        }
+
+        #[test]
+        fn compact_envelope() {
+            let output = bytes(Compact::serialize(Response { id: 27, ok: OBJ }).unwrap().0);
+            let value = rmp_serde::from_slice::<serde_json::Value>(&output).unwrap();
+
+            let expected = serde_json::json!({ "id": 27, "ok": ["name_value", 8855] });
+
+            assert_eq!(value, expected);
+        }
+
+        #[test]
+        fn compact_is_smaller() {
+            let compact = Compact::serialize(Response { id: 27, ok: OBJ }).unwrap().1;
+            let binary = Vec::<u8>::serialize(Response { id: 27, ok: OBJ })
+                .unwrap()
+                .1;
+
+            assert!(compact < binary);
+        }
+    }
+
+    mod in_flight {
+        use super::super::InFlight;
+
+        #[test]
+        fn cancel() {
+            let mut in_flight = InFlight::default();
+            in_flight.insert(27, None);
+
+            // Tasks that cannot be aborted only have their response dropped
+            assert!(in_flight.cancel(27));
+            assert!(!in_flight.complete(27, 0));
+            assert!(!in_flight.cancel(27));
+        }
+
+        #[test]
+        fn reused_id() {
+            let mut in_flight = InFlight::default();
+            in_flight.insert(27, None);
+            assert!(in_flight.cancel(27));
+            in_flight.insert(27, None);
+
+            assert!(!in_flight.complete(27, 0));
+            assert!(in_flight.complete(27, 1));
+        }
+
+        #[test]
+        fn cancel_all() {
+            let mut in_flight = InFlight::default();
+            in_flight.insert(27, None);
+            in_flight.insert(28, None);
+
+            in_flight.cancel_all();
+
+            assert!(!in_flight.complete(27, 0));
+            assert!(!in_flight.complete(28, 1));
+        }
+    }
+
+    mod compression {
+        use super::super::{compress, COMPRESSION_THRESHOLD};
+
+        #[test]
+        fn small() {
+            let message = axum::extract::ws::Message::Text(String::from("small"));
+
+            let (output, compressed) = compress(message.clone(), 5);
+
+            assert_eq!(output, message);
+            assert_eq!(compressed, None);
+        }
+
+        #[test]
+        fn large() {
+            let text = "name_value,".repeat(COMPRESSION_THRESHOLD);
+            let message = axum::extract::ws::Message::Text(text.clone());
+
+            let (output, compressed) = compress(message, text.len());
+
+            let axum::extract::ws::Message::Binary(bytes) = output else {
+                panic!("Expected a binary message");
+            };
+            assert_eq!(bytes.first(), Some(&0x78));
+            assert_eq!(compressed, Some(bytes.len()));
+            assert!(bytes.len() < text.len());
+
+            let mut inflated = String::new();
+            std::io::Read::read_to_string(
+                &mut flate2::read::ZlibDecoder::new(bytes.as_slice()),
+                &mut inflated,
+            )
+            .unwrap();
+            assert_eq!(inflated, text);
+        }
+    }
+
     mod push {
         use super::{
-            super::{message::Push, mode::sealed::Mode},
+            super::{
+                message::{Push, Resync},
+                mode::sealed::Mode,
+            },
             OBJ, STR,
         };
 
         #[test]
         fn happy() {
-            let payload = Push { push: OBJ };
+            let payload = Push { seq: 5, push: OBJ };
 
             let output = String::serialize(payload).unwrap().0;
 
-            let expected = axum::extract::ws::Message::Text(format!(r#"{{"push":{STR}}}"#));
+            let expected = axum::extract::ws::Message::Text(format!(r#"{{"seq":5,"push":{STR}}}"#));
 
             assert_eq!(output, expected);
         }
+
+        #[test]
+        fn resync() {
+            let payload = Resync { resync: 5 };
+
+            let output = String::serialize(payload).unwrap().0;
+
+            let expected = axum::extract::ws::Message::Text(String::from(r#"{"resync":5}"#));
+
+            assert_eq!(output, expected);
+        }
+    }
+
+    mod session {
+        use super::{
+            super::{
+                message::{Request, Resume, Session},
+                mode::sealed::Mode,
+            },
+            Payload, STR,
+        };
+
+        #[test]
+        fn issue() {
+            let payload = Session {
+                session: String::from("abc"),
+                seq: 5,
+            };
+
+            let output = String::serialize(payload).unwrap().0;
+
+            let expected =
+                axum::extract::ws::Message::Text(String::from(r#"{"session":"abc","seq":5}"#));
+
+            assert_eq!(output, expected);
+        }
+
+        #[test]
+        fn resume() {
+            let message = String::deserialize::<Resume>(br#"{"resume":"abc","seq":5}"#).unwrap();
+
+            let expected = Resume {
+                resume: String::from("abc"),
+                seq: 5,
+            };
+            assert_eq!(message, expected);
+        }
+
+        #[test]
+        fn request_is_not_resume() {
+            let payload = format!(r#"{{"do":{STR},"id":27}}"#);
+
+            assert!(String::deserialize::<Resume>(payload.as_bytes()).is_err());
+            assert!(String::deserialize::<Request<Payload<'_>>>(payload.as_bytes()).is_ok());
+        }
+    }
+
+    mod batch {
+        use super::{
+            super::{
+                message::{Batch, BatchResponse, Error, Outcome, Request, Response},
+                mode::sealed::Mode,
+                service,
+            },
+            Payload, OBJ, STR,
+        };
+
+        fn batch() -> Batch<Payload<'static>> {
+            Batch {
+                id: 27,
+                batch: vec![
+                    Request {
+                        id: 28,
+                        payload: OBJ,
+                    },
+                    Request {
+                        id: 29,
+                        payload: OBJ,
+                    },
+                ],
+                stop_on_error: true,
+            }
+        }
+
+        #[test]
+        fn text() {
+            let payload = format!(
+                r#"{{"id":27,"batch":[{{"id":28,"do":{STR}}},{{"id":29,"do":{STR}}}],"stopOnError":true}}"#
+            );
+
+            let message = String::deserialize::<Batch<Payload<'_>>>(payload.as_bytes()).unwrap();
+
+            assert_eq!(message, batch());
+        }
+
+        #[test]
+        fn binary() {
+            let axum::extract::ws::Message::Binary(bytes) =
+                Vec::<u8>::serialize(batch()).unwrap().0
+            else {
+                panic!("Expected a binary message");
+            };
+
+            let message = Vec::<u8>::deserialize::<Batch<Payload<'_>>>(&bytes).unwrap();
+
+            assert_eq!(message, batch());
+        }
+
+        #[test]
+        fn continues_on_error_by_default() {
+            let payload = format!(r#"{{"id":27,"batch":[{{"id":28,"do":{STR}}}]}}"#);
+
+            let message = String::deserialize::<Batch<Payload<'_>>>(payload.as_bytes()).unwrap();
+
+            assert!(!message.stop_on_error);
+        }
+
+        #[test]
+        fn request_is_not_batch() {
+            let payload = format!(r#"{{"do":{STR},"id":27}}"#);
+
+            assert!(String::deserialize::<Batch<Payload<'_>>>(payload.as_bytes()).is_err());
+        }
+
+        #[test]
+        fn response() {
+            let payload = BatchResponse {
+                id: 27,
+                batch: vec![
+                    Outcome::Ok(Response { id: 28, ok: OBJ }),
+                    Outcome::Error(Error {
+                        id: Some(29),
+                        error: service::Error::new(hyper::StatusCode::FAILED_DEPENDENCY, "Skipped"),
+                    }),
+                ],
+            };
+
+            let output = String::serialize(payload).unwrap().0;
+
+            let expected = axum::extract::ws::Message::Text(format!(
+                r#"{{"id":27,"batch":[{{"id":28,"ok":{STR}}},{{"id":29,"error":{{"code":424,"message":"Skipped"}}}}]}}"#
+            ));
+
+            assert_eq!(output, expected);
+        }
+    }
+
+    mod cancel {
+        use super::{
+            super::{
+                message::{Cancel, Request},
+                mode::sealed::Mode,
+            },
+            Payload, STR,
+        };
+
+        #[test]
+        fn text() {
+            let message = String::deserialize::<Cancel>(br#"{"cancel":27}"#).unwrap();
+
+            assert_eq!(message, Cancel { cancel: 27 });
+        }
+
+        #[test]
+        fn binary() {
+            let axum::extract::ws::Message::Binary(bytes) =
+                Vec::<u8>::serialize(Cancel { cancel: 27 }).unwrap().0
+            else {
+                panic!("Expected a binary message");
+            };
+
+            let message = Vec::<u8>::deserialize::<Cancel>(&bytes).unwrap();
+
+            assert_eq!(message, Cancel { cancel: 27 });
+        }
+
+        #[test]
+        fn request_is_not_cancel() {
+            let payload = format!(r#"{{"do":{STR},"id":27}}"#);
+
+            assert!(String::deserialize::<Cancel>(payload.as_bytes()).is_err());
+            assert!(String::deserialize::<Request<Payload<'_>>>(payload.as_bytes()).is_ok());
+        }
     }
 
     mod response {
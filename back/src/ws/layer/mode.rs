@@ -1,7 +1,17 @@
 pub trait Mode: sealed::Mode + Send + Sync + 'static {}
 
+// CBOR, with the same shape as the binary mode
+#[derive(Debug)]
+pub struct Cbor;
+
+// MessagePack with the payload structs encoded as arrays, which drops the field names
+#[derive(Debug)]
+pub struct Compact;
+
 impl Mode for String {}
 impl Mode for Vec<u8> {}
+impl Mode for Cbor {}
+impl Mode for Compact {}
 
 pub(crate) mod sealed {
     pub trait Mode {
@@ -14,10 +24,9 @@ pub(crate) mod sealed {
         ) -> Result<(axum::extract::ws::Message, usize), Self::SerializeError>
         where
             T: serde::Serialize;
-        fn deserialize<'b, 'de, T>(bytes: &'b [u8]) -> Result<T, Self::DeserializeError>
+        fn deserialize<T>(bytes: &[u8]) -> Result<T, Self::DeserializeError>
         where
-            'b: 'de,
-            T: serde::Deserialize<'de>;
+            T: serde::de::DeserializeOwned;
     }
 
     impl Mode for String {
@@ -40,10 +49,9 @@ pub(crate) mod sealed {
             })
         }
 
-        fn deserialize<'b, 'de, T>(bytes: &'b [u8]) -> Result<T, Self::DeserializeError>
+        fn deserialize<T>(bytes: &[u8]) -> Result<T, Self::DeserializeError>
         where
-            'b: 'de,
-            T: serde::Deserialize<'de>,
+            T: serde::de::DeserializeOwned,
         {
             serde_json::from_slice(bytes)
         }
@@ -69,10 +77,67 @@ pub(crate) mod sealed {
             })
         }
 
-        fn deserialize<'b, 'de, T>(bytes: &'b [u8]) -> Result<T, Self::DeserializeError>
+        fn deserialize<T>(bytes: &[u8]) -> Result<T, Self::DeserializeError>
+        where
+            T: serde::de::DeserializeOwned,
+        {
+            rmp_serde::from_slice(bytes)
+        }
+    }
+
+    impl Mode for super::Cbor {
+        type SerializeError = ciborium::ser::Error<std::io::Error>;
+        type DeserializeError = ciborium::de::Error<std::io::Error>;
+
+        fn mode() -> &'static str {
+            "cbor"
+        }
+
+        fn serialize<T>(
+            payload: T,
+        ) -> Result<(axum::extract::ws::Message, usize), Self::SerializeError>
+        where
+            T: serde::Serialize,
+        {
+            let mut buffer = Vec::new();
+            ciborium::into_writer(&payload, &mut buffer).map(|()| {
+                let len = buffer.len();
+                (axum::extract::ws::Message::Binary(buffer), len)
+            })
+        }
+
+        fn deserialize<T>(bytes: &[u8]) -> Result<T, Self::DeserializeError>
+        where
+            T: serde::de::DeserializeOwned,
+        {
+            ciborium::from_reader(bytes)
+        }
+    }
+
+    impl Mode for super::Compact {
+        type SerializeError = rmp_serde::encode::Error;
+        type DeserializeError = rmp_serde::decode::Error;
+
+        fn mode() -> &'static str {
+            "compact"
+        }
+
+        fn serialize<T>(
+            payload: T,
+        ) -> Result<(axum::extract::ws::Message, usize), Self::SerializeError>
+        where
+            T: serde::Serialize,
+        {
+            rmp_serde::to_vec(&payload).map(|p| {
+                let len = p.len();
+                (axum::extract::ws::Message::Binary(p), len)
+            })
+        }
+
+        // Structs are read from either arrays or maps, so clients may keep sending named fields
+        fn deserialize<T>(bytes: &[u8]) -> Result<T, Self::DeserializeError>
         where
-            'b: 'de,
-            T: serde::Deserialize<'de>,
+            T: serde::de::DeserializeOwned,
         {
             rmp_serde::from_slice(bytes)
         }
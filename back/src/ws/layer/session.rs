@@ -0,0 +1,89 @@
+// Tokens are forgotten after this many newer sessions, and a restart forgets all of them, which
+// forces those clients into a full resync
+const CAPACITY: usize = 1024;
+
+#[derive(Debug, Clone, Default)]
+pub struct Sessions {
+    tokens: std::sync::Arc<std::sync::Mutex<Tokens>>,
+}
+
+// Every token belongs to the client it was issued to, kept in the order they were issued
+#[derive(Debug, Default)]
+struct Tokens {
+    issued: std::collections::VecDeque<String>,
+    owners: std::collections::HashMap<String, String>,
+}
+
+impl Sessions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub(super) fn issue(&self, owner: String) -> String {
+        let token = format!("{token:032x}", token = rand::random::<u128>());
+
+        let mut tokens = self.lock();
+        if tokens.issued.len() == CAPACITY {
+            if let Some(oldest) = tokens.issued.pop_front() {
+                tokens.owners.remove(&oldest);
+            }
+        }
+        tokens.issued.push_back(token.clone());
+        tokens.owners.insert(token.clone(), owner);
+
+        token
+    }
+
+    // Sessions can only be resumed by the client they were issued to
+    pub(super) fn owns(&self, token: &str, owner: &str) -> bool {
+        self.lock().owners.get(token).is_some_and(|o| o == owner)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Tokens> {
+        self.tokens
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Sessions, CAPACITY};
+
+    const OWNER: &str = "owner@email.com";
+
+    #[test]
+    fn issue() {
+        let sessions = Sessions::new();
+
+        let token = sessions.issue(String::from(OWNER));
+        assert_eq!(token.len(), 32);
+        assert_ne!(token, sessions.issue(String::from(OWNER)));
+
+        assert!(sessions.owns(&token, OWNER));
+        assert!(!sessions.owns("unknown", OWNER));
+    }
+
+    #[test]
+    fn owner() {
+        let sessions = Sessions::new();
+
+        let token = sessions.issue(String::from(OWNER));
+
+        assert!(!sessions.owns(&token, "other@email.com"));
+    }
+
+    #[test]
+    fn bounded() {
+        let sessions = Sessions::new();
+
+        let oldest = sessions.issue(String::from(OWNER));
+        let newest = (0..CAPACITY)
+            .map(|_| sessions.issue(String::from(OWNER)))
+            .last()
+            .unwrap();
+
+        assert!(!sessions.owns(&oldest, OWNER));
+        assert!(sessions.owns(&newest, OWNER));
+    }
+}
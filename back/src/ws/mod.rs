@@ -1,5 +1,5 @@
 mod layer;
 mod service;
 
-pub use layer::{Layer, Mode};
-pub use service::{Error, Service};
+pub use layer::{Cbor, Compact, Layer, Mode, Sessions, DEFLATE};
+pub use service::{Close, Error, Replay, Seq, Service};
@@ -1,14 +1,78 @@
-pub trait Service {
-    type Request: serde::de::DeserializeOwned;
-    type Response: serde::Serialize;
-    type Error: Into<Error>;
-    type Push: Clone + serde::Serialize;
+pub type Seq = u64;
 
-    fn subscribe(&self) -> tokio::sync::broadcast::Receiver<Self::Push>;
+// Every request is served by its own clone, so that requests can run concurrently
+pub trait Service: Clone + Send + 'static {
+    type Request: serde::de::DeserializeOwned + Send + 'static;
+    type Response: Send + 'static;
+    type Error: Into<Error> + Send + 'static;
+    type Push: Clone;
+    // Responses and pushes are sent in the shapes known to the client
+    type PresentedResponse: serde::Serialize + Send;
+    type PresentedPush: serde::Serialize + Send;
+
+    fn subscribe(&self) -> (Seq, tokio::sync::broadcast::Receiver<(Seq, Self::Push)>);
+    fn replay(&self, after: Seq) -> Replay<Self::Push>;
+    // Whether the client is interested in the push at all
+    fn accepts(&self, push: &Self::Push) -> bool;
+    // Whether the push changes the access of the client, ending the connection
+    fn closes(&self, push: &Self::Push) -> Option<Close>;
+    // Whether the client cannot be served at all, checked when connecting
+    fn rejects(&self) -> Option<Close>;
+    // Who is connected, so that only they can resume the sessions issued to them
+    fn owner(&self) -> String;
+    // Whether the request can be aborted midway. Writes are left to finish when cancelled, so
+    // that nothing that follows their commit is lost, and only their response is dropped
+    fn cancellable(&self, request: &Self::Request) -> bool;
+    fn present_response(&self, response: Self::Response) -> Self::PresentedResponse;
+    // Pushes the client does not know are not presented at all
+    fn present_push(&self, push: Self::Push) -> Option<Self::PresentedPush>;
     fn call(
         &mut self,
         request: Self::Request,
-    ) -> impl std::future::Future<Output = Result<Self::Response, Self::Error>>;
+    ) -> impl std::future::Future<Output = Result<Self::Response, Self::Error>> + Send;
+    // Batches that stop on error run without other requests interleaving and skip every request
+    // after the first failure. Requests that already succeeded are not undone, so services refuse
+    // the batches that a failure would leave half applied
+    fn batch(
+        &mut self,
+        requests: Vec<Self::Request>,
+        stop_on_error: bool,
+    ) -> impl std::future::Future<Output = Vec<Result<Self::Response, Self::Error>>> + Send;
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Replay<T> {
+    // Pushes sent after the requested sequence
+    Missed(Vec<(Seq, T)>),
+    // The requested sequence is no longer buffered, carries the latest sequence
+    Resync(Seq),
+}
+
+// Sent in the close frame, with codes in the range reserved for applications
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Close {
+    pub code: u16,
+    pub reason: &'static str,
+}
+
+impl Close {
+    // The access changed, so the client should connect again
+    #[must_use]
+    pub const fn reconnect(reason: &'static str) -> Self {
+        Self { code: 4001, reason }
+    }
+
+    // The client speaks an unsupported protocol, so it must be reloaded
+    #[must_use]
+    pub const fn outdated(reason: &'static str) -> Self {
+        Self { code: 4000, reason }
+    }
+
+    // The access is gone, so connecting again will be forbidden
+    #[must_use]
+    pub const fn revoked(reason: &'static str) -> Self {
+        Self { code: 4003, reason }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, serde::Serialize)]
@@ -1,12 +1,20 @@
 export const name = 'EloPong';
 
-export const version = 3;
+export const version = 4;
+
+// The protocol version is declared when connecting, so the server can adapt to it
+const withVersion = (url: string) => {
+  const versioned = new URL(url);
+  versioned.searchParams.set('version', String(version));
+  return versioned.href;
+};
 
 export const host = {
-  ws:
+  ws: withVersion(
     import.meta.env.VITE_HOST_WS === undefined
       ? `ws://${location.hostname}:3333/ws/binary`
       : (import.meta.env.VITE_HOST_WS as string),
+  ),
   check:
     import.meta.env.VITE_HOST_CHECK === undefined
       ? `http://${location.hostname}:3333/check`
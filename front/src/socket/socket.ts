@@ -9,6 +9,13 @@ type Reject = (reason?: any) => void;
 type Handler<Message> = (message: Message) => boolean;
 type RequestHandler<Message, Response> = (message: Message) => Response | undefined;
 
+// Sent by the server when the access of the user changes
+enum CloseCode {
+  Outdated = 4000,
+  Reconnect = 4001,
+  Revoked = 4003,
+}
+
 interface RequestHandlerInner<Message> {
   handle(message: Message): boolean;
   abort(disconnected: error.Disconnected): void;
@@ -108,7 +115,22 @@ export class Socket<Request, Message> {
       this.setState(state.Disconnected.Error);
     };
 
-    socket.onclose = () => {
+    socket.onclose = evt => {
+      if (evt.code === CloseCode.Outdated) {
+        window.location.reload();
+        return;
+      }
+
+      if (evt.code === CloseCode.Revoked) {
+        this.setState(state.Disconnected.Unauthorized);
+        return;
+      }
+
+      // The access changed, so connect again right away
+      if (evt.code === CloseCode.Reconnect) {
+        this.attempts = 0;
+      }
+
       if (this.state !== state.Disconnected.Error) {
         this.setState(state.Disconnected.Closed);
       }
@@ -256,6 +278,13 @@ export class Socket<Request, Message> {
     });
   }
 
+  // For messages that get no response, dropped if not connected
+  public send(message: object) {
+    if (state.isConnected(this.state)) {
+      this.socket.send(this.encoder.encode(message));
+    }
+  }
+
   public getState() {
     return this.state;
   }
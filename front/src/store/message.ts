@@ -14,6 +14,9 @@ export type Ided = {
 };
 
 export type Request = Ided & { do: 'version' | RequestPlayer | RequestGame | RequestInvite };
+export type RequestCancel = { cancel: number };
+export type RequestResume = { resume: string; seq: number };
+export type RequestBatch = Ided & { batch: Request[]; stopOnError?: boolean };
 export type RequestPlayer = { player: 'id' | 'list' | { rename: string } };
 export type RequestGame = {
   game:
@@ -26,9 +29,12 @@ export type RequestGame = {
           opponentScore: number;
           challenge: boolean;
           millis: number;
+          comment?: string;
+          venue?: string;
+          firstServer?: number;
         };
       }
-    | { update: Game }
+    | { update: { game: Game; reason?: string } }
     | { history: number };
 };
 export type RequestInvite = {
@@ -40,15 +46,24 @@ export type RequestInvite = {
     | 'reject';
 };
 
-export type Message = MessagePush | MessageOk | MessageError;
+export type Message =
+  | MessagePush
+  | MessageResync
+  | MessageSession
+  | MessageBatch
+  | MessageOk
+  | MessageError;
 export type MessageOk = { id: number; ok: Ok };
 export type MessageError = { id?: number; error: Error };
-export type MessagePush = { push: Push };
+export type MessageBatch = { id: number; batch: (MessageOk | MessageError)[] };
+export type MessagePush = { seq: number; push: Push };
+export type MessageResync = { resync: number };
+export type MessageSession = { session: string; seq: number };
 
 export type Ok = 'done' | OkResponse;
 
 export type OkResponse = {
-  version: { server: number; data: number };
+  version: { server: number; protocol: number; data: number };
   user: User;
   players: PlayerTuple[];
   games: GameTuple[];
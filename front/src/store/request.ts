@@ -15,7 +15,7 @@ export class FetchError extends Error {
 }
 
 export const preValidateMessage = (id: number, message: Message): Ok | undefined => {
-  if ('push' in message) {
+  if ('push' in message || 'resync' in message || 'session' in message || 'batch' in message) {
     return;
   }
 
@@ -19,7 +19,14 @@ import {
   type Player,
   type User,
 } from '../types';
-import { type Message, type Request, type Ided, type PushPlayer, type PushGame } from './message';
+import {
+  type Message,
+  type Request,
+  type RequestResume,
+  type Ided,
+  type PushPlayer,
+  type PushGame,
+} from './message';
 import { newRequestId, ResponseError, validateDone, validateMessage } from './request';
 import * as consts from '../consts';
 
@@ -34,6 +41,8 @@ export class Store {
   private readonly invites: Resource<Invite[]>;
 
   private dataVersion?: number;
+  // The last session given by the server, resumed on reconnection to catch up on missed pushes
+  private session?: { token: string; seq: number };
 
   public static makeSocket(
     url: string | URL,
@@ -47,18 +56,32 @@ export class Store {
     this.socket = socket;
 
     this.socket.registerStateListener(newState => {
-      if (newState === state.Connected.Open) {
+      // Resumed sessions catch up through the replayed pushes, or get a resync otherwise
+      if (newState === state.Connected.Open && this.session === undefined) {
         this.refresh();
       }
     });
 
     this.socket.registerHandler(message => {
       if ('push' in message) {
+        if (this.session !== undefined) {
+          this.session.seq = Math.max(this.session.seq, message.seq);
+        }
+
         if ('player' in message.push) {
           this.handlePushPlayer(message.push.player);
         } else if ('game' in message.push) {
           this.handlePushGame(message.push.game);
         }
+      } else if ('resync' in message) {
+        this.refresh();
+      } else if ('session' in message) {
+        const previous = this.session;
+        if (previous !== undefined) {
+          const resume: RequestResume = { resume: previous.token, seq: previous.seq };
+          this.socket.send(resume);
+        }
+        this.session = { token: message.session, seq: previous?.seq ?? message.seq };
       }
       return true;
     });
@@ -163,8 +186,8 @@ export class Store {
     return this.request({ player: { rename: name } });
   }
 
-  public editGame(game: Game) {
-    return this.request({ game: { update: game } });
+  public editGame(game: Game, reason?: string) {
+    return this.request({ game: { update: { game, reason } } });
   }
 
   public invitePlayer(name: string, email: string) {
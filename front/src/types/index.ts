@@ -36,6 +36,9 @@ export type Game = {
   readonly deleted: boolean;
   readonly millis: number;
   readonly createdMs: number;
+  readonly comment?: string;
+  readonly venue?: string;
+  readonly firstServer?: number;
 };
 
 export type GameTuple = [
@@ -51,6 +54,9 @@ export type GameTuple = [
   boolean,
   number,
   number,
+  string | null,
+  string | null,
+  number | null,
 ];
 
 export const gameFromTuple = ([
@@ -66,6 +72,9 @@ export const gameFromTuple = ([
   deleted,
   millis,
   createdMs,
+  comment,
+  venue,
+  firstServer,
 ]: GameTuple): Game => {
   return {
     id,
@@ -80,6 +89,9 @@ export const gameFromTuple = ([
     deleted,
     millis,
     createdMs,
+    comment: comment !== null ? comment : undefined,
+    venue: venue !== null ? venue : undefined,
+    firstServer: firstServer !== null ? firstServer : undefined,
   };
 };
 
@@ -114,6 +126,8 @@ export type History = {
   readonly deleted: boolean;
   readonly millis: number;
   readonly createdMs: number;
+  readonly editor?: number;
+  readonly reason?: string;
 };
 
 export type HistoryTuple = [
@@ -127,6 +141,8 @@ export type HistoryTuple = [
   boolean,
   number,
   number,
+  number | null,
+  string | null,
 ];
 
 export const historyFromTuple = ([
@@ -140,6 +156,8 @@ export const historyFromTuple = ([
   deleted,
   millis,
   createdMs,
+  editor,
+  reason,
 ]: HistoryTuple): History => {
   return {
     id,
@@ -152,6 +170,8 @@ export const historyFromTuple = ([
     deleted,
     millis,
     createdMs,
+    editor: editor !== null ? editor : undefined,
+    reason: reason !== null ? reason : undefined,
   };
 };
 